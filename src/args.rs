@@ -1,7 +1,128 @@
+use std::path::PathBuf;
+
 use structopt::StructOpt;
 
+use prickly::format::Format;
+use prickly::path::ParamPath;
+use prickly::settings::FloatFormat;
+
 #[derive(Debug, StructOpt)]
 pub struct Args {
-    /// The param file to open on startup, if any
-    pub file: Option<String>,
+    /// The param files to open on startup, if any. The first is shown, and the others are
+    /// switched to with the next and previous file keys
+    pub files: Vec<String>,
+
+    /// The param to go to once the file is open, like `fighter_param_table[12]`
+    #[structopt(long)]
+    pub at: Option<ParamPath>,
+
+    /// A second param file to show next to the first, for comparing the two
+    #[structopt(long)]
+    pub compare: Option<String>,
+
+    /// Logs what happens to prickly.log in the application directory. Repeat for more detail:
+    /// -v for opens, saves, and errors, -vv for every edit, -vvv for everything
+    #[structopt(short, long, parse(from_occurrences))]
+    pub verbose: u8,
+
+    /// Shows floats with this many decimals, instead of the fewest which are exact.
+    /// This applies to the editor and the `get` and `diff` commands
+    #[structopt(long)]
+    pub decimals: Option<usize>,
+
+    /// Shows floats in scientific notation
+    #[structopt(long)]
+    pub scientific: bool,
+
+    /// Reads the param labels from this file, instead of ParamLabels.csv or the one in the
+    /// config. Repeat it to merge the labels of several files
+    #[structopt(long, number_of_values = 1)]
+    pub labels: Vec<PathBuf>,
+
+    /// Reads the config from this file, instead of the prickly.toml in the application
+    /// or config directory
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Args {
+    pub fn float_format(&self) -> FloatFormat {
+        FloatFormat {
+            decimals: self.decimals,
+            scientific: self.scientific,
+        }
+    }
+}
+
+// Commands which run without starting the editor
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Converts between the prc, xml, and json formats, chosen by each file's extension
+    Convert {
+        /// The file to read
+        input: String,
+        /// The file to write
+        output: String,
+    },
+    /// Prints the type and value of a param, like `fighter_param_table[3].walk_speed`
+    Get {
+        /// The file to read
+        file: String,
+        /// The path of the param
+        path: String,
+    },
+    /// Changes the value of a param and saves the file
+    Set {
+        /// The file to edit
+        file: String,
+        /// The path of the param
+        path: String,
+        /// The new value, which must fit the param's current type
+        value: String,
+    },
+    /// Applies a list of edits from a script and saves the file.
+    /// Each line of the script is written `path = value` or `path,value`
+    Apply {
+        /// The file to edit
+        file: String,
+        /// The file listing the edits
+        #[structopt(long)]
+        script: String,
+    },
+    /// Writes a file to stdout, for piping into other tools
+    Dump {
+        /// The file to read
+        file: String,
+        /// The format to write: json, xml, or prc.
+        /// Defaults to the config's export_format, or json
+        #[structopt(long)]
+        format: Option<Format>,
+    },
+    /// Lists the params which don't match a schema: the types expected for keys, and the keys
+    /// structs must have. Exits with 1 when any don't match
+    Validate {
+        /// The file to check
+        file: String,
+        /// The schema to check against. Defaults to the config's schema, or Schema.toml
+        #[structopt(long)]
+        schema: Option<PathBuf>,
+    },
+    /// Lists the params added, removed, or changed between two files.
+    /// Exits with 1 when the files differ
+    Diff {
+        /// The original file
+        a: String,
+        /// The changed file
+        b: String,
+        /// Prints the differences as JSON
+        #[structopt(long)]
+        json: bool,
+        /// Compares only the structure of the files: the keys in each struct, the lengths of
+        /// lists, and the types of params. Prints a tree of where they differ
+        #[structopt(long, conflicts_with = "json")]
+        structure: bool,
+    },
 }
@@ -0,0 +1,25 @@
+use std::env::current_exe;
+use std::fs::{read, write};
+use std::io::Error;
+use std::path::PathBuf;
+
+pub const BOOKMARKS_FILE: &str = "Bookmarks.json";
+
+/// The bookmarks are kept in the application directory, so they're shared between working directories
+fn bookmarks_path() -> Result<PathBuf, Error> {
+    let exe = current_exe()?;
+    Ok(exe.parent().unwrap().join(BOOKMARKS_FILE))
+}
+
+/// Reads the bookmarked folders. Without a bookmarks file there are none
+pub fn load_bookmarks() -> Vec<PathBuf> {
+    bookmarks_path()
+        .and_then(read)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_bookmarks(bookmarks: &[PathBuf]) -> Result<(), Error> {
+    write(bookmarks_path()?, serde_json::to_vec_pretty(bookmarks)?)
+}
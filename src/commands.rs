@@ -0,0 +1,189 @@
+use std::fs::read_to_string;
+use std::io::{stdout, Write};
+use std::path::Path;
+
+use prc::{ParamKind, ParamStruct};
+use serde_json::json;
+
+use crate::args::Command;
+use prickly::diff::{diff, diff_structure, Change, Difference, ShapeChange, ShapeNode};
+use prickly::error::AppError;
+use prickly::format::{open, save, to_bytes, Format};
+use prickly::path::ParamPath;
+use prickly::schema::load_schema;
+use prickly::script::{run_statement, Statement};
+use prickly::settings::FloatFormat;
+use prickly::value::{format_value, param_type, parse_value};
+
+/// Runs a command without starting the editor, returning the exit code.
+/// `dump` writes in the export format when none is given, and `validate` uses the config's
+/// schema when none is given
+pub fn run(
+    command: Command,
+    floats: FloatFormat,
+    export_format: Option<Format>,
+    schema: Option<&Path>,
+) -> Result<i32, AppError> {
+    match command {
+        Command::Convert { input, output } => {
+            let param = open(&input).map_err(AppError::Io)?;
+            save(&output, &param).map_err(AppError::Io)?;
+        }
+        Command::Dump { file, format } => {
+            let param = open(&file).map_err(AppError::Io)?;
+            let format = format.or(export_format).unwrap_or(Format::Json);
+            let bytes = to_bytes(&param, format).map_err(AppError::Io)?;
+            stdout().write_all(&bytes).map_err(AppError::Io)?;
+        }
+        Command::Get { file, path } => {
+            let root = open(&file).map_err(AppError::Io)?;
+            let path = parse_path(&path)?;
+            let param = path.get(&root).ok_or_else(|| not_found(&path))?;
+            println!("{}", describe(param, floats));
+        }
+        Command::Set { file, path, value } => {
+            let mut root = open(&file).map_err(AppError::Io)?;
+            let path = parse_path(&path)?;
+            let param = path.get_mut(&mut root).ok_or_else(|| not_found(&path))?;
+            *param = parse_value(param, &value).map_err(AppError::InvalidArgument)?;
+            save(&file, &root).map_err(AppError::Io)?;
+        }
+        Command::Apply { file, script } => {
+            let mut root = open(&file).map_err(AppError::Io)?;
+            let script = read_to_string(&script).map_err(AppError::Io)?;
+            let count = apply_script(&mut root, &script)?;
+            save(&file, &root).map_err(AppError::Io)?;
+            println!("applied {} edits", count);
+        }
+        Command::Validate { file, schema: path } => {
+            let root = open(&file).map_err(AppError::Io)?;
+            let schema = load_schema(path.as_deref().or(schema))
+                .map_err(AppError::InvalidArgument)?
+                .ok_or_else(|| {
+                    AppError::InvalidArgument(String::from(
+                        "no schema found: pass --schema or add a Schema.toml",
+                    ))
+                })?;
+            let violations = schema.validate(&root);
+            for violation in &violations {
+                println!("{} {}", violation.path, violation.message);
+            }
+            return Ok(if violations.is_empty() { 0 } else { 1 });
+        }
+        Command::Diff {
+            a,
+            b,
+            json,
+            structure,
+        } => {
+            let a = open(&a).map_err(AppError::Io)?;
+            let b = open(&b).map_err(AppError::Io)?;
+            if structure {
+                let nodes = diff_structure(&a, &b);
+                print_structure(&nodes, 0);
+                return Ok(if nodes.is_empty() { 0 } else { 1 });
+            }
+            let differences = diff(&a, &b);
+            if json {
+                print_diff_json(&differences);
+            } else {
+                print_diff(&differences, floats);
+            }
+            return Ok(if differences.is_empty() { 0 } else { 1 });
+        }
+    }
+    Ok(0)
+}
+
+fn parse_path(path: &str) -> Result<ParamPath, AppError> {
+    path.parse().map_err(AppError::InvalidArgument)
+}
+
+fn not_found(path: &ParamPath) -> AppError {
+    AppError::InvalidArgument(format!("no param found at {}", path))
+}
+
+/// Applies every edit in the script, skipping blank lines and `#` comments.
+/// Returns the number of edits applied
+fn apply_script(root: &mut ParamStruct, script: &str) -> Result<usize, AppError> {
+    let mut count = 0;
+    for (number, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_error =
+            |msg: String| AppError::InvalidArgument(format!("line {}: {}", number + 1, msg));
+        let statement = line.parse::<Statement>().map_err(line_error)?;
+        count += run_statement(root, &statement).map_err(line_error)?.len();
+    }
+    Ok(count)
+}
+
+fn describe(param: &ParamKind, floats: FloatFormat) -> String {
+    let value = match param {
+        ParamKind::Float(float) => floats.format(*float),
+        _ => format_value(param),
+    };
+    format!("{} {}", param_type(param), value)
+}
+
+/// Prints one line per difference, marked with `+` (added), `-` (removed), or `~` (changed)
+fn print_diff(differences: &[Difference], floats: FloatFormat) {
+    for Difference { path, change } in differences {
+        match change {
+            Change::Added(new) => println!("+ {} = {}", path, describe(new, floats)),
+            Change::Removed(old) => println!("- {} = {}", path, describe(old, floats)),
+            Change::Changed(old, new) => {
+                println!(
+                    "~ {} = {} -> {}",
+                    path,
+                    describe(old, floats),
+                    describe(new, floats)
+                )
+            }
+        }
+    }
+}
+
+/// Prints the structural differences as a tree, indenting each param below its parent
+fn print_structure(nodes: &[ShapeNode], depth: usize) {
+    for node in nodes {
+        let name = node.path.segment_strings().pop().unwrap_or_default();
+        let change = match &node.change {
+            Some(ShapeChange::Added(ty)) => format!("  + {}", ty),
+            Some(ShapeChange::Removed(ty)) => format!("  - {}", ty),
+            Some(ShapeChange::Retyped(old, new)) => format!("  ~ {} -> {}", old, new),
+            Some(ShapeChange::Resized(old, new)) => format!("  # {} -> {} entries", old, new),
+            None => String::new(),
+        };
+        println!(
+            "{}{}{}",
+            "  ".repeat(depth),
+            name.trim_start_matches('.'),
+            change
+        );
+        print_structure(&node.children, depth + 1);
+    }
+}
+
+/// Prints the differences as a JSON array, with params in the same form as JSON param files
+fn print_diff_json(differences: &[Difference]) {
+    let entries = differences
+        .iter()
+        .map(|Difference { path, change }| {
+            let (kind, old, new) = match change {
+                Change::Added(new) => ("added", None, Some(new)),
+                Change::Removed(old) => ("removed", Some(old), None),
+                Change::Changed(old, new) => ("changed", Some(old), Some(new)),
+            };
+            json!({
+                "change": kind,
+                "path": path.to_string(),
+                "old": old,
+                "new": new,
+            })
+        })
+        .collect::<Vec<_>>();
+    println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+}
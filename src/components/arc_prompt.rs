@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use tui_components::components::{Input, InputResponse};
+use tui_components::crossterm::event::KeyCode;
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::Span;
+use tui_components::tui::widgets::{Block, Borders, Clear, Widget};
+use tui_components::{Component, Event};
+
+use super::explorer::complete_path;
+use crate::theme::Theme;
+
+/// The widest the prompt gets
+const PROMPT_WIDTH: u16 = 80;
+
+/// Asks for the path of a file in the game's archive, like `fighter/mario/param/vl.prc`, and
+/// finds it in the folder the game's files were extracted to
+#[derive(Debug)]
+pub struct ArcPrompt {
+    input: Input,
+    /// The folder the game's files were extracted to
+    root: PathBuf,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub enum ArcPromptResponse {
+    None,
+    Handled,
+    /// The typed path is a file in the extracted folder
+    Open(PathBuf),
+    Cancel,
+}
+
+impl ArcPrompt {
+    pub fn new(root: PathBuf, theme: Theme) -> Self {
+        let mut input = Input::default()
+            .editing_style(Style::default().bg(theme.highlight))
+            .error_style(Style::default().fg(theme.error));
+        input.focused = true;
+        Self { input, root, theme }
+    }
+
+    /// Where the typed path is in the extracted folder. Paths in the archive are relative to
+    /// its root, even when they start with a slash
+    fn path(&self) -> PathBuf {
+        self.root
+            .join(self.input.value.trim().trim_start_matches(['/', '\\']))
+    }
+}
+
+impl Component for ArcPrompt {
+    type Response = ArcPromptResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Key(key) = event {
+            if key.code == KeyCode::Tab {
+                if let Some(completed) = complete_path(&self.root, &self.input.value) {
+                    self.input.value = completed;
+                }
+                self.input.error = None;
+                return ArcPromptResponse::Handled;
+            }
+        }
+        match self.input.handle_event(event) {
+            InputResponse::Submit => {
+                let path = self.path();
+                if path.is_file() {
+                    ArcPromptResponse::Open(path)
+                } else {
+                    self.input.error =
+                        Some(format!("There's no such file in {}", self.root.display()));
+                    ArcPromptResponse::Handled
+                }
+            }
+            InputResponse::Cancel => ArcPromptResponse::Cancel,
+            InputResponse::Edited { .. } => {
+                self.input.error = None;
+                ArcPromptResponse::Handled
+            }
+            InputResponse::None => ArcPromptResponse::None,
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Open from {}", self.root.display()),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width: PROMPT_WIDTH.min(rect.width),
+            height: 3,
+        });
+        let inner = block.inner(area);
+        Widget::render(Clear, area, buffer);
+        Widget::render(block, area, buffer);
+        self.input.draw(inner, buffer);
+    }
+}
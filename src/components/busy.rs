@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event};
+
+use crate::task::Progress;
+use crate::theme::Theme;
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+/// How long each frame of the spinner is shown, and so how often anything waiting is redrawn
+pub const SPINNER_FRAME: Duration = Duration::from_millis(100);
+
+/// The frame of the spinner for something which started at the given time
+pub fn spinner(started: Instant) -> char {
+    let frame = started.elapsed().as_millis() / SPINNER_FRAME.as_millis();
+    SPINNER[frame as usize % SPINNER.len()]
+}
+
+/// How far the work has got, like ` 40%`, or nothing before its size is known
+pub fn percent(progress: &Progress) -> String {
+    progress
+        .fraction()
+        .map(|fraction| format!(" {:.0}%", fraction * 100.0))
+        .unwrap_or_default()
+}
+
+/// A popup with a spinner, shown while the app waits on a background task
+#[derive(Debug)]
+pub struct Busy {
+    message: String,
+    started: Instant,
+    /// How far the task has got, if it reports that
+    progress: Option<Progress>,
+    theme: Theme,
+}
+
+impl Busy {
+    pub fn new(message: String, theme: Theme) -> Self {
+        Self {
+            message,
+            started: Instant::now(),
+            progress: None,
+            theme,
+        }
+    }
+
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+}
+
+impl Component for Busy {
+    type Response = ();
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, _event: Event) -> Self::Response {}
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) -> Self::DrawResponse {
+        let progress = self.progress.as_ref().map(percent).unwrap_or_default();
+        let text = format!("{} {}{}", spinner(self.started), self.message, progress);
+        let area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width: text.chars().count() as u16 + 4,
+            height: 3,
+        });
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(area);
+        Widget::render(Clear, area, buffer);
+        Widget::render(block, area, buffer);
+        Widget::render(Paragraph::new(text), inner, buffer);
+    }
+}
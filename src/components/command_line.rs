@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex};
+
+use tui_components::components::{Input, InputResponse};
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Clear, Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::history::{HistoryField, InputHistory, Recall};
+use crate::theme::Theme;
+use crate::utils::vim::VimCommand;
+
+/// A line at the bottom for typing a command after `:`, like in vim. Up/Down step through the
+/// commands run before
+#[derive(Debug)]
+pub struct CommandLine {
+    input: Input,
+    history: Arc<Mutex<InputHistory>>,
+    recall: Recall,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub enum CommandLineResponse {
+    None,
+    Handled,
+    Run(VimCommand),
+    Cancel,
+}
+
+impl CommandLine {
+    pub fn new(history: Arc<Mutex<InputHistory>>, theme: Theme) -> Self {
+        let mut input = Input::default()
+            .editing_style(Style::default().bg(theme.highlight))
+            .error_style(Style::default().fg(theme.error));
+        input.focused = true;
+        Self {
+            input,
+            history,
+            recall: Recall::default(),
+            theme,
+        }
+    }
+
+    fn recall(&mut self, back: bool) {
+        let history = match self.history.lock() {
+            Ok(history) => history,
+            Err(err) => err.into_inner(),
+        };
+        let text = if back {
+            self.recall
+                .older(&history, HistoryField::Command, &self.input.value)
+        } else {
+            self.recall.newer(&history, HistoryField::Command)
+        };
+        if let Some(text) = text {
+            self.input.value = text;
+            self.input.error = None;
+        }
+    }
+}
+
+impl Component for CommandLine {
+    type Response = CommandLineResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Up => {
+                    self.recall(true);
+                    return CommandLineResponse::Handled;
+                }
+                KeyCode::Down => {
+                    self.recall(false);
+                    return CommandLineResponse::Handled;
+                }
+                // like in vim, erasing the colon leaves the command line
+                KeyCode::Backspace if self.input.value.is_empty() => {
+                    return CommandLineResponse::Cancel
+                }
+                _ => {}
+            }
+        }
+        match self.input.handle_event(event) {
+            InputResponse::Submit => match self.input.value.parse() {
+                Ok(command) => {
+                    if let Ok(mut history) = self.history.lock() {
+                        history.push(HistoryField::Command, self.input.value.trim().to_owned());
+                    }
+                    CommandLineResponse::Run(command)
+                }
+                Err(err) => {
+                    self.input.error = Some(err);
+                    CommandLineResponse::Handled
+                }
+            },
+            InputResponse::Cancel => CommandLineResponse::Cancel,
+            InputResponse::Edited { .. } => {
+                self.input.error = None;
+                CommandLineResponse::Handled
+            }
+            InputResponse::None => CommandLineResponse::None,
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        // the colon takes the place of the input's own prompt
+        let mut spans = vec![Span::styled(":", Style::default().fg(self.theme.accent))];
+        spans.extend(self.input.get_spans().0.into_iter().skip(1));
+        Widget::render(Clear, rect, buffer);
+        Widget::render(Paragraph::new(Spans::from(spans)), rect, buffer);
+    }
+}
@@ -0,0 +1,62 @@
+use tui_components::components::Confirm as PlainConfirm;
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Color;
+use tui_components::{Component, Event};
+
+pub use tui_components::components::ConfirmResponse;
+
+use crate::theme::Theme;
+
+/// The width of "Yes / No" and the popup's borders
+const CHOICES_WIDTH: usize = 10;
+
+/// tui_components' yes or no question, answered with the arrow keys and Enter,
+/// drawn in the theme's colors instead of its own
+#[derive(Debug, Clone)]
+pub struct Confirm {
+    confirm: PlainConfirm,
+    /// The size of the popup, worked out the same way tui_components does
+    width: u16,
+    theme: Theme,
+}
+
+impl Confirm {
+    pub fn new<T: Into<String>>(title: T, theme: Theme) -> Self {
+        let title = title.into();
+        Self {
+            width: CHOICES_WIDTH.max(title.len() + 2) as u16,
+            confirm: PlainConfirm::new(title),
+            theme,
+        }
+    }
+}
+
+impl Component for Confirm {
+    type Response = ConfirmResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        self.confirm.handle_event(event)
+    }
+
+    fn draw(&mut self, rect: Rect, buf: &mut Buffer) {
+        self.confirm.draw(rect, buf);
+        let area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: 3,
+        });
+        self.theme.recolor(
+            buf,
+            area,
+            &[
+                (Color::White, self.theme.title),
+                (Color::Yellow, self.theme.accent),
+                (Color::Green, self.theme.success),
+            ],
+        );
+    }
+}
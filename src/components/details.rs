@@ -0,0 +1,91 @@
+use prc::hash40::Hash40;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
+use tui_components::{Component, Event};
+
+use crate::path::ParamPath;
+use crate::settings::HashDisplay;
+use crate::theme::Theme;
+
+use super::param::{ParamDetails, ParentIndex};
+
+/// A side panel showing everything about the selected param, including anything
+/// cut off in the table
+#[derive(Debug)]
+pub struct Details {
+    pub path: ParamPath,
+    pub details: Option<ParamDetails>,
+    /// The note attached to the param, if it has one
+    pub note: Option<String>,
+    pub hashes: HashDisplay,
+    pub theme: Theme,
+}
+
+impl Details {
+    fn line(&self, name: &'static str, value: String) -> Spans<'static> {
+        Spans::from(vec![
+            Span::styled(format!("{}: ", name), Style::default().fg(self.theme.hint)),
+            Span::raw(value),
+        ])
+    }
+}
+
+fn hash_numbers(hash: Hash40) -> String {
+    format!("0x{:010x} ({})", hash.0, hash.0)
+}
+
+impl Component for Details {
+    type Response = ();
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, _event: Event) -> Self::Response {}
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                "Details",
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.inactive));
+
+        let mut lines = vec![];
+        if let Some(details) = &self.details {
+            lines.push(self.line("Path", self.path.to_string()));
+            lines.push(self.line("Type", details.ty.to_owned()));
+            match details.key {
+                ParentIndex::List(index) => lines.push(self.line("Index", index.to_string())),
+                ParentIndex::Struct(hash) => {
+                    lines.push(self.line("Key", self.hashes.format(hash)));
+                    lines.push(self.line("Key hash", hash_numbers(hash)));
+                }
+            }
+            if let Some(value) = &details.value {
+                lines.push(self.line("Value", value.clone()));
+            }
+            if let Some(hash) = details.value_hash {
+                lines.push(self.line("Value hash", hash_numbers(hash)));
+            }
+            if let Some(children) = details.children {
+                lines.push(self.line("Children", children.to_string()));
+            }
+            if let Some(note) = &self.note {
+                lines.push(self.line("Note", note.clone()));
+            }
+        } else {
+            lines.push(Spans::from(Span::styled(
+                "Nothing selected",
+                Style::default().fg(self.theme.hint),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        Widget::render(Clear, rect, buffer);
+        Widget::render(paragraph, rect, buffer);
+    }
+}
@@ -2,14 +2,18 @@ use tui_components::rect_ext::RectExt;
 use tui_components::tui::layout::Rect;
 use tui_components::tui::text::Text;
 use tui_components::tui::widgets::Widget;
-use tui_components::tui::{
-    layout::Alignment,
-    style::{Color, Style},
-    widgets::Paragraph,
-};
+use tui_components::tui::{layout::Alignment, style::Style, widgets::Paragraph};
 use tui_components::Component;
 
-pub struct Empty;
+use crate::keys::Key;
+use crate::theme::Theme;
+
+/// The message shown while no file is open
+pub struct Empty {
+    /// The key for opening a file
+    pub open: Key,
+    pub theme: Theme,
+}
 
 impl Component for Empty {
     type Response = ();
@@ -24,8 +28,8 @@ impl Component for Empty {
     ) -> Self::DrawResponse {
         let mut message = Text::raw("No params loaded. Press\n");
         message.extend(Text::styled(
-            "ctrl + o\n",
-            Style::default().fg(Color::Green),
+            format!("{}\n", self.open),
+            Style::default().fg(self.theme.explorer),
         ));
         message.extend(Text::raw("to open a file"));
 
@@ -0,0 +1,138 @@
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{
+    Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget,
+};
+use tui_components::{Component, Event, Spannable};
+
+use crate::theme::Theme;
+use crate::utils::modulo::{add_mod, sub_mod};
+
+/// The most names listed at once below the value
+const PICKER_ROWS: usize = 8;
+
+/// Picks an integer's value from the names its key's values have, instead of typing a number
+#[derive(Debug)]
+pub struct EnumPicker {
+    options: Vec<(i64, String)>,
+    selected: usize,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum EnumPickerResponse {
+    None,
+    Handled,
+    Submit,
+    Cancel,
+}
+
+impl EnumPicker {
+    /// Starts with the value selected, or the first name if the value has none
+    pub fn new(value: i64, options: Vec<(i64, String)>, theme: Theme) -> Self {
+        let selected = options
+            .iter()
+            .position(|(option, _)| *option == value)
+            .unwrap_or_default();
+        Self {
+            options,
+            selected,
+            theme,
+        }
+    }
+
+    pub fn value(&self) -> i64 {
+        self.options
+            .get(self.selected)
+            .map(|(value, _)| *value)
+            .unwrap_or_default()
+    }
+
+    /// How many rows the list of names takes, including its borders
+    pub fn options_height(&self) -> u16 {
+        self.options.len().min(PICKER_ROWS) as u16 + 2
+    }
+
+    /// The width the list of names would like, to fit the longest one
+    pub fn options_width(&self) -> u16 {
+        let longest = self
+            .options
+            .iter()
+            .map(|option| option_label(option).len())
+            .max()
+            .unwrap_or(0);
+        (longest + 2) as u16
+    }
+
+    /// Draws every name with the one being picked selected
+    pub fn draw_options(&self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let items = self
+            .options
+            .iter()
+            .map(|option| ListItem::new(option_label(option)))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(self.theme.highlight));
+        let mut state = ListState::default();
+        state.select(Some(self.selected));
+        Widget::render(Clear, rect, buffer);
+        StatefulWidget::render(list, rect, buffer, &mut state);
+    }
+}
+
+/// A name followed by its value, like `fighter (3)`
+fn option_label((value, name): &(i64, String)) -> String {
+    format!("{} ({})", name, value)
+}
+
+impl Component for EnumPicker {
+    type Response = EnumPickerResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return EnumPickerResponse::None,
+        };
+        let len = self.options.len();
+        match key.code {
+            KeyCode::Up if len > 0 => self.selected = sub_mod(self.selected, 1, len),
+            KeyCode::Down if len > 0 => self.selected = add_mod(self.selected, 1, len),
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(PICKER_ROWS),
+            KeyCode::PageDown => {
+                self.selected = (self.selected + PICKER_ROWS).min(len.saturating_sub(1))
+            }
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = len.saturating_sub(1),
+            KeyCode::Enter if len > 0 => return EnumPickerResponse::Submit,
+            KeyCode::Esc => return EnumPickerResponse::Cancel,
+            _ => return EnumPickerResponse::None,
+        }
+        EnumPickerResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        Widget::render(Paragraph::new(self.get_spans()), rect, buffer);
+    }
+}
+
+impl Spannable for EnumPicker {
+    fn get_spans<'b>(&self) -> Spans<'b> {
+        let label = self
+            .options
+            .get(self.selected)
+            .map(option_label)
+            .unwrap_or_default();
+        Spans::from(vec![
+            Span::styled("> ", Style::default().fg(self.theme.hint)),
+            Span::styled(label, Style::default().fg(self.theme.success)),
+        ])
+    }
+}
@@ -0,0 +1,114 @@
+use tui_components::crossterm::event::KeyCode;
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Alignment, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
+use tui_components::{Component, Event};
+
+use crate::theme::Theme;
+
+/// The widest the popup gets before wrapping the message
+const MAX_WIDTH: u16 = 60;
+
+/// A message about something which failed, shown until the user acknowledges it
+#[derive(Debug, Clone)]
+pub struct ErrorPopup {
+    title: String,
+    message: String,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorPopupResponse {
+    Dismiss,
+    None,
+}
+
+impl ErrorPopup {
+    pub fn new<T: Into<String>, M: Into<String>>(title: T, message: M, theme: Theme) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            theme,
+        }
+    }
+}
+
+/// How many lines the text takes when wrapped between words to the given width
+fn wrapped_height(text: &str, width: usize) -> u16 {
+    let mut lines = 1;
+    let mut line = 0;
+    for word in text.split_whitespace() {
+        let len = word.chars().count();
+        if line > 0 && line + 1 + len > width {
+            lines += 1;
+            line = 0;
+        }
+        if line > 0 {
+            line += 1;
+        }
+        // words longer than the line are broken across several
+        lines += len.saturating_sub(1) / width;
+        line += len % width;
+    }
+    lines as u16
+}
+
+impl Component for ErrorPopup {
+    type Response = ErrorPopupResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Enter | KeyCode::Esc | KeyCode::Backspace => ErrorPopupResponse::Dismiss,
+                _ => ErrorPopupResponse::None,
+            }
+        } else {
+            ErrorPopupResponse::None
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                &self.title,
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.error));
+
+        let hint = "Press Enter to continue";
+        let text_width = self.message.chars().count().max(hint.len()) as u16;
+        let width = (text_width.max(self.title.len() as u16) + 2)
+            .min(MAX_WIDTH)
+            .min(rect.width);
+        // the message wraps within the borders, and is followed by a blank line and the hint
+        let inner_width = width.saturating_sub(2).max(1);
+        let message_height = wrapped_height(&self.message, inner_width as usize);
+        let height = (message_height + 4).min(rect.height);
+
+        let lines = vec![
+            Spans::from(self.message.as_str()),
+            Spans::default(),
+            Spans::from(Span::styled(hint, Style::default().fg(self.theme.hint))),
+        ];
+        let p = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+
+        let block_area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        });
+        let block_inner = block.inner(block_area);
+
+        Widget::render(Clear, block_area, buf);
+        Widget::render(block, block_area, buf);
+        Widget::render(p, block_inner, buf);
+    }
+}
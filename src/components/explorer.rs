@@ -0,0 +1,914 @@
+use std::cmp::Ordering;
+use std::env::var_os;
+use std::fs::{canonicalize, create_dir, read_dir, Metadata};
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use tui_components::components::{Input, InputResponse};
+use tui_components::crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Direction, Layout, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::Span;
+use tui_components::tui::widgets::{
+    Block, Borders, Clear, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+};
+use tui_components::{Component, Event};
+
+use crate::bookmarks::{load_bookmarks, save_bookmarks};
+use crate::config::ExplorerConfig;
+use crate::format::is_param_file;
+use crate::theme::Theme;
+use crate::utils::format_size::format_size;
+use crate::utils::mouse::{ClickTracker, SCROLL_ROWS};
+use crate::utils::paste::{clipboard_text, is_paste, paste_events};
+
+use super::confirm::{Confirm, ConfirmResponse};
+
+const SIZE_WIDTH: u16 = 10;
+const MODIFIED_WIDTH: u16 = 16;
+/// The narrowest the list can be while still showing each file's size and modified date
+const MIN_DETAILS_WIDTH: u16 = 60;
+
+/// A file browser for choosing a file to open, or where to save one
+#[derive(Debug, Clone)]
+pub struct Explorer {
+    path: PathBuf,
+    input: Input,
+    input_active: bool,
+    files: Result<Vec<EntryInfo>, String>,
+    mode: ExplorerMode,
+    /// Whether every file is listed, instead of only the ones with a param file extension
+    show_all: bool,
+    sort: ExplorerSort,
+    /// Whether the files are listed from the last to the first in the sort order
+    descending: bool,
+    /// Folders pinned by the user, which can be jumped to from any folder
+    bookmarks: Vec<PathBuf>,
+    /// The selection in the list of bookmarks, while it's shown
+    bookmark_list: Option<TableState>,
+    /// The name of a folder to create in the current one, while it's being typed
+    new_folder: Option<Input>,
+    /// A path to go straight to, while it's being typed
+    path_input: Option<Input>,
+    /// used to confirm if the user wants to overwrite an existing file
+    confirm_overwrite: Option<(Confirm, PathBuf)>,
+    table_state: TableState,
+    /// What the explorer is shown as, instead of opening or saving a file
+    title: Option<&'static str>,
+    /// The first row shown in the list
+    offset: usize,
+    /// Where the rows were last drawn on screen
+    area: Rect,
+    clicks: ClickTracker,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExplorerMode {
+    Open,
+    Save,
+}
+
+/// What the files are listed in order of
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExplorerSort {
+    #[default]
+    Name,
+    #[serde(alias = "date")]
+    Modified,
+    Size,
+}
+
+impl ExplorerSort {
+    pub fn next(self) -> Self {
+        match self {
+            ExplorerSort::Name => ExplorerSort::Modified,
+            ExplorerSort::Modified => ExplorerSort::Size,
+            ExplorerSort::Size => ExplorerSort::Name,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExplorerSort::Name => "name",
+            ExplorerSort::Modified => "date",
+            ExplorerSort::Size => "size",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EntryInfo {
+    path: PathBuf,
+    meta: Metadata,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExplorerResponse {
+    Open(PathBuf),
+    Save(PathBuf),
+    Cancel,
+    Handled,
+    None,
+}
+
+impl Explorer {
+    pub fn new<P: AsRef<Path>>(path: P, mode: ExplorerMode, theme: Theme) -> Self {
+        let files = Self::get_files(&path, false);
+        let mut table_state = TableState::default();
+        table_state.select(Some(0));
+        Explorer {
+            path: path.as_ref().to_path_buf(),
+            input: Input::default().editing_style(Style::default().bg(theme.highlight)),
+            input_active: false,
+            files,
+            mode,
+            show_all: false,
+            sort: ExplorerSort::Name,
+            descending: false,
+            bookmarks: load_bookmarks(),
+            bookmark_list: None,
+            new_folder: None,
+            path_input: None,
+            confirm_overwrite: None,
+            table_state,
+            title: None,
+            offset: 0,
+            area: Rect::default(),
+            clicks: ClickTracker::default(),
+            theme,
+        }
+    }
+
+    /// Starts with the file filter and sort order from the config
+    pub fn with_config(mut self, config: ExplorerConfig) -> Self {
+        self.show_all = config.show_all;
+        self.sort = config.sort;
+        self.descending = config.descending;
+        let path = self.path.clone();
+        self.set_path(path);
+        self
+    }
+
+    /// Shows the explorer as picking a file for something other than opening or saving the file
+    pub fn with_title(mut self, title: &'static str) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    /// Lists the folders and files in the path. Unless show_all is set,
+    /// only files with a param file extension are listed
+    fn get_files<P: AsRef<Path>>(path: P, show_all: bool) -> Result<Vec<EntryInfo>, String> {
+        read_dir(path).map_err(|e| format!("{}", e)).map(|dir| {
+            let mut entries: Vec<EntryInfo> = dir
+                .filter_map(|sub| {
+                    let sub = sub.ok()?;
+                    Some(EntryInfo {
+                        path: sub.path(),
+                        meta: sub.metadata().ok()?,
+                    })
+                })
+                .filter(|entry| show_all || entry.meta.is_dir() || is_param_file(&entry.path))
+                .collect();
+            entries.sort_unstable_by_key(|e| e.path.file_name().unwrap().to_owned());
+            entries
+        })
+    }
+
+    /// Puts the files in the sort order, keeping the same file selected
+    fn sort_files(&mut self) {
+        let selected = self.selected_path().map(|entry| entry.path.clone());
+        let (sort, descending) = (self.sort, self.descending);
+        if let Ok(files) = &mut self.files {
+            files.sort_by(|a, b| {
+                let size = |entry: &EntryInfo| {
+                    if entry.meta.is_dir() {
+                        0
+                    } else {
+                        entry.meta.len()
+                    }
+                };
+                let order = match sort {
+                    ExplorerSort::Name => Ordering::Equal,
+                    ExplorerSort::Modified => a.meta.modified().ok().cmp(&b.meta.modified().ok()),
+                    ExplorerSort::Size => size(a).cmp(&size(b)),
+                }
+                .then_with(|| a.path.file_name().cmp(&b.path.file_name()));
+                if descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+            let index = selected.and_then(|path| files.iter().position(|f| f.path == path));
+            self.table_state.select(Some(index.unwrap_or(0)));
+        }
+    }
+
+    fn set_path<P: AsRef<Path>>(&mut self, path: P) {
+        self.files = Self::get_files(&path, self.show_all);
+        self.sort_files();
+        self.path = path.as_ref().to_path_buf();
+        self.table_state.select(Some(0));
+        self.offset = 0;
+    }
+
+    fn index(&self) -> usize {
+        self.table_state.selected().unwrap()
+    }
+
+    fn increment(&mut self) {
+        if let Ok(paths) = &self.files {
+            let new = if self.index() >= paths.len().saturating_sub(1) {
+                0
+            } else {
+                self.index() + 1
+            };
+            self.table_state.select(Some(new));
+        }
+    }
+
+    fn decrement(&mut self) {
+        if let Ok(paths) = &self.files {
+            let new = if self.index() == 0 {
+                paths.len().saturating_sub(1)
+            } else {
+                self.index() - 1
+            };
+            self.table_state.select(Some(new));
+        }
+    }
+
+    /// Selects the file at the index, or the last file if the index is past the end
+    fn select_clamped(&mut self, index: usize) {
+        if let Ok(paths) = &self.files {
+            let last = paths.len().saturating_sub(1);
+            self.table_state.select(Some(index.min(last)));
+        }
+    }
+
+    /// How many files fit in the list at once
+    fn page(&self) -> usize {
+        (self.area.height as usize).max(1)
+    }
+
+    fn selected_path(&self) -> Option<&EntryInfo> {
+        let index = self.index();
+        self.files.as_ref().ok().and_then(|files| files.get(index))
+    }
+
+    fn confirm_overwrite(&mut self, path: PathBuf) {
+        self.confirm_overwrite = Some((Confirm::new("Overwrite file?", self.theme), path));
+    }
+
+    /// Enters the selected folder, or picks the selected file
+    fn enter(&mut self) -> ExplorerResponse {
+        let info = self
+            .selected_path()
+            .map(|entry| (entry.path.clone(), entry.meta.is_dir()));
+        if let Some((path, is_dir)) = info {
+            if is_dir {
+                self.set_path(path);
+            } else {
+                match self.mode {
+                    ExplorerMode::Open => return ExplorerResponse::Open(path),
+                    ExplorerMode::Save => self.confirm_overwrite(path),
+                }
+            }
+        }
+        ExplorerResponse::Handled
+    }
+
+    /// Selects the clicked file, and enters or picks it on a double click.
+    /// The scroll wheel moves the selection a few files at a time
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> ExplorerResponse {
+        let area = self.area;
+        let inside = mouse.column >= area.x
+            && mouse.column < area.x + area.width
+            && mouse.row >= area.y
+            && mouse.row < area.y + area.height;
+        let len = self.files.as_ref().map_or(0, |files| files.len());
+        if !inside || len == 0 {
+            return ExplorerResponse::None;
+        }
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let index = self.offset + (mouse.row - area.y) as usize;
+                if index >= len {
+                    return ExplorerResponse::Handled;
+                }
+                self.table_state.select(Some(index));
+                if self.clicks.click(mouse.column, mouse.row) {
+                    return self.enter();
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                let index = self.index().saturating_sub(SCROLL_ROWS);
+                self.table_state.select(Some(index));
+            }
+            MouseEventKind::ScrollDown => {
+                let index = (self.index() + SCROLL_ROWS).min(len - 1);
+                self.table_state.select(Some(index));
+            }
+            _ => return ExplorerResponse::None,
+        }
+        ExplorerResponse::Handled
+    }
+
+    /// The current folder, as it's written in the bookmarks
+    fn bookmark_path(&self) -> PathBuf {
+        canonicalize(&self.path).unwrap_or_else(|_| self.path.clone())
+    }
+
+    /// Pins the current folder, or unpins it if it's already pinned
+    fn toggle_bookmark(&mut self) {
+        let path = self.bookmark_path();
+        match self.bookmarks.iter().position(|bookmark| *bookmark == path) {
+            Some(index) => {
+                self.bookmarks.remove(index);
+            }
+            None => self.bookmarks.push(path),
+        }
+        self.save_bookmarks();
+    }
+
+    fn save_bookmarks(&self) {
+        if let Err(err) = save_bookmarks(&self.bookmarks) {
+            log::warn!("failed to save the bookmarks: {}", err);
+        }
+    }
+
+    fn show_bookmarks(&mut self) {
+        let mut state = TableState::default();
+        let current = self.bookmark_path();
+        let index = self
+            .bookmarks
+            .iter()
+            .position(|bookmark| *bookmark == current);
+        if !self.bookmarks.is_empty() {
+            state.select(Some(index.unwrap_or(0)));
+        }
+        self.bookmark_list = Some(state);
+    }
+
+    /// Moves through the list of bookmarks. Enter goes to the selected folder,
+    /// and Delete unpins it
+    fn handle_bookmark_key(&mut self, key: KeyEvent) -> ExplorerResponse {
+        let state = match &mut self.bookmark_list {
+            Some(state) => state,
+            None => return ExplorerResponse::None,
+        };
+        let len = self.bookmarks.len();
+        let selected = state.selected();
+        match key.code {
+            KeyCode::Esc => self.bookmark_list = None,
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.bookmark_list = None
+            }
+            KeyCode::Up if len > 0 => {
+                state.select(selected.map(|selected| (selected + len - 1) % len));
+            }
+            KeyCode::Down if len > 0 => {
+                state.select(selected.map(|selected| (selected + 1) % len));
+            }
+            KeyCode::Enter => {
+                if let Some(path) = selected.and_then(|index| self.bookmarks.get(index)) {
+                    let path = path.clone();
+                    self.bookmark_list = None;
+                    self.set_path(path);
+                }
+            }
+            KeyCode::Delete => {
+                if let Some(index) = selected.filter(|index| *index < len) {
+                    self.bookmarks.remove(index);
+                    let len = self.bookmarks.len();
+                    state.select(if len == 0 {
+                        None
+                    } else {
+                        Some(index.min(len - 1))
+                    });
+                    self.save_bookmarks();
+                }
+            }
+            _ => return ExplorerResponse::None,
+        }
+        ExplorerResponse::Handled
+    }
+
+    fn draw_bookmarks(&self, state: &TableState, rect: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                "Bookmarks",
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let area = rect.centered(rect.scaled(0.8, 0.6));
+        let inner = block.inner(area);
+        Widget::render(Clear, area, buf);
+        Widget::render(block, area, buf);
+        if self.bookmarks.is_empty() {
+            let hint = "No bookmarks yet. Press Ctrl+B in a folder to pin it";
+            let p = Paragraph::new(Span::styled(hint, Style::default().fg(self.theme.hint)));
+            Widget::render(p, inner, buf);
+            return;
+        }
+        let rows = self
+            .bookmarks
+            .iter()
+            .map(|path| Row::new(vec![path.to_string_lossy().to_string()]));
+        let table = Table::new(rows)
+            .widths(&[Constraint::Percentage(100)])
+            .highlight_style(Style::default().bg(self.theme.explorer));
+        let mut state = state.clone();
+        StatefulWidget::render(table, inner, buf, &mut state);
+    }
+
+    fn start_new_folder(&mut self) {
+        let mut input = Input::default()
+            .editing_style(Style::default().bg(self.theme.highlight))
+            .error_style(Style::default().fg(self.theme.error));
+        input.focused = true;
+        self.new_folder = Some(input);
+    }
+
+    /// Creates the folder as it's named, and goes into it. If it can't be created,
+    /// the reason is shown next to the name
+    fn handle_new_folder_event(&mut self, event: Event) -> ExplorerResponse {
+        let input = match &mut self.new_folder {
+            Some(input) => input,
+            None => return ExplorerResponse::None,
+        };
+        match input.handle_event(event) {
+            InputResponse::Submit => {
+                if input.value.is_empty() {
+                    self.new_folder = None;
+                    return ExplorerResponse::Handled;
+                }
+                let path = self.path.join(&input.value);
+                match create_dir(&path) {
+                    Ok(()) => {
+                        self.new_folder = None;
+                        self.set_path(path);
+                    }
+                    Err(err) => input.error = Some(err.to_string()),
+                }
+                ExplorerResponse::Handled
+            }
+            InputResponse::Cancel => {
+                self.new_folder = None;
+                ExplorerResponse::Handled
+            }
+            InputResponse::Edited { .. } => {
+                input.error = None;
+                ExplorerResponse::Handled
+            }
+            InputResponse::None => ExplorerResponse::None,
+        }
+    }
+
+    fn start_path_input(&mut self) {
+        let mut input = Input::default()
+            .editing_style(Style::default().bg(self.theme.highlight))
+            .error_style(Style::default().fg(self.theme.error));
+        input.value = self.path.to_string_lossy().to_string();
+        if !input.value.ends_with(MAIN_SEPARATOR) {
+            input.value.push(MAIN_SEPARATOR);
+        }
+        input.focused = true;
+        self.path_input = Some(input);
+    }
+
+    /// Goes to the typed path. A folder is opened in the explorer, and a file is
+    /// opened or saved to. Tab completes the name being typed
+    fn handle_path_input_event(&mut self, event: Event) -> ExplorerResponse {
+        let input = match &mut self.path_input {
+            Some(input) => input,
+            None => return ExplorerResponse::None,
+        };
+        if let Event::Key(key) = event {
+            if key.code == KeyCode::Tab {
+                if let Some(completed) = complete_path(Path::new("."), &input.value) {
+                    input.value = completed;
+                }
+                input.error = None;
+                return ExplorerResponse::Handled;
+            }
+        }
+        match input.handle_event(event) {
+            InputResponse::Submit => {
+                let path = self.path.join(expand_home(&input.value));
+                if path.is_dir() {
+                    self.path_input = None;
+                    self.set_path(path);
+                    return ExplorerResponse::Handled;
+                }
+                let parent_exists = path.parent().is_some_and(|parent| parent.is_dir());
+                match self.mode {
+                    ExplorerMode::Open if path.is_file() => {
+                        self.path_input = None;
+                        return ExplorerResponse::Open(path);
+                    }
+                    ExplorerMode::Save if path.is_file() => {
+                        self.path_input = None;
+                        self.confirm_overwrite(path);
+                    }
+                    ExplorerMode::Save if parent_exists => {
+                        self.path_input = None;
+                        return ExplorerResponse::Save(path);
+                    }
+                    _ => input.error = Some(String::from("not found")),
+                }
+                ExplorerResponse::Handled
+            }
+            InputResponse::Cancel => {
+                self.path_input = None;
+                ExplorerResponse::Handled
+            }
+            InputResponse::Edited { .. } => {
+                input.error = None;
+                ExplorerResponse::Handled
+            }
+            InputResponse::None => ExplorerResponse::None,
+        }
+    }
+
+    /// Scrolls the list just enough to show the selected file
+    fn scroll_to_selected(&mut self, height: usize) {
+        let selected = self.index();
+        if selected < self.offset {
+            self.offset = selected;
+        } else if selected >= self.offset + height {
+            self.offset = selected + 1 - height;
+        }
+    }
+}
+
+impl Component for Explorer {
+    type Response = ExplorerResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let typing = self.input_active || self.path_input.is_some() || self.new_folder.is_some();
+        if let Event::Key(key) = event {
+            if is_paste(&key) && typing && self.confirm_overwrite.is_none() {
+                for event in paste_events(&clipboard_text().unwrap_or_default()) {
+                    self.handle_event(event);
+                }
+                return ExplorerResponse::Handled;
+            }
+        }
+        if let Some((overwrite, path)) = &mut self.confirm_overwrite {
+            if let ConfirmResponse::Confirm(yes) = overwrite.handle_event(event) {
+                let path = path.to_owned();
+                self.confirm_overwrite = None;
+                if yes {
+                    return ExplorerResponse::Save(path);
+                }
+            }
+            ExplorerResponse::Handled
+        } else if self.path_input.is_some() {
+            self.handle_path_input_event(event)
+        } else if self.new_folder.is_some() {
+            self.handle_new_folder_event(event)
+        } else if self.bookmark_list.is_some() {
+            match event {
+                Event::Key(key) => self.handle_bookmark_key(key),
+                _ => ExplorerResponse::None,
+            }
+        } else if self.input_active {
+            match self.input.handle_event(event) {
+                InputResponse::Submit => {
+                    if let Ok(files) = &self.files {
+                        match self.mode {
+                            ExplorerMode::Open => {
+                                self.input_active = false;
+                                ExplorerResponse::Handled
+                            }
+                            ExplorerMode::Save => {
+                                // if the input matches a folder name exactly, enter it.
+                                // Otherwise save with the input as the file name
+                                let possible_folder = files.iter().find(|f| {
+                                    f.meta.is_dir()
+                                        && f.path.file_name().unwrap().to_string_lossy()
+                                            == self.input.value
+                                });
+                                match possible_folder {
+                                    Some(folder) => {
+                                        let p = folder.path.clone();
+                                        self.set_path(p);
+                                        ExplorerResponse::Handled
+                                    }
+                                    None => {
+                                        let new_path = self.path.join(&self.input.value);
+                                        if new_path.is_file() {
+                                            self.confirm_overwrite(new_path);
+                                            ExplorerResponse::Handled
+                                        } else {
+                                            ExplorerResponse::Save(new_path)
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        self.input_active = false;
+                        ExplorerResponse::Handled
+                    }
+                }
+                InputResponse::Cancel => {
+                    self.input_active = false;
+                    ExplorerResponse::Handled
+                }
+                InputResponse::Edited { deletion } => {
+                    // select the first match
+                    if let Ok(files) = &self.files {
+                        if deletion {
+                            return ExplorerResponse::Handled;
+                        }
+                        if let Some(index) = files.iter().position(|file| {
+                            file.path
+                                .file_name()
+                                // only fails if path ends in .., so this is fine
+                                .unwrap()
+                                .to_string_lossy()
+                                .to_lowercase()
+                                .starts_with(&self.input.value.to_lowercase())
+                        }) {
+                            self.table_state.select(Some(index));
+                        }
+                    }
+                    ExplorerResponse::Handled
+                }
+                InputResponse::None => ExplorerResponse::None,
+            }
+        } else if let Event::Mouse(mouse) = event {
+            self.handle_mouse(mouse)
+        } else if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Esc => ExplorerResponse::Cancel,
+                KeyCode::Up => {
+                    self.decrement();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Down => {
+                    self.increment();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Home => {
+                    self.select_clamped(0);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::End => {
+                    self.select_clamped(usize::MAX);
+                    ExplorerResponse::Handled
+                }
+                KeyCode::PageUp => {
+                    self.select_clamped(self.index().saturating_sub(self.page()));
+                    ExplorerResponse::Handled
+                }
+                KeyCode::PageDown => {
+                    self.select_clamped(self.index() + self.page());
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Enter => self.enter(),
+                KeyCode::Backspace => {
+                    let parent = self.path.parent().map(|p| p.to_path_buf());
+                    if let Some(par) = parent {
+                        self.set_path(par);
+                    }
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('/') => {
+                    self.input_active = true;
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('n')
+                    if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.mode == ExplorerMode::Save =>
+                {
+                    self.start_new_folder();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('l') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.start_path_input();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('b') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.toggle_bookmark();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('g') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.show_bookmarks();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('a') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.show_all = !self.show_all;
+                    self.set_path(self.path.clone());
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('s') if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.sort = self.sort.next();
+                    self.sort_files();
+                    ExplorerResponse::Handled
+                }
+                KeyCode::Char('r') if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.descending = !self.descending;
+                    self.sort_files();
+                    ExplorerResponse::Handled
+                }
+                _ => ExplorerResponse::None,
+            }
+        } else {
+            ExplorerResponse::None
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buf: &mut Buffer) {
+        self.input.focused = self.input_active;
+        let title = format!(
+            "{} ({}, by {} {})",
+            match (self.title, self.mode) {
+                (Some(title), _) => title,
+                (None, ExplorerMode::Open) => "Open File",
+                (None, ExplorerMode::Save) => "Save File",
+            },
+            if self.show_all {
+                "all files"
+            } else {
+                "param files"
+            },
+            self.sort.label(),
+            if self.descending { "▼" } else { "▲" },
+        );
+        let outer = Block::default()
+            .title(Span::styled(title, Style::default().fg(self.theme.title)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.explorer));
+        let inner = outer.inner(rect);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Percentage(100),
+            ])
+            .split(inner);
+
+        let bookmarked = self.bookmarks.contains(&self.bookmark_path());
+        let p = Paragraph::new(if bookmarked {
+            format!("{} (bookmarked)", self.path.to_string_lossy())
+        } else {
+            self.path.to_string_lossy().to_string()
+        });
+        // only the rows which fit are given to the table, so clicks can be mapped to them
+        let height = areas[2].height as usize;
+        self.scroll_to_selected(height);
+        self.area = areas[2];
+
+        Widget::render(outer, rect, buf);
+        match &mut self.path_input {
+            Some(input) => input.draw(areas[0], buf),
+            None => Widget::render(p, areas[0], buf),
+        }
+        self.input.draw(areas[1], buf);
+        match &self.files {
+            Ok(files) => {
+                // the size and date are left out when there isn't room for the names
+                let details = areas[2].width >= MIN_DETAILS_WIDTH;
+                let names = files
+                    .iter()
+                    .skip(self.offset)
+                    .take(height)
+                    .map(|p| {
+                        let name = p.path.as_path().file_name().unwrap().to_string_lossy();
+                        let string = if p.meta.is_dir() {
+                            format!("{} /", name)
+                        } else {
+                            name.to_string()
+                        };
+                        if details {
+                            let size = if p.meta.is_dir() {
+                                String::new()
+                            } else {
+                                format_size(p.meta.len())
+                            };
+                            Row::new(vec![string, size, format_modified(&p.meta)])
+                        } else {
+                            Row::new(vec![string])
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let widths: &[Constraint] = if details {
+                    &[
+                        // the spacing between the 3 columns takes 4 cells
+                        Constraint::Length(areas[2].width - SIZE_WIDTH - MODIFIED_WIDTH - 4),
+                        Constraint::Length(SIZE_WIDTH),
+                        Constraint::Length(MODIFIED_WIDTH),
+                    ]
+                } else {
+                    &[Constraint::Percentage(100)]
+                };
+                let table = Table::new(names)
+                    .widths(widths)
+                    .column_spacing(2)
+                    .highlight_style(Style::default().bg(self.theme.explorer));
+                let mut visible_state = TableState::default();
+                visible_state.select(self.index().checked_sub(self.offset));
+                StatefulWidget::render(table, areas[2], buf, &mut visible_state);
+            }
+            Err(e) => {
+                let p = Paragraph::new(Span::styled(e, Style::default().fg(self.theme.error)));
+                Widget::render(p, areas[2], buf);
+            }
+        }
+        if let Some(state) = &self.bookmark_list {
+            self.draw_bookmarks(state, rect, buf);
+        }
+        if let Some(input) = &mut self.new_folder {
+            let block = Block::default()
+                .title(Span::styled(
+                    "New folder name",
+                    Style::default().fg(self.theme.title),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.accent));
+            let area = rect.centered(Rect {
+                x: 0,
+                y: 0,
+                width: (rect.width * 3 / 4).max(20).min(rect.width),
+                height: 3,
+            });
+            let inner = block.inner(area);
+            Widget::render(Clear, area, buf);
+            Widget::render(block, area, buf);
+            input.draw(inner, buf);
+        }
+        // the overwrite prompt appears above everything
+        if let Some((overwrite, _)) = &mut self.confirm_overwrite {
+            overwrite.draw(rect, buf)
+        }
+    }
+}
+
+/// Replaces a leading `~` with the home folder
+fn expand_home(path: &str) -> PathBuf {
+    let home = var_os("HOME").or_else(|| var_os("USERPROFILE"));
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            PathBuf::from(home).join(rest.trim_start_matches(['/', '\\']))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Completes the last part of a typed path, as far as every folder or file it could name agrees.
+/// A folder which is completed in full gets a separator after it, so the next name can be typed.
+/// Relative paths are completed from the base folder
+pub(crate) fn complete_path(base: &Path, typed: &str) -> Option<String> {
+    let split = typed.rfind(['/', '\\']).map_or(0, |index| index + 1);
+    let (folder, prefix) = typed.split_at(split);
+    let dir = base.join(expand_home(folder));
+    let prefix_lower = prefix.to_lowercase();
+    let matches = read_dir(dir)
+        .ok()?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.path().is_dir();
+            Some((name, is_dir)).filter(|(name, _)| name.to_lowercase().starts_with(&prefix_lower))
+        })
+        .collect::<Vec<_>>();
+    let (first, first_is_dir) = matches.first()?;
+    let mut common = first.as_str();
+    for (name, _) in &matches[1..] {
+        let shared = common
+            .char_indices()
+            .zip(name.chars())
+            .find(|((_, a), b)| !a.eq_ignore_ascii_case(b))
+            .map_or(common.len().min(name.len()), |((index, _), _)| index);
+        common = &common[..shared];
+    }
+    let mut completed = format!("{}{}", folder, common);
+    if matches.len() == 1 && *first_is_dir {
+        completed.push(MAIN_SEPARATOR);
+    }
+    Some(completed)
+}
+
+/// When the file was last modified, in local time
+fn format_modified(meta: &Metadata) -> String {
+    meta.modified()
+        .map(|time| {
+            DateTime::<Local>::from(time)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
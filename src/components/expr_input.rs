@@ -0,0 +1,120 @@
+use prc::ParamKind;
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::expr::evaluate;
+use crate::settings::FloatFormat;
+use crate::theme::Theme;
+use crate::value::{format_value, number_value, param_number};
+
+/// Edits a number param as arithmetic, like `*1.1` or `2.5*3`, showing what it comes to
+#[derive(Debug)]
+pub struct ExprInput {
+    text: String,
+    /// The param's value before editing, which expressions starting with an operator apply to
+    current: ParamKind,
+    result: Result<ParamKind, String>,
+    floats: FloatFormat,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExprInputResponse {
+    None,
+    Handled,
+    Submit,
+    Cancel,
+}
+
+impl ExprInput {
+    pub fn new(text: String, current: ParamKind, floats: FloatFormat, theme: Theme) -> Self {
+        let mut this = Self {
+            text,
+            result: Ok(current.clone()),
+            current,
+            floats,
+            theme,
+        };
+        this.update_result();
+        this
+    }
+
+    /// The expression typed so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// What the expression comes to, or the value before editing if it's invalid
+    pub fn value(&self) -> ParamKind {
+        self.result.clone().unwrap_or_else(|_| self.current.clone())
+    }
+
+    fn update_result(&mut self) {
+        let current = param_number(&self.current).unwrap_or_default();
+        self.result =
+            evaluate(&self.text, current).and_then(|number| number_value(&self.current, number));
+    }
+}
+
+impl Component for ExprInput {
+    type Response = ExprInputResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Char(c) => {
+                    self.text.push(c);
+                    self.update_result();
+                    ExprInputResponse::Handled
+                }
+                KeyCode::Backspace => {
+                    self.text.pop();
+                    self.update_result();
+                    ExprInputResponse::Handled
+                }
+                KeyCode::Enter if self.result.is_ok() => ExprInputResponse::Submit,
+                KeyCode::Esc => ExprInputResponse::Cancel,
+                _ => ExprInputResponse::None,
+            }
+        } else {
+            ExprInputResponse::None
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) -> Self::DrawResponse {
+        let text = Paragraph::new(self.get_spans());
+        Widget::render(text, rect, buffer);
+    }
+}
+
+impl Spannable for ExprInput {
+    fn get_spans<'b>(&self) -> Spans<'b> {
+        let mut spans = Spans::default();
+        spans.0.push(Span::styled(
+            String::from("> "),
+            Style::default().fg(self.theme.hint),
+        ));
+        spans.0.push(Span::styled(
+            self.text.clone(),
+            Style::default().fg(self.theme.accent),
+        ));
+        spans.0.push(match &self.result {
+            Ok(ParamKind::Float(float)) => Span::styled(
+                format!(" = {}", self.floats.format(*float)),
+                Style::default().fg(self.theme.hint),
+            ),
+            Ok(value) => Span::styled(
+                format!(" = {}", format_value(value)),
+                Style::default().fg(self.theme.hint),
+            ),
+            Err(err) => Span::styled(format!(" {}", err), Style::default().fg(self.theme.error)),
+        });
+        spans
+    }
+}
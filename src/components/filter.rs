@@ -0,0 +1,367 @@
+use std::cell::OnceCell;
+use std::sync::{Arc, Mutex};
+
+use prc::ParamKind;
+use regex::Regex;
+use tui_components::components::{Input, InputResponse};
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::Spans;
+use tui_components::{Component, Event, Spannable};
+
+use crate::history::{HistoryField, InputHistory, Recall};
+use crate::theme::Theme;
+
+/// A text input for filtering the rows of a param level.
+///
+/// The text is made of whitespace-separated terms, which all have to match:
+/// - `name:<regex>` or just `<regex>` matches against the row's name
+/// - `value:<op><number>` compares numeric values, where op is one of `>`, `>=`, `<`, `<=`, `=`, `!=`
+/// - `value:<regex>` matches against the row's displayed value
+/// - any term starting with `!` hides the rows it matches instead, like `!_frame$`
+///
+/// Filters which are kept are added to the history, and Up/Down step through it while typing
+#[derive(Debug)]
+pub struct Filter {
+    input: Input,
+    expr: Option<FilterExpr>,
+    /// The text the expression was last parsed from
+    text: String,
+    history: Option<Arc<Mutex<InputHistory>>>,
+    /// Where the text is in the history, if stepping through it
+    recall: Recall,
+}
+
+#[derive(Debug, Clone)]
+pub struct FilterExpr {
+    terms: Vec<FilterTerm>,
+}
+
+#[derive(Debug, Clone)]
+enum FilterTerm {
+    Name(Regex),
+    Value(ValueCondition),
+    /// Matches the rows the inner term doesn't
+    Not(Box<FilterTerm>),
+}
+
+#[derive(Debug, Clone)]
+enum ValueCondition {
+    Compare(CompareOp, f64),
+    Pattern(Regex),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FilterResponse {
+    None,
+    /// The filter text changed, so the matching rows may have changed.
+    /// If narrowed, the filter can only have become stricter, so only the rows
+    /// which matched before need to be checked again
+    Changed {
+        narrowed: bool,
+    },
+    Submit,
+    Cancel,
+}
+
+impl Filter {
+    pub fn new(theme: Theme) -> Self {
+        let mut input = Input::default()
+            .editing_style(Style::default().fg(theme.accent))
+            .error_style(Style::default().fg(theme.error));
+        input.focused = true;
+        Self {
+            input,
+            expr: None,
+            text: String::new(),
+            history: None,
+            recall: Recall::default(),
+        }
+    }
+
+    /// Remembers the filters kept, and recalls earlier ones
+    pub fn with_history(mut self, history: Arc<Mutex<InputHistory>>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Creates an unfocused filter which already has some text
+    pub fn with_text(text: &str, theme: Theme) -> Self {
+        let mut filter = Self::new(theme);
+        filter.input.focused = false;
+        filter.input.value = text.to_owned();
+        filter.update_expr();
+        filter
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text, as if it had been typed
+    pub fn set_text(&mut self, text: String) {
+        self.input.value = text;
+        self.update_expr();
+    }
+
+    /// The parsed expression, or None if the text is empty or invalid
+    pub fn expr(&self) -> Option<&FilterExpr> {
+        self.expr.as_ref()
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.input.focused = focused;
+        self.recall = Recall::default();
+    }
+
+    /// Replaces the text with an older entry from the history, or a newer one if not going back.
+    /// Returns whether there was one
+    fn recall(&mut self, back: bool) -> bool {
+        let history = match &self.history {
+            Some(history) => history.clone(),
+            None => return false,
+        };
+        let history = match history.lock() {
+            Ok(history) => history,
+            Err(err) => err.into_inner(),
+        };
+        let text = if back {
+            self.recall
+                .older(&history, HistoryField::Filter, &self.input.value)
+        } else {
+            self.recall.newer(&history, HistoryField::Filter)
+        };
+        match text {
+            Some(text) => {
+                self.set_text(text);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds the text to the history, if it's a filter
+    fn remember(&self) {
+        let history = match (&self.history, &self.expr) {
+            (Some(history), Some(_)) => history,
+            _ => return,
+        };
+        if let Ok(mut history) = history.lock() {
+            history.push(HistoryField::Filter, self.text.clone());
+        }
+    }
+
+    /// Whether the text was only extended in a way which can't match anything new.
+    /// When there was no expression, every row was shown, so any new one narrows it
+    fn is_narrowing(&self) -> bool {
+        let expr = match &self.expr {
+            Some(expr) => expr,
+            None => return true,
+        };
+        let added = match self.input.value.strip_prefix(self.text.as_str()) {
+            Some(added) if !added.is_empty() => added,
+            _ => return false,
+        };
+        // appending plain characters to a regex only makes it more specific, and whitespace
+        // starts a new term, but a number added to a comparison changes it entirely
+        let plain = added
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c.is_whitespace());
+        let extends_last_term =
+            !added.starts_with(char::is_whitespace) && !self.text.ends_with(char::is_whitespace);
+        // and making an excluded term more specific excludes less
+        let extends_comparison = extends_last_term
+            && matches!(
+                expr.terms.last(),
+                Some(FilterTerm::Value(ValueCondition::Compare(..)) | FilterTerm::Not(_))
+            );
+        plain && !extends_comparison
+    }
+
+    fn update_expr(&mut self) {
+        self.text = self.input.value.clone();
+        match FilterExpr::parse(&self.input.value) {
+            Ok(expr) => {
+                self.expr = expr;
+                self.input.error = None;
+            }
+            Err(err) => {
+                self.expr = None;
+                self.input.error = Some(err);
+            }
+        }
+    }
+}
+
+impl Component for Filter {
+    type Response = FilterResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Key(key) = event {
+            if matches!(key.code, KeyCode::Up | KeyCode::Down) {
+                return if self.recall(key.code == KeyCode::Up) {
+                    FilterResponse::Changed { narrowed: false }
+                } else {
+                    FilterResponse::None
+                };
+            }
+        }
+        match self.input.handle_event(event) {
+            InputResponse::Edited { .. } => {
+                let narrowed = self.is_narrowing();
+                self.update_expr();
+                FilterResponse::Changed { narrowed }
+            }
+            InputResponse::Submit => {
+                self.remember();
+                FilterResponse::Submit
+            }
+            InputResponse::Cancel => FilterResponse::Cancel,
+            InputResponse::None => FilterResponse::None,
+        }
+    }
+
+    fn draw(
+        &mut self,
+        rect: tui_components::tui::layout::Rect,
+        buffer: &mut tui_components::tui::buffer::Buffer,
+    ) -> Self::DrawResponse {
+        self.input.draw(rect, buffer);
+    }
+}
+
+impl Spannable for Filter {
+    fn get_spans<'b>(&self) -> Spans<'b> {
+        let mut spans = Spans::from("/");
+        spans.0.extend(self.input.get_spans().0);
+        spans
+    }
+}
+
+impl FilterExpr {
+    /// Parses the filter text. Returns Ok(None) when there are no terms to filter by
+    pub fn parse(text: &str) -> Result<Option<Self>, String> {
+        let terms = text
+            .split_whitespace()
+            .map(FilterTerm::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if terms.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(Self { terms }))
+        }
+    }
+
+    /// Checks a row against every term, given the row's param and functions making its name and
+    /// displayed value. These are only called if a term needs them, since formatting every row of
+    /// a large list (and looking up its labels) is slow
+    pub fn matches<N, V, S, T>(&self, name: N, param: &ParamKind, value: V) -> bool
+    where
+        N: Fn() -> S,
+        V: Fn() -> T,
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let name_cell = OnceCell::new();
+        let value_cell = OnceCell::new();
+        let name = || name_cell.get_or_init(&name).as_ref();
+        let value = || value_cell.get_or_init(&value).as_ref();
+        self.terms
+            .iter()
+            .all(|term| term.matches(&name, param, &value))
+    }
+}
+
+impl FilterTerm {
+    fn parse(term: &str) -> Result<Self, String> {
+        if let Some(term) = term.strip_prefix('!') {
+            FilterTerm::parse(term).map(|term| FilterTerm::Not(Box::new(term)))
+        } else if let Some(value) = term.strip_prefix("value:") {
+            ValueCondition::parse(value).map(FilterTerm::Value)
+        } else {
+            let name = term.strip_prefix("name:").unwrap_or(term);
+            Regex::new(name)
+                .map(FilterTerm::Name)
+                .map_err(|_| String::from("(invalid regex)"))
+        }
+    }
+
+    fn matches<'a, N, V>(&self, name: &N, param: &ParamKind, value: &V) -> bool
+    where
+        N: Fn() -> &'a str,
+        V: Fn() -> &'a str,
+    {
+        match self {
+            FilterTerm::Name(regex) => regex.is_match(name()),
+            FilterTerm::Value(ValueCondition::Pattern(regex)) => regex.is_match(value()),
+            FilterTerm::Value(ValueCondition::Compare(op, rhs)) => match param {
+                // compare floats at their own precision, so "=0.1" matches a stored 0.1f32
+                ParamKind::Float(lhs) => op.compare(*lhs as f64, *rhs as f32 as f64),
+                _ => param_number(param).is_some_and(|lhs| op.compare(lhs, *rhs)),
+            },
+            FilterTerm::Not(term) => !term.matches(name, param, value),
+        }
+    }
+}
+
+impl ValueCondition {
+    fn parse(condition: &str) -> Result<Self, String> {
+        // longer operators first, so ">=" isn't read as ">" followed by "=..."
+        let ops = [
+            (">=", CompareOp::GreaterEqual),
+            ("<=", CompareOp::LessEqual),
+            ("!=", CompareOp::NotEqual),
+            (">", CompareOp::Greater),
+            ("<", CompareOp::Less),
+            ("=", CompareOp::Equal),
+        ];
+        for (prefix, op) in ops.iter() {
+            if let Some(number) = condition.strip_prefix(prefix) {
+                return number
+                    .parse::<f64>()
+                    .map(|number| ValueCondition::Compare(*op, number))
+                    .map_err(|_| String::from("(invalid number)"));
+            }
+        }
+        Regex::new(condition)
+            .map(ValueCondition::Pattern)
+            .map_err(|_| String::from("(invalid regex)"))
+    }
+}
+
+impl CompareOp {
+    fn compare(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            CompareOp::Greater => lhs > rhs,
+            CompareOp::GreaterEqual => lhs >= rhs,
+            CompareOp::Less => lhs < rhs,
+            CompareOp::LessEqual => lhs <= rhs,
+            CompareOp::Equal => lhs == rhs,
+            CompareOp::NotEqual => lhs != rhs,
+        }
+    }
+}
+
+/// Gets the numeric value of a param, if it has one
+fn param_number(param: &ParamKind) -> Option<f64> {
+    match param {
+        ParamKind::I8(v) => Some(*v as f64),
+        ParamKind::U8(v) => Some(*v as f64),
+        ParamKind::I16(v) => Some(*v as f64),
+        ParamKind::U16(v) => Some(*v as f64),
+        ParamKind::I32(v) => Some(*v as f64),
+        ParamKind::U32(v) => Some(*v as f64),
+        _ => None,
+    }
+}
@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use prc::ParamStruct;
+use tui_components::components::Input;
+use tui_components::crossterm::event::{KeyCode, KeyModifiers};
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Direction, Layout, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::path::ParamPath;
+use crate::replace::{find_replacements_in, Pattern, Replacement};
+use crate::task::Cancellable;
+use crate::theme::Theme;
+use crate::utils::paste::{clipboard_text, is_paste, paste_events};
+
+use super::busy::{percent, spinner};
+use super::preview::ReplacementPreview;
+
+/// A panel for replacing values below a param, listing every change before it's applied.
+/// The values are searched for on another thread, so a search over a large file can be stopped
+#[derive(Debug)]
+pub struct FindReplace {
+    find: Input,
+    with: Input,
+    /// Whether the text to find is a regex, instead of a whole value
+    regex: bool,
+    /// The file when the panel was opened, which the changes are previewed against
+    root: Arc<ParamStruct>,
+    /// The param whose values are searched
+    base: ParamPath,
+    preview: ReplacementPreview,
+    /// The search for what's typed now, until it finishes
+    search: Option<Cancellable<Vec<Replacement>>>,
+    theme: Theme,
+}
+
+#[derive(Debug)]
+pub enum FindReplaceResponse {
+    None,
+    Handled,
+    /// The user confirmed the previewed changes
+    Apply(Vec<Replacement>),
+    Cancel,
+}
+
+impl FindReplace {
+    pub fn new(root: Arc<ParamStruct>, base: ParamPath, theme: Theme) -> Self {
+        let input = || Input::default().editing_style(Style::default().bg(theme.highlight));
+        let mut find = input();
+        find.focused = true;
+        Self {
+            find,
+            with: input(),
+            regex: false,
+            root,
+            base,
+            preview: ReplacementPreview::default(),
+            search: None,
+            theme,
+        }
+    }
+
+    /// Starts searching for what's typed now, which stops the search for what was typed before
+    fn update_replacements(&mut self) {
+        self.search = None;
+        if self.find.value.is_empty() && !self.regex {
+            self.preview.set(Ok(vec![]));
+            return;
+        }
+        match Pattern::new(&self.find.value, self.regex) {
+            Ok(pattern) => {
+                let root = self.root.clone();
+                let base = self.base.clone();
+                let with = self.with.value.clone();
+                self.search = Some(Cancellable::spawn(move |progress| {
+                    find_replacements_in(&root, &base, &pattern, &with, progress)
+                }));
+            }
+            Err(err) => self.preview.set(Err(err)),
+        }
+    }
+
+    /// Shows the changes once the search for them finishes.
+    /// Returns whether anything needs to be redrawn, which it does while the spinner turns
+    pub fn update(&mut self) -> bool {
+        match &self.search {
+            Some(search) if search.is_finished() => {
+                if let Some(replacements) = self.search.take().and_then(Cancellable::join) {
+                    self.preview.set(Ok(replacements));
+                }
+                true
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Whether the changes are still being searched for
+    pub fn is_busy(&self) -> bool {
+        self.search.is_some()
+    }
+
+    fn focused(&mut self) -> &mut Input {
+        if self.find.focused {
+            &mut self.find
+        } else {
+            &mut self.with
+        }
+    }
+}
+
+impl Component for FindReplace {
+    type Response = FindReplaceResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return FindReplaceResponse::None,
+        };
+        if is_paste(&key) {
+            for event in paste_events(&clipboard_text().unwrap_or_default()) {
+                self.handle_event(event);
+            }
+            return FindReplaceResponse::Handled;
+        }
+        match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.regex = !self.regex;
+                self.update_replacements();
+            }
+            KeyCode::Char(_) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return FindReplaceResponse::None
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.find.focused = !self.find.focused;
+                self.with.focused = !self.with.focused;
+            }
+            KeyCode::Up => self.preview.move_selection(true),
+            KeyCode::Down => self.preview.move_selection(false),
+            // the preview is of what was typed before until the search finishes
+            KeyCode::Enter if self.search.is_none() && self.preview.valid_count() > 0 => {
+                return FindReplaceResponse::Apply(self.preview.take());
+            }
+            KeyCode::Enter => {}
+            KeyCode::Esc if self.search.is_some() => {
+                self.search = None;
+                self.preview.set(Err(String::from("(search stopped)")));
+            }
+            KeyCode::Esc => return FindReplaceResponse::Cancel,
+            KeyCode::Char(_) | KeyCode::Backspace => {
+                self.focused().handle_event(event);
+                self.update_replacements();
+            }
+            _ => return FindReplaceResponse::None,
+        }
+        FindReplaceResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let base = if self.base.0.is_empty() {
+            String::from("the whole file")
+        } else {
+            self.base.to_string()
+        };
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Replace values in {}", base),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(rect);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+
+        let label = |text: &'static str| Span::styled(text, Style::default().fg(self.theme.hint));
+        let mode = if self.regex { "regex: " } else { "find:  " };
+        let mut find = Spans::from(label(mode));
+        find.0.extend(self.find.get_spans().0);
+        let mut with = Spans::from(label("with:  "));
+        with.0.extend(self.with.get_spans().0);
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(block, rect, buffer);
+        Widget::render(Paragraph::new(find), areas[0], buffer);
+        Widget::render(Paragraph::new(with), areas[1], buffer);
+        self.preview.set_status(self.search.as_ref().map(|search| {
+            format!(
+                "{} Searching...{}  Esc: stop",
+                spinner(search.started()),
+                percent(search.progress())
+            )
+        }));
+        self.preview.draw(areas[2], buffer, self.theme);
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeSet;
+use std::iter::once;
+use std::sync::{Arc, Mutex};
+
+use prc::hash40::Hash40;
+use tui_components::crossterm::event::KeyCode;
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Direction, Layout, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::Span;
+use tui_components::tui::widgets::{
+    Block, Borders, Clear, Gauge, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+};
+use tui_components::{Component, Event};
+
+use crate::cracker::Cracker;
+use crate::labels::labels_changed;
+use crate::theme::Theme;
+
+/// A popup which searches for a label matching a hash in the background,
+/// and lets the user add any matches to the label map
+#[derive(Debug)]
+pub struct HashCracker {
+    cracker: Cracker,
+    sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+    found: Vec<String>,
+    running: bool,
+    state: TableState,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum HashCrackerResponse {
+    None,
+    Handled,
+    Exit,
+}
+
+impl HashCracker {
+    pub fn new(target: Hash40, sorted_labels: Arc<Mutex<BTreeSet<String>>>, theme: Theme) -> Self {
+        Self {
+            cracker: Cracker::new(target, &sorted_labels),
+            sorted_labels,
+            found: vec![],
+            running: true,
+            state: TableState::default(),
+            theme,
+        }
+    }
+
+    /// Checks the background search for new matches.
+    /// Returns whether anything may have changed since the last update
+    pub fn update(&mut self) -> bool {
+        let running = self.cracker.is_running();
+        let changed = running || self.running;
+        self.running = running;
+        self.found = self.cracker.found();
+        if self.state.selected().is_none() && !self.found.is_empty() {
+            self.state.select(Some(0));
+        }
+        changed
+    }
+
+    /// Whether the search was still running as of the last update
+    pub fn is_busy(&self) -> bool {
+        self.running
+    }
+
+    fn add_label(&self, label: String) {
+        let hash = self.cracker.target();
+        if let Ok(mut sorted) = self.sorted_labels.lock() {
+            sorted.insert(label.clone());
+        }
+        if let Ok(mut labels) = Hash40::label_map().lock() {
+            labels.add_custom_labels(once((hash, label)));
+        }
+        labels_changed();
+    }
+}
+
+impl Component for HashCracker {
+    type Response = HashCrackerResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Up => {
+                    if let Some(selected) = self.state.selected() {
+                        self.state.select(Some(selected.saturating_sub(1)));
+                    }
+                    HashCrackerResponse::Handled
+                }
+                KeyCode::Down => {
+                    if let Some(selected) = self.state.selected() {
+                        let last = self.found.len().saturating_sub(1);
+                        self.state.select(Some((selected + 1).min(last)));
+                    }
+                    HashCrackerResponse::Handled
+                }
+                KeyCode::Enter => {
+                    let selected = self
+                        .state
+                        .selected()
+                        .and_then(|selected| self.found.get(selected));
+                    if let Some(label) = selected {
+                        self.add_label(label.to_owned());
+                        self.cracker.cancel();
+                        HashCrackerResponse::Exit
+                    } else {
+                        HashCrackerResponse::Handled
+                    }
+                }
+                KeyCode::Esc => {
+                    self.cracker.cancel();
+                    HashCrackerResponse::Exit
+                }
+                _ => HashCrackerResponse::None,
+            }
+        } else {
+            HashCrackerResponse::None
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block_area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width: 60,
+            height: 12,
+        });
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Cracking 0x{:010x}", self.cracker.target().0),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(block_area);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+
+        let status = if self.cracker.is_running() {
+            format!("Searching... {} found", self.found.len())
+        } else {
+            format!("Done, {} found. Enter adds a label", self.found.len())
+        };
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(self.theme.highlight))
+            .ratio(self.cracker.progress());
+        let rows = self
+            .found
+            .iter()
+            .map(|label| Row::new(vec![label.to_owned()]))
+            .collect::<Vec<_>>();
+        let table = Table::new(rows)
+            .widths(&[Constraint::Percentage(100)])
+            .highlight_style(Style::default().bg(self.theme.highlight));
+
+        Widget::render(Clear, block_area, buffer);
+        Widget::render(block, block_area, buffer);
+        Widget::render(gauge, areas[0], buffer);
+        Widget::render(Paragraph::new(status), areas[1], buffer);
+        StatefulWidget::render(table, areas[2], buffer, &mut self.state);
+    }
+}
@@ -1,26 +1,44 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
 use prc::hash40::{hash40, Hash40};
-use tui_components::tui::style::Modifier;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
 use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{
+    Block, Borders, Clear, List, ListItem, ListState, StatefulWidget,
+};
 use tui_components::Spannable;
 use tui_components::{
     crossterm::event::KeyCode,
     tui::{
-        style::{Color, Style},
+        style::Style,
         widgets::{Paragraph, Widget},
     },
     Component,
 };
 
+use crate::theme::Theme;
+
+/// The most suggestions shown at once below the input
+pub const SUGGESTION_ROWS: usize = 8;
+/// The most suggestions kept, since short prefixes can match a huge number of labels
+const MAX_MATCHES: usize = 1000;
+
 #[derive(Debug)]
 pub struct HashInput {
     value: String,
     return_value: Hash40,
     sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+    /// The labels of hashes used in the open file, most used first. These are suggested first
+    used: Vec<String>,
     matches: Vec<String>,
+    /// How many of the matches are used in the file, which are always the first ones
+    used_matches: usize,
+    /// How many labels start with the value, including any past the ones kept
+    total_matches: usize,
     match_num: Option<usize>,
+    theme: Theme,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -33,13 +51,36 @@ pub enum Validity {
 }
 
 impl HashInput {
-    pub fn new(hash: Hash40, sorted_labels: Arc<Mutex<BTreeSet<String>>>) -> Self {
+    /// Starts editing a hash. The usage counts how often each hash appears in the open file
+    pub fn new(
+        hash: Hash40,
+        sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+        usage: &HashMap<Hash40, usize>,
+        theme: Theme,
+    ) -> Self {
+        let mut used = usage
+            .iter()
+            .filter_map(|(hash, count)| {
+                let label = hash.to_label();
+                // hashes without a label are shown as hex, which is never suggested
+                if label.starts_with("0x") {
+                    None
+                } else {
+                    Some((label, *count))
+                }
+            })
+            .collect::<Vec<_>>();
+        used.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
         let mut this = Self {
             value: hash.to_string(),
             return_value: hash,
             sorted_labels,
+            used: used.into_iter().map(|(label, _)| label).collect(),
             matches: vec![],
+            used_matches: 0,
+            total_matches: 0,
             match_num: None,
+            theme,
         };
         this.update_matches();
         this
@@ -69,6 +110,17 @@ impl HashInput {
         self.return_value
     }
 
+    /// The label or hex typed so far
+    pub fn text(&self) -> &str {
+        &self.value
+    }
+
+    /// Replaces what's typed, like a suggested label
+    pub fn set_text(&mut self, text: &str) {
+        self.value = text.to_owned();
+        self.update_matches();
+    }
+
     fn update_matches(&mut self) {
         let status = self.status();
         match status {
@@ -77,14 +129,27 @@ impl HashInput {
                 if let Ok(sorted_labels) = sorted_lock {
                     let prefix = self.value.to_owned();
                     let prefix_str = self.value.as_str();
-                    self.matches = sorted_labels
-                        .range(prefix..)
-                        // remove the exact match
-                        .filter(|str| str.len() > prefix_str.len() && str.starts_with(prefix_str))
-                        // limit to 1000 matches (small prefixes could lead to huge match count)
-                        .take(1000)
+                    let is_match =
+                        |str: &String| str.len() > prefix_str.len() && str.starts_with(prefix_str);
+                    let used = self
+                        .used
+                        .iter()
+                        .filter(|str| is_match(str))
+                        .collect::<HashSet<_>>();
+                    self.used_matches = used.len().min(MAX_MATCHES);
+                    let mut matches = self.used.iter().filter(|str| used.contains(str)).chain(
+                        sorted_labels
+                            .range(prefix..)
+                            .take_while(|str| str.starts_with(prefix_str))
+                            // remove the exact match, and the labels already listed
+                            .filter(|str| is_match(str) && !used.contains(str)),
+                    );
+                    self.matches = matches
+                        .by_ref()
+                        .take(MAX_MATCHES)
                         .map(|str| str.to_owned())
                         .collect();
+                    self.total_matches = self.matches.len() + matches.count();
                     if matches!(status, Validity::LabelNotExists(..)) && !self.matches.is_empty() {
                         self.match_num = Some(0)
                     } else {
@@ -92,11 +157,15 @@ impl HashInput {
                     }
                 } else {
                     self.matches = vec![];
+                    self.used_matches = 0;
+                    self.total_matches = 0;
                     self.match_num = None;
                 }
             }
             _ => {
                 self.matches = vec![];
+                self.used_matches = 0;
+                self.total_matches = 0;
                 self.match_num = None;
             }
         }
@@ -106,6 +175,82 @@ impl HashInput {
         self.match_num
             .and_then(|num| self.matches.get(num).map(|str| str.as_str()))
     }
+
+    /// Moves the highlighted suggestion by some number of rows, stopping at either end
+    fn move_match(&mut self, rows: isize) {
+        let last = match self.matches.len().checked_sub(1) {
+            Some(last) => last,
+            None => {
+                self.match_num = None;
+                return;
+            }
+        };
+        self.match_num = match self.match_num {
+            Some(current) if rows < 0 => Some(current.saturating_sub(rows.unsigned_abs())),
+            Some(current) => Some((current + rows as usize).min(last)),
+            // moving down from the input goes to the first suggestion
+            None if rows > 0 => Some((rows as usize - 1).min(last)),
+            None => None,
+        };
+    }
+
+    /// How many rows the list of suggestions takes, including its borders, or 0 without any
+    pub fn suggestions_height(&self) -> u16 {
+        if self.matches.is_empty() {
+            0
+        } else {
+            self.matches.len().min(SUGGESTION_ROWS) as u16 + 2
+        }
+    }
+
+    /// The width the list of suggestions would like, to fit the longest one shown
+    pub fn suggestions_width(&self) -> u16 {
+        let longest = self.matches.iter().map(String::len).max().unwrap_or(0);
+        (longest.max(self.matches_title().len()) + 2) as u16
+    }
+
+    fn matches_title(&self) -> String {
+        let mut title = format!("{} matches", self.total_matches);
+        if self.used_matches > 0 {
+            title += &format!(", {} in file", self.used_matches);
+        }
+        if self.total_matches > self.matches.len() {
+            title += &format!(", showing {}", self.matches.len());
+        }
+        title
+    }
+
+    /// Draws the labels starting with the value, with the highlighted one selected.
+    /// The ones used in the file come first, in a different color
+    pub fn draw_suggestions(&self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                self.matches_title(),
+                Style::default().fg(self.theme.hint),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let items = self
+            .matches
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let item = ListItem::new(label.as_str());
+                if i < self.used_matches {
+                    item.style(Style::default().fg(self.theme.success))
+                } else {
+                    item
+                }
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(self.theme.highlight));
+        let mut state = ListState::default();
+        state.select(self.match_num);
+        Widget::render(Clear, rect, buffer);
+        StatefulWidget::render(list, rect, buffer, &mut state);
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -134,25 +279,19 @@ impl Component for HashInput {
                     HashInputResponse::Handled
                 }
                 KeyCode::Down => {
-                    if self.matches.is_empty() {
-                        self.match_num = None;
-                    } else if let Some(current) = self.match_num {
-                        if current < self.matches.len() - 1 {
-                            self.match_num = Some(current + 1);
-                        }
-                    } else {
-                        self.match_num = Some(0)
-                    }
+                    self.move_match(1);
                     HashInputResponse::Handled
                 }
                 KeyCode::Up => {
-                    if self.matches.is_empty() {
-                        self.match_num = None;
-                    } else if let Some(current) = self.match_num {
-                        if current > 0 {
-                            self.match_num = Some(current - 1);
-                        }
-                    }
+                    self.move_match(-1);
+                    HashInputResponse::Handled
+                }
+                KeyCode::PageDown => {
+                    self.move_match(SUGGESTION_ROWS as isize);
+                    HashInputResponse::Handled
+                }
+                KeyCode::PageUp => {
+                    self.move_match(-(SUGGESTION_ROWS as isize));
                     HashInputResponse::Handled
                 }
                 KeyCode::Tab => {
@@ -194,29 +333,21 @@ impl Component for HashInput {
 }
 
 impl Spannable for HashInput {
-    fn get_spans<'a, 'b>(&'a self) -> tui_components::tui::text::Spans<'b> {
+    fn get_spans<'b>(&self) -> tui_components::tui::text::Spans<'b> {
         let mut spans = Spans::default();
         spans.0.push(Span::styled(
             String::from("> "),
-            Style::default().fg(Color::Gray),
+            Style::default().fg(self.theme.hint),
         ));
         let status = self.status();
         let color = match status {
-            Validity::Hash(..) | Validity::LabelExists(..) => Color::Green,
-            Validity::HashInvalid => Color::Red,
-            Validity::LabelNotExists(..) | Validity::LabelsPoisoned(..) => Color::LightYellow,
+            Validity::Hash(..) | Validity::LabelExists(..) => self.theme.success,
+            Validity::HashInvalid => self.theme.error,
+            Validity::LabelNotExists(..) | Validity::LabelsPoisoned(..) => self.theme.warning,
         };
         spans
             .0
             .push(Span::styled(self.value.clone(), Style::default().fg(color)));
-        if let Some(current_match) = self.current_match() {
-            spans.0.push(Span::styled(
-                format!(" ({})?", current_match.trim_start_matches(&self.value)),
-                Style::default()
-                    .fg(Color::Gray)
-                    .add_modifier(Modifier::ITALIC),
-            ))
-        }
         spans
     }
 }
@@ -0,0 +1,141 @@
+use prc::hash40::Hash40;
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Direction, Layout, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::Span;
+use tui_components::tui::widgets::{
+    Block, Borders, Clear, Paragraph, Row, StatefulWidget, Table, TableState, Widget,
+};
+use tui_components::{Component, Event};
+
+use crate::report::{write_unknown_hashes, UnknownHash, UNKNOWN_HASHES_FILE};
+use crate::theme::Theme;
+
+/// A panel listing every unlabeled hash in the file
+#[derive(Debug)]
+pub struct HashReport {
+    unknown: Vec<UnknownHash>,
+    state: TableState,
+    message: Option<String>,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum HashReportResponse {
+    None,
+    Handled,
+    /// The user chose to search for a label for this hash
+    Crack(Hash40),
+    Exit,
+}
+
+impl HashReport {
+    pub fn new(unknown: Vec<UnknownHash>, theme: Theme) -> Self {
+        let mut state = TableState::default();
+        if !unknown.is_empty() {
+            state.select(Some(0));
+        }
+        Self {
+            unknown,
+            state,
+            message: None,
+            theme,
+        }
+    }
+}
+
+impl Component for HashReport {
+    type Response = HashReportResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Up => {
+                    if let Some(selected) = self.state.selected() {
+                        self.state.select(Some(selected.saturating_sub(1)));
+                    }
+                    HashReportResponse::Handled
+                }
+                KeyCode::Down => {
+                    if let Some(selected) = self.state.selected() {
+                        let last = self.unknown.len().saturating_sub(1);
+                        self.state.select(Some((selected + 1).min(last)));
+                    }
+                    HashReportResponse::Handled
+                }
+                KeyCode::Enter => match self.state.selected() {
+                    Some(selected) => HashReportResponse::Crack(self.unknown[selected].hash),
+                    None => HashReportResponse::Handled,
+                },
+                KeyCode::Char('e') => {
+                    self.message = Some(
+                        match write_unknown_hashes(UNKNOWN_HASHES_FILE, &self.unknown) {
+                            Ok(()) => format!("Exported to {}", UNKNOWN_HASHES_FILE),
+                            Err(err) => format!("Export failed: {}", err),
+                        },
+                    );
+                    HashReportResponse::Handled
+                }
+                KeyCode::Esc | KeyCode::Backspace => HashReportResponse::Exit,
+                _ => HashReportResponse::None,
+            }
+        } else {
+            HashReportResponse::None
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Unlabeled hashes ({})", self.unknown.len()),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(rect);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+
+        let help = self
+            .message
+            .clone()
+            .unwrap_or_else(|| String::from("Enter: search for a label, e: export as CSV"));
+        let rows = self
+            .unknown
+            .iter()
+            .map(|entry| {
+                let first = entry.paths[0].to_string();
+                let paths = match entry.paths.len() {
+                    1 => first,
+                    len => format!("{} (+{} more)", first, len - 1),
+                };
+                Row::new(vec![
+                    format!("0x{:010x}", entry.hash.0),
+                    entry.paths.len().to_string(),
+                    paths,
+                ])
+            })
+            .collect::<Vec<_>>();
+        let table = Table::new(rows)
+            .header(
+                Row::new(vec!["hash", "count", "paths"])
+                    .style(Style::default().fg(self.theme.hint)),
+            )
+            .widths(&[
+                Constraint::Length(12),
+                Constraint::Length(6),
+                Constraint::Percentage(100),
+            ])
+            .column_spacing(1)
+            .highlight_style(Style::default().bg(self.theme.highlight));
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(block, rect, buffer);
+        Widget::render(Paragraph::new(help), areas[0], buffer);
+        StatefulWidget::render(table, areas[1], buffer, &mut self.state);
+    }
+}
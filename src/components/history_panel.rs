@@ -0,0 +1,184 @@
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event};
+
+use super::param::keyed_value;
+use crate::path::ParamPath;
+use crate::settings::Settings;
+use crate::theme::Theme;
+use crate::undo::EditEntry;
+use crate::utils::modulo::{add_mod, sub_mod};
+
+/// A panel listing the edits which can be undone or redone, to go to the params they changed or
+/// to bring the file back to how it was after any of them
+#[derive(Debug)]
+pub struct HistoryPanel {
+    entries: Vec<EditEntry>,
+    /// How many of the entries are made. The rest have been undone
+    made: usize,
+    /// The row selected. The first row is the file before any of the edits
+    selected: usize,
+    offset: usize,
+    settings: Settings,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub enum HistoryPanelResponse {
+    None,
+    Handled,
+    /// The user chose to go to the param the selected edit changed
+    Open(ParamPath),
+    /// Undoing this many edits brings the file back to the selected point
+    Undo(usize),
+    /// Redoing this many edits brings the file forward to the selected point
+    Redo(usize),
+    Exit,
+}
+
+impl HistoryPanel {
+    /// Starts with the latest edit made selected
+    pub fn new(entries: Vec<EditEntry>, made: usize, settings: Settings, theme: Theme) -> Self {
+        Self {
+            entries,
+            made,
+            selected: made,
+            offset: 0,
+            settings,
+            theme,
+        }
+    }
+
+    /// Lists the edits again after some were undone or redone, keeping the same row selected
+    pub fn update(&mut self, entries: Vec<EditEntry>, made: usize) {
+        self.entries = entries;
+        self.made = made;
+        self.selected = self.selected.min(self.entries.len());
+    }
+
+    fn line(&self, row: usize) -> Spans<'_> {
+        let base = if row == self.selected {
+            Style::default().bg(self.theme.highlight)
+        } else {
+            Style::default()
+        };
+        // the edits after the current point are greyed out, like they're waiting to be redone
+        let style = if row > self.made {
+            base.fg(self.theme.hint)
+        } else {
+            base
+        };
+        let marker = if row == self.made { "> " } else { "  " };
+        let entry = match row.checked_sub(1).and_then(|index| self.entries.get(index)) {
+            Some(entry) => entry,
+            None => {
+                return Spans::from(vec![
+                    Span::styled(marker, base.fg(self.theme.accent)),
+                    Span::styled("Before the oldest edit kept", style.fg(self.theme.hint)),
+                ])
+            }
+        };
+        let change = match &entry.values {
+            Some((old, new)) => {
+                let value = |param| {
+                    keyed_value(
+                        entry.path.key(),
+                        param,
+                        self.settings.hash_display,
+                        self.settings.float_format,
+                    )
+                };
+                format!("{} -> {}", value(old), value(new))
+            }
+            None if entry.count > 1 => format!("{} param(s)", entry.count),
+            None => String::new(),
+        };
+        // most descriptions already name the param
+        let path = entry.path.to_string();
+        let path = if entry.description.contains(&path) {
+            String::new()
+        } else {
+            format!("  {}", path)
+        };
+        Spans::from(vec![
+            Span::styled(marker, base.fg(self.theme.accent)),
+            Span::styled(entry.description.as_str(), style),
+            Span::styled(path, style.fg(self.theme.hint)),
+            Span::styled("  ", style),
+            Span::styled(change, style),
+        ])
+    }
+}
+
+impl Component for HistoryPanel {
+    type Response = HistoryPanelResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return HistoryPanelResponse::None,
+        };
+        let len = self.entries.len() + 1;
+        match key.code {
+            KeyCode::Up => self.selected = sub_mod(self.selected, 1, len),
+            KeyCode::Down => self.selected = add_mod(self.selected, 1, len),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = len - 1,
+            KeyCode::Enter => {
+                return match self
+                    .selected
+                    .checked_sub(1)
+                    .map(|index| &self.entries[index])
+                {
+                    Some(entry) if !entry.path.0.is_empty() => {
+                        HistoryPanelResponse::Open(entry.path.clone())
+                    }
+                    _ => HistoryPanelResponse::None,
+                }
+            }
+            KeyCode::Char('r') => {
+                return if self.selected < self.made {
+                    HistoryPanelResponse::Undo(self.made - self.selected)
+                } else if self.selected > self.made {
+                    HistoryPanelResponse::Redo(self.selected - self.made)
+                } else {
+                    HistoryPanelResponse::None
+                }
+            }
+            KeyCode::Esc => return HistoryPanelResponse::Exit,
+            _ => return HistoryPanelResponse::None,
+        }
+        HistoryPanelResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Edit history ({})", self.entries.len()),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(rect);
+        let height = inner.height as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let lines = (0..=self.entries.len())
+            .skip(self.offset)
+            .take(height)
+            .map(|row| self.line(row))
+            .collect::<Vec<_>>();
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(Paragraph::new(lines).block(block), rect, buffer);
+    }
+}
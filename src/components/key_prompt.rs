@@ -0,0 +1,87 @@
+use prc::hash40::Hash40;
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::Span;
+use tui_components::tui::widgets::{Block, Borders, Clear, Widget};
+use tui_components::{Component, Event};
+
+use crate::theme::Theme;
+
+use super::hash_input::{HashInput, HashInputResponse};
+
+/// The widest the prompt gets
+const PROMPT_WIDTH: u16 = 60;
+
+/// Asks for the key to give a param in a struct, suggesting labels as it's typed
+#[derive(Debug)]
+pub struct KeyPrompt {
+    input: HashInput,
+    title: String,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum KeyPromptResponse {
+    None,
+    Handled,
+    Submit(Hash40),
+    Cancel,
+}
+
+impl KeyPrompt {
+    pub fn new<T: Into<String>>(title: T, input: HashInput, theme: Theme) -> Self {
+        Self {
+            input,
+            title: title.into(),
+            theme,
+        }
+    }
+}
+
+impl Component for KeyPrompt {
+    type Response = KeyPromptResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        match self.input.handle_event(event) {
+            HashInputResponse::Submit => KeyPromptResponse::Submit(self.input.value()),
+            HashInputResponse::Cancel => KeyPromptResponse::Cancel,
+            HashInputResponse::Handled => KeyPromptResponse::Handled,
+            HashInputResponse::None => KeyPromptResponse::None,
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                self.title.as_str(),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let width = PROMPT_WIDTH.min(rect.width);
+        let suggestions = self.input.suggestions_height();
+        let area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width,
+            height: 3 + suggestions,
+        });
+        let area = Rect { height: 3, ..area };
+        let inner = block.inner(area);
+        Widget::render(Clear, area, buffer);
+        Widget::render(block, area, buffer);
+        self.input.draw(inner, buffer);
+        let below = Rect {
+            y: area.bottom(),
+            height: suggestions.min(rect.bottom().saturating_sub(area.bottom())),
+            ..area
+        };
+        // a border on each side and at least one suggestion
+        if below.height >= 3 {
+            self.input.draw_suggestions(below, buffer);
+        }
+    }
+}
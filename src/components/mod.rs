@@ -1,4 +1,32 @@
+pub mod arc_prompt;
+pub mod busy;
+pub mod command_line;
+pub mod confirm;
+pub mod details;
 pub mod empty;
+pub mod enum_picker;
+pub mod error_popup;
+pub mod explorer;
+pub mod expr_input;
+pub mod filter;
+pub mod find_replace;
+pub mod hash_cracker;
 pub mod hash_input;
+pub mod hash_report;
+pub mod history_panel;
+pub mod key_prompt;
+pub mod note_prompt;
+pub mod notification;
+pub mod palette;
 pub mod param;
+pub mod path_list;
+pub mod preview;
 pub mod root;
+pub mod script_input;
+pub mod snapshot_panel;
+pub mod stats_panel;
+pub mod status_bar;
+pub mod structure_panel;
+pub mod transform_input;
+pub mod tree_view;
+pub mod warnings;
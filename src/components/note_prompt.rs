@@ -0,0 +1,76 @@
+use tui_components::components::{Input, InputResponse};
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::Span;
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::theme::Theme;
+
+/// The widest the prompt gets
+const PROMPT_WIDTH: u16 = 80;
+
+/// Asks for the note to attach to a param, starting from the one it has
+#[derive(Debug)]
+pub struct NotePrompt {
+    input: Input,
+    title: String,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub enum NotePromptResponse {
+    None,
+    Handled,
+    Submit(String),
+    Cancel,
+}
+
+impl NotePrompt {
+    pub fn new<T: Into<String>>(title: T, note: Option<&str>, theme: Theme) -> Self {
+        let mut input = Input::default().editing_style(Style::default().bg(theme.highlight));
+        input.value = note.unwrap_or_default().to_owned();
+        input.focused = true;
+        Self {
+            input,
+            title: title.into(),
+            theme,
+        }
+    }
+}
+
+impl Component for NotePrompt {
+    type Response = NotePromptResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        match self.input.handle_event(event) {
+            InputResponse::Submit => NotePromptResponse::Submit(self.input.value.clone()),
+            InputResponse::Cancel => NotePromptResponse::Cancel,
+            InputResponse::Edited { .. } => NotePromptResponse::Handled,
+            InputResponse::None => NotePromptResponse::None,
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                self.title.as_str(),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width: PROMPT_WIDTH.min(rect.width),
+            height: 3,
+        });
+        let inner = block.inner(area);
+        Widget::render(Clear, area, buffer);
+        Widget::render(block, area, buffer);
+        Widget::render(Paragraph::new(self.input.get_spans()), inner, buffer);
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::{Color, Style};
+use tui_components::tui::widgets::{Paragraph, Widget};
+use tui_components::{Component, Event};
+
+use crate::theme::Theme;
+
+/// How long a finished notification stays on screen
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(3);
+
+/// A short message in the bottom right corner, like the result of saving a file
+#[derive(Debug)]
+pub struct Notification {
+    message: String,
+    kind: NotificationKind,
+    shown: Instant,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationKind {
+    /// Something is still in progress, so the notification stays until it's replaced
+    Progress,
+    /// Offers the user a choice, so the notification stays until they make it
+    Prompt,
+    Info,
+    Error,
+}
+
+impl Notification {
+    pub fn new(message: String, kind: NotificationKind, theme: Theme) -> Self {
+        Self {
+            message,
+            kind,
+            shown: Instant::now(),
+            theme,
+        }
+    }
+
+    pub fn kind(&self) -> NotificationKind {
+        self.kind
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_in().is_some_and(|left| left.is_zero())
+    }
+
+    /// How long until the notification goes away, or None if it stays until it's replaced
+    pub fn expires_in(&self) -> Option<Duration> {
+        match self.kind {
+            NotificationKind::Progress | NotificationKind::Prompt => None,
+            _ => Some(NOTIFICATION_DURATION.saturating_sub(self.shown.elapsed())),
+        }
+    }
+}
+
+impl Component for Notification {
+    type Response = ();
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, _event: Event) -> Self::Response {}
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) -> Self::DrawResponse {
+        let text = format!(" {} ", self.message);
+        let width = (text.chars().count() as u16).min(rect.width);
+        if rect.height == 0 {
+            return;
+        }
+        let area = Rect {
+            x: rect.x + rect.width - width,
+            y: rect.y + rect.height - 1,
+            width,
+            height: 1,
+        };
+        let style = match self.kind {
+            NotificationKind::Progress => Style::default().fg(Color::Black).bg(self.theme.accent),
+            NotificationKind::Prompt => Style::default().fg(Color::Black).bg(self.theme.warning),
+            NotificationKind::Info => Style::default().fg(Color::Black).bg(self.theme.success),
+            NotificationKind::Error => Style::default().fg(Color::White).bg(self.theme.error),
+        };
+        Widget::render(Paragraph::new(text).style(style), area, buffer);
+    }
+}
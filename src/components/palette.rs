@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use tui_components::components::Input;
+use tui_components::crossterm::event::{KeyCode, KeyModifiers};
+use tui_components::rect_ext::RectExt;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::plugin::{Exporter, Plugin, PluginCommand};
+use crate::theme::Theme;
+use crate::utils::modulo::{add_mod, sub_mod};
+
+/// The widest the palette gets
+const PALETTE_WIDTH: u16 = 70;
+/// The most entries listed at once
+const PALETTE_ROWS: u16 = 12;
+
+/// Something the palette can run
+#[derive(Debug, Clone)]
+pub enum PaletteEntry {
+    Command(Arc<dyn PluginCommand>),
+    /// Exporting the open file with a plugin's format
+    Export(Arc<dyn Exporter>),
+}
+
+impl PaletteEntry {
+    pub fn label(&self) -> String {
+        match self {
+            PaletteEntry::Command(command) => command.name().to_owned(),
+            PaletteEntry::Export(exporter) => format!("Export as {}", exporter.name()),
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            PaletteEntry::Command(command) => command.description().to_owned(),
+            PaletteEntry::Export(exporter) => format!("a .{} file", exporter.extension()),
+        }
+    }
+}
+
+/// Lists what the plugins add to the editor, narrowed down by typing part of a name
+#[derive(Debug)]
+pub struct Palette {
+    input: Input,
+    entries: Vec<PaletteEntry>,
+    /// The indices of the entries matching what's typed
+    rows: Vec<usize>,
+    selected: usize,
+    offset: usize,
+    theme: Theme,
+}
+
+#[derive(Debug)]
+pub enum PaletteResponse {
+    None,
+    Handled,
+    Run(PaletteEntry),
+    Cancel,
+}
+
+impl Palette {
+    pub fn new(plugins: &[Arc<dyn Plugin>], theme: Theme) -> Self {
+        let mut input = Input::default().editing_style(Style::default().bg(theme.highlight));
+        input.focused = true;
+        let entries = plugins
+            .iter()
+            .flat_map(|plugin| {
+                let commands = plugin.commands().into_iter().map(PaletteEntry::Command);
+                let exporters = plugin.exporters().into_iter().map(PaletteEntry::Export);
+                commands.chain(exporters).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        Self {
+            input,
+            rows: (0..entries.len()).collect(),
+            entries,
+            selected: 0,
+            offset: 0,
+            theme,
+        }
+    }
+
+    fn filter_rows(&mut self) {
+        let text = self.input.value.to_lowercase();
+        self.rows = (0..self.entries.len())
+            .filter(|index| self.entries[*index].label().to_lowercase().contains(&text))
+            .collect();
+        self.selected = 0;
+    }
+}
+
+impl Component for Palette {
+    type Response = PaletteResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return PaletteResponse::None,
+        };
+        let len = self.rows.len();
+        match key.code {
+            KeyCode::Char(_) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return PaletteResponse::None
+            }
+            KeyCode::Up if len > 0 => self.selected = sub_mod(self.selected, 1, len),
+            KeyCode::Down if len > 0 => self.selected = add_mod(self.selected, 1, len),
+            KeyCode::Enter => {
+                if let Some(index) = self.rows.get(self.selected) {
+                    return PaletteResponse::Run(self.entries[*index].clone());
+                }
+            }
+            KeyCode::Esc => return PaletteResponse::Cancel,
+            KeyCode::Char(_) | KeyCode::Backspace => {
+                self.input.handle_event(event);
+                self.filter_rows();
+            }
+            _ => return PaletteResponse::None,
+        }
+        PaletteResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                "Commands",
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let rows = (self.rows.len() as u16).clamp(1, PALETTE_ROWS);
+        let area = rect.centered(Rect {
+            x: 0,
+            y: 0,
+            width: PALETTE_WIDTH.min(rect.width),
+            // the borders, the input, and a line between it and the entries
+            height: (rows + 4).min(rect.height),
+        });
+        let inner = block.inner(area);
+        let input_area = Rect { height: 1, ..inner };
+        let list_area = Rect {
+            y: inner.y + 2,
+            height: inner.height.saturating_sub(2),
+            ..inner
+        };
+
+        let height = list_area.height as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let lines = if self.rows.is_empty() {
+            let message = if self.entries.is_empty() {
+                "No plugins add any commands"
+            } else {
+                "No commands match"
+            };
+            vec![Spans::from(Span::styled(
+                message,
+                Style::default().fg(self.theme.hint),
+            ))]
+        } else {
+            self.rows
+                .iter()
+                .enumerate()
+                .skip(self.offset)
+                .take(height)
+                .map(|(row, index)| {
+                    let entry = &self.entries[*index];
+                    let style = if row == self.selected {
+                        Style::default().bg(self.theme.highlight)
+                    } else {
+                        Style::default()
+                    };
+                    Spans::from(vec![
+                        Span::styled(entry.label(), style),
+                        Span::styled("  ", style),
+                        Span::styled(entry.description(), style.fg(self.theme.hint)),
+                    ])
+                })
+                .collect()
+        };
+
+        Widget::render(Clear, area, buffer);
+        Widget::render(block, area, buffer);
+        Widget::render(Paragraph::new(self.input.get_spans()), input_area, buffer);
+        Widget::render(Paragraph::new(lines), list_area, buffer);
+    }
+}
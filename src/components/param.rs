@@ -1,40 +1,170 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Display;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use prc::{hash40::Hash40, ParamKind, ParamList, ParamStruct};
+use rayon::prelude::*;
 use tui_components::components::num_input::{
     FloatInput, NumInputResponse, SignedIntInput, UnsignedIntInput,
 };
 use tui_components::components::{Input, InputResponse, FALSE_CHAR, TRUE_CHAR};
-use tui_components::crossterm::event::KeyCode;
+use tui_components::crossterm::event::{
+    KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use tui_components::tui::buffer::Buffer;
 use tui_components::tui::layout::{Constraint, Rect};
 use tui_components::tui::style::{Color, Style};
-use tui_components::tui::text::Spans;
+use tui_components::tui::text::{Span, Spans};
 use tui_components::tui::widgets::{Block, Borders, Row, StatefulWidget, Table, Widget};
 use tui_components::Event;
 use tui_components::Spannable;
 use tui_components::{tui::widgets::TableState, Component};
+use unicode_width::UnicodeWidthChar;
 
+use crate::enums::{enum_name, enum_options, int_value, with_int};
+use crate::history::{HistoryField, InputHistory, Recall};
+use crate::keys::Key;
+use crate::labels::label_changes;
+use crate::path::{ParamPath, PathSegment, PARALLEL_MIN_CHILDREN};
+use crate::replace::{apply_replacements, Replacement};
+use crate::report::hash_usage;
+use crate::session::LevelSession;
+use crate::settings::{hex_pair, FloatFormat, HashDisplay, Settings};
+use crate::shared::SharedParam;
+use crate::sort::SortOrder;
+use crate::theme::Theme;
+use crate::undo::{Change, EditEntry, UndoHistory};
 use crate::utils::modulo::{add_mod, sub_mod};
+use crate::utils::mouse::{ClickTracker, SCROLL_ROWS};
+use crate::utils::paste::{clipboard_text, is_paste, paste_events};
+use crate::utils::type_ahead::TypeAhead;
+use crate::utils::vim::{is_plain, vim_code};
+use crate::value::{blank_value, format_value, param_type, parse_value, step_float};
 
+use super::enum_picker::{EnumPicker, EnumPickerResponse};
+use super::expr_input::{ExprInput, ExprInputResponse};
+use super::filter::{Filter, FilterResponse};
 use super::hash_input::{HashInput, HashInputResponse};
 
 const MIN_PARAM_TABLE_WIDTH: u16 = 10;
+/// How many rows around the visible ones are measured for the column widths,
+/// so the columns don't change size on every scroll step
+const WIDTH_MARGIN: usize = 64;
 
 #[derive(Debug)]
 pub struct Param {
-    param: ParamParent,
+    /// The whole file, which every level reads from and edits in place
+    root: SharedParam,
+    /// The position of each child leading from the root to this level
+    path: Vec<usize>,
     state: TableState,
     selected: Option<Box<SelectedParam>>,
     sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+    settings: Arc<Mutex<Settings>>,
+    history: Arc<Mutex<InputHistory>>,
+    /// The edits made to the file, shared by every level
+    undo: Arc<Mutex<UndoHistory>>,
+    /// Where the filter or value being edited is in its history, if stepping through it
+    recall: Recall,
+    filter: Option<Box<Filter>>,
+    filter_editing: bool,
+    /// What's kept about each list or struct while it's closed, shared by every level
+    levels: Arc<Mutex<LevelMemory>>,
+    /// The indices of the children shown in the table, after filtering and sorting
+    rows: Vec<usize>,
+    /// The order the rows are shown in, which doesn't change the file until it's applied
+    sort: SortOrder,
+    /// Which children differ from the file being compared against, if any
+    marks: Vec<bool>,
+    /// The edit count of the file and the version of it the marks were last made against, so
+    /// they're only made again once either changes
+    marked_against: Option<Box<(usize, Arc<ParamStruct>)>>,
+    /// The first row shown in the table. Only the visible rows are built when drawing
+    offset: usize,
+    /// Where the rows were last drawn on screen, or None if the column was hidden
+    area: Option<Rect>,
+    clicks: ClickTracker,
+    /// The start of a name being typed to jump to
+    type_ahead: TypeAhead,
+    /// The name and value of each child as they're shown, kept between draws and filters
+    texts: Box<RowTexts>,
 }
 
-#[derive(Debug, Clone)]
-pub enum ParamParent {
-    List(ParamList),
-    Struct(ParamStruct),
+/// The text of a level's children, each built the first time it's drawn, filtered, or typed
+/// ahead to, so a large level isn't formatted again on every key. It's built again once the
+/// file or labels change, or hashes and floats are shown differently
+#[derive(Debug, Default)]
+struct RowTexts {
+    edits: usize,
+    label_changes: usize,
+    hashes: HashDisplay,
+    floats: FloatFormat,
+    names: Vec<OnceLock<Arc<str>>>,
+    values: Vec<OnceLock<Arc<str>>>,
+}
+
+impl RowTexts {
+    fn new(
+        len: usize,
+        edits: usize,
+        label_changes: usize,
+        hashes: HashDisplay,
+        floats: FloatFormat,
+    ) -> Self {
+        Self {
+            edits,
+            label_changes,
+            hashes,
+            floats,
+            names: (0..len).map(|_| OnceLock::new()).collect(),
+            values: (0..len).map(|_| OnceLock::new()).collect(),
+        }
+    }
+
+    fn name(&self, child: usize, index: ParentIndex) -> Arc<str> {
+        self.names[child]
+            .get_or_init(|| index.name(self.hashes))
+            .clone()
+    }
+
+    fn value(&self, child: usize, index: ParentIndex, param: &ParamKind) -> Arc<str> {
+        self.values[child]
+            .get_or_init(|| keyed_value(index.key(), param, self.hashes, self.floats).into())
+            .clone()
+    }
+}
+
+/// What's kept about each list or struct while it isn't open
+#[derive(Debug, Default)]
+struct LevelMemory {
+    /// The filter kept at each level, so leaving a level and coming back to it brings its
+    /// filter back
+    filters: HashMap<ParamPath, String>,
+    /// How each level was shown when it was closed, by the positions leading to it, so going
+    /// back into a large list or struct doesn't filter and sort its rows all over again
+    views: HashMap<Vec<usize>, LevelView>,
+}
+
+/// The rows of a closed level, and where it was scrolled to
+#[derive(Debug)]
+struct LevelView {
+    /// How many edits the file had. The rows are out of date once it's edited again
+    edits: usize,
+    /// The filter the rows were filtered with
+    filter: Option<String>,
+    hashes: HashDisplay,
+    floats: FloatFormat,
+    rows: Vec<usize>,
+    sort: SortOrder,
+    selected: Option<usize>,
+    offset: usize,
+}
+
+/// A list or struct whose children are shown in one column
+#[derive(Debug, Clone, Copy)]
+pub enum ParamParent<'a> {
+    List(&'a ParamList),
+    Struct(&'a ParamStruct),
 }
 
 #[derive(Debug)]
@@ -46,27 +176,395 @@ pub enum SelectedParam {
     I32(SignedIntInput<i32>),
     U32(UnsignedIntInput<u32>),
     Float(FloatInput<f32>),
+    /// A number being edited as arithmetic, once an operator has been typed
+    Expr(ExprInput),
     Hash(HashInput),
+    /// An integer whose key's values have names, picked by name
+    Enum(EnumPicker),
     Str(Input),
     NewLevel(Param),
 }
 
+impl SelectedParam {
+    /// The value typed so far, as text which `parse_value` reads back
+    fn text(&self) -> String {
+        match self {
+            SelectedParam::I8(int) => int.value().to_string(),
+            SelectedParam::U8(int) => int.value().to_string(),
+            SelectedParam::I16(int) => int.value().to_string(),
+            SelectedParam::U16(int) => int.value().to_string(),
+            SelectedParam::I32(int) => int.value().to_string(),
+            SelectedParam::U32(int) => int.value().to_string(),
+            SelectedParam::Float(val) => val.value().to_string(),
+            SelectedParam::Hash(hash) => hash.text().to_owned(),
+            SelectedParam::Enum(picker) => picker.value().to_string(),
+            SelectedParam::Expr(expr) => expr.text().to_owned(),
+            SelectedParam::Str(str) => str.value.clone(),
+            SelectedParam::NewLevel(_) => String::new(),
+        }
+    }
+}
+
 impl Param {
-    pub fn new(param: ParamParent, sorted_labels: Arc<Mutex<BTreeSet<String>>>) -> Self {
+    pub fn new<P: Into<Arc<ParamStruct>>>(
+        param: P,
+        sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+        settings: Arc<Mutex<Settings>>,
+        history: Arc<Mutex<InputHistory>>,
+    ) -> Self {
+        Self::with_path(
+            SharedParam::new(param),
+            vec![],
+            sorted_labels,
+            settings,
+            history,
+            Arc::default(),
+            Arc::default(),
+        )
+    }
+
+    fn with_path(
+        root: SharedParam,
+        path: Vec<usize>,
+        sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+        settings: Arc<Mutex<Settings>>,
+        history: Arc<Mutex<InputHistory>>,
+        undo: Arc<Mutex<UndoHistory>>,
+        levels: Arc<Mutex<LevelMemory>>,
+    ) -> Self {
+        let len = root.with(|root| ParamParent::find(root, &path).len());
         let mut state = TableState::default();
-        if !param.is_empty() {
+        if len > 0 {
             state.select(Some(0));
         }
-        Self {
-            param,
+        let mut param = Self {
+            root,
+            path,
             state,
             selected: None,
             sorted_labels,
+            settings,
+            history,
+            undo,
+            recall: Recall::default(),
+            filter: None,
+            filter_editing: false,
+            levels,
+            rows: (0..len).collect(),
+            sort: SortOrder::default(),
+            marks: vec![],
+            marked_against: None,
+            offset: 0,
+            area: None,
+            clicks: ClickTracker::default(),
+            type_ahead: TypeAhead::default(),
+            texts: Box::default(),
+        };
+        let own_path = param.own_path();
+        let (kept, view) = match param.levels.lock() {
+            Ok(mut levels) => {
+                let kept = levels.filters.get(&own_path).cloned();
+                let view = levels.views.remove(&param.path);
+                (kept, view)
+            }
+            Err(_) => (None, None),
+        };
+        let Settings {
+            hash_display: hashes,
+            float_format: floats,
+            ..
+        } = param.settings();
+        match (kept, view) {
+            // the rows are the same as when the level was closed
+            (kept, Some(view))
+                if view.edits == param.root.edits()
+                    && view.filter == kept
+                    && view.hashes == hashes
+                    && view.floats == floats =>
+            {
+                param.restore_view(view)
+            }
+            (Some(text), _) => param.set_filter(&text),
+            (None, _) => {}
+        }
+        param
+    }
+
+    /// Shows the rows kept from when the level was last closed
+    fn restore_view(&mut self, view: LevelView) {
+        if let Some(text) = &view.filter {
+            self.filter = Some(Box::new(
+                Filter::with_text(text, self.settings().theme).with_history(self.history.clone()),
+            ));
+        }
+        self.rows = view.rows;
+        self.sort = view.sort;
+        self.state.select(view.selected);
+        self.offset = view.offset;
+    }
+
+    /// Keeps the rows of this level and the ones open below it, for when they're opened again
+    fn keep_views(&self) {
+        if let Some(SelectedParam::NewLevel(next)) = self.selected.as_deref() {
+            next.keep_views();
+        }
+        let Settings {
+            hash_display: hashes,
+            float_format: floats,
+            ..
+        } = self.settings();
+        let edits = self.root.edits();
+        let view = LevelView {
+            edits,
+            filter: self
+                .filter
+                .as_deref()
+                .filter(|filter| filter.expr().is_some())
+                .map(|filter| filter.text().to_owned()),
+            hashes,
+            floats,
+            rows: self.rows.clone(),
+            sort: self.sort,
+            selected: self.state.selected(),
+            offset: self.offset,
+        };
+        if let Ok(mut levels) = self.levels.lock() {
+            // the views kept before an edit are never used
+            levels.views.retain(|_, view| view.edits == edits);
+            levels.views.insert(self.path.clone(), view);
+        }
+    }
+
+    /// Starts with the filters kept from before, like in the file's sidecar, so each list or
+    /// struct is filtered again when it's opened
+    pub fn with_filters(mut self, filters: HashMap<ParamPath, String>) -> Self {
+        if let Some(text) = filters.get(&ParamPath::default()) {
+            self.set_filter(text);
+        }
+        if let Ok(mut levels) = self.levels.lock() {
+            levels.filters = filters;
+        }
+        self
+    }
+
+    /// The filter kept for each list or struct, by its path
+    pub fn level_filters(&self) -> HashMap<ParamPath, String> {
+        self.levels
+            .lock()
+            .map(|levels| levels.filters.clone())
+            .unwrap_or_default()
+    }
+
+    /// Filters the rows with the given text, as if it had been typed and kept
+    fn set_filter(&mut self, text: &str) {
+        self.filter = Some(Box::new(
+            Filter::with_text(text, self.settings().theme).with_history(self.history.clone()),
+        ));
+        self.update_rows();
+    }
+
+    /// The path of this level's list or struct, which its filter is remembered by
+    fn own_path(&self) -> ParamPath {
+        self.root.with(|root| {
+            let segments = (0..self.path.len())
+                .map(
+                    |end| match ParamParent::find(root, &self.path[..end]).index(self.path[end]) {
+                        ParentIndex::List(index) => PathSegment::Index(index),
+                        ParentIndex::Struct(hash) => PathSegment::Key(hash),
+                    },
+                )
+                .collect();
+            ParamPath(segments)
+        })
+    }
+
+    /// Remembers this level's filter, or that it has none, for when the level is opened again
+    fn keep_filter(&self) {
+        if let Ok(mut levels) = self.levels.lock() {
+            let filters = &mut levels.filters;
+            match self
+                .filter
+                .as_deref()
+                .filter(|filter| filter.expr().is_some())
+            {
+                Some(filter) => filters.insert(self.own_path(), filter.text().to_owned()),
+                None => filters.remove(&self.own_path()),
+            };
+        }
+    }
+
+    /// Whether each open level has a filter, from the top level down
+    pub fn filtered_levels(&self) -> Vec<bool> {
+        let mut levels = vec![self.filter.is_some()];
+        if let Some(SelectedParam::NewLevel(next)) = self.selected.as_deref() {
+            levels.extend(next.filtered_levels());
+        }
+        levels
+    }
+
+    /// The file as it is now, including any edits
+    pub fn snapshot(&self) -> Arc<ParamStruct> {
+        self.root.snapshot()
+    }
+
+    /// Runs a function on the list or struct shown in this level
+    fn with_level<R, F: FnOnce(ParamParent) -> R>(&self, f: F) -> R {
+        self.root
+            .with(|root| f(ParamParent::find(root, &self.path)))
+    }
+
+    /// Runs a function on the nth child of this level, for editing it
+    fn with_child_mut<F: FnOnce(&mut ParamKind)>(&self, n: usize, f: F) {
+        let mut positions = self.path.clone();
+        positions.push(n);
+        self.record_edit(&positions, |path| format!("Edited {}", path));
+        self.root.with_mut(|root| {
+            if let Some(child) = child_mut(root, &self.path, n) {
+                f(child)
+            }
+        })
+    }
+
+    /// Remembers the param at the positions as it is now, so the edit about to be made to it
+    /// can be undone
+    fn record_edit<F: FnOnce(&ParamPath) -> String>(&self, positions: &[usize], describe: F) {
+        let (description, change) = self.root.with(|root| {
+            let path = position_path(root, positions);
+            (describe(&path), Change::of(root, path))
+        });
+        if let Ok(mut undo) = self.undo.lock() {
+            undo.push(description, change.into_iter().collect());
+        }
+    }
+
+    /// Reverses the latest edit, returning its description, or None if there's nothing to undo
+    pub fn undo(&mut self) -> Option<String> {
+        let undo = self.undo.clone();
+        let description = self.root.with_mut(|root| undo.lock().ok()?.undo(root))?;
+        self.refresh_rows();
+        Some(description)
+    }
+
+    /// Makes the latest undone edit again, returning its description
+    pub fn redo(&mut self) -> Option<String> {
+        let undo = self.undo.clone();
+        let description = self.root.with_mut(|root| undo.lock().ok()?.redo(root))?;
+        self.refresh_rows();
+        Some(description)
+    }
+
+    /// The edits which can be undone or redone, oldest first, and how many of them are made
+    pub fn edit_history(&self) -> (Vec<EditEntry>, usize) {
+        let undo = self.undo.clone();
+        self.root.with(|root| {
+            undo.lock()
+                .map(|undo| undo.entries(root))
+                .unwrap_or_default()
+        })
+    }
+
+    /// The index of the selected child within the param, accounting for filtered rows
+    fn selected_index(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|row| self.rows.get(row).copied())
+    }
+
+    /// Re-applies the filter to the children, keeping the same child selected if it's still shown
+    fn update_rows(&mut self) {
+        let len = self.with_level(|level| level.len());
+        self.filter_rows(0..len);
+    }
+
+    /// Re-applies the filter to only the rows already shown, for when it can only have become
+    /// stricter. This keeps typing a filter responsive in large lists
+    fn narrow_rows(&mut self) {
+        let rows = self.rows.clone();
+        self.filter_rows(rows.into_iter());
+    }
+
+    /// Keeps the candidate children matching the filter, checking large levels on several
+    /// threads at once, like `visit_par`
+    fn filter_rows<I: Iterator<Item = usize>>(&mut self, candidates: I) {
+        let current = self.selected_index();
+        let hashes = self.settings().hash_display;
+        self.refresh_texts();
+        let texts = &self.texts;
+        let expr = self.filter.as_ref().and_then(|filter| filter.expr());
+        let sort = self.sort;
+        let rows = self.with_level(|level| {
+            let mut rows = match expr {
+                Some(expr) => {
+                    let matches = |child: &usize| {
+                        let (index, param) = level.child(*child);
+                        expr.matches(
+                            || texts.name(*child, index),
+                            param,
+                            || texts.value(*child, index, param),
+                        )
+                    };
+                    let candidates = candidates.collect::<Vec<_>>();
+                    if candidates.len() < PARALLEL_MIN_CHILDREN {
+                        candidates.into_iter().filter(matches).collect()
+                    } else {
+                        candidates.into_par_iter().filter(matches).collect()
+                    }
+                }
+                None => (0..level.len()).collect(),
+            };
+            sort.sort(level, &mut rows, hashes);
+            rows
+        });
+        self.rows = rows;
+        let row = current
+            .and_then(|current| self.rows.iter().position(|child| *child == current))
+            .or(if self.rows.is_empty() { None } else { Some(0) });
+        self.state.select(row);
+    }
+
+    /// Scrolls the table the least amount needed to show the selected row
+    fn scroll_to_selected(&mut self, height: usize) {
+        let max_offset = self.rows.len().saturating_sub(height);
+        if let Some(selected) = self.state.selected() {
+            if selected < self.offset {
+                self.offset = selected;
+            } else if selected >= self.offset + height {
+                self.offset = selected + 1 - height;
+            }
+        }
+        self.offset = self.offset.min(max_offset);
+    }
+
+    /// Forgets the text of the rows if it's out of date
+    fn refresh_texts(&mut self) {
+        let Settings {
+            hash_display: hashes,
+            float_format: floats,
+            ..
+        } = self.settings();
+        let edits = self.root.edits();
+        let label_changes = label_changes();
+        let len = self.with_level(|level| level.len());
+        let texts = &self.texts;
+        if texts.names.len() != len
+            || texts.edits != edits
+            || texts.label_changes != label_changes
+            || texts.hashes != hashes
+            || texts.floats != floats
+        {
+            *self.texts = RowTexts::new(len, edits, label_changes, hashes, floats);
         }
     }
 
+    fn settings(&self) -> Settings {
+        self.settings
+            .lock()
+            .map(|settings| *settings)
+            .unwrap_or_default()
+    }
+
     fn down(&mut self) {
-        let len = self.param.len();
+        let len = self.rows.len();
         if len > 0 {
             match self.state.selected() {
                 Some(selected) => self.state.select(Some(add_mod(selected, 1, len))),
@@ -78,7 +576,7 @@ impl Param {
     }
 
     fn up(&mut self) {
-        let len = self.param.len();
+        let len = self.rows.len();
         if len > 0 {
             match self.state.selected() {
                 Some(selected) => self.state.select(Some(sub_mod(selected, 1, len))),
@@ -90,97 +588,477 @@ impl Param {
     }
 
     fn enter(&mut self) -> bool {
-        if let Some(selected) = self.state.selected() {
-            match self.param.nth_mut(selected) {
-                ParamKind::List(list) => {
-                    let taken = std::mem::take(list);
-                    let new_param =
-                        Param::new(ParamParent::List(taken), self.sorted_labels.clone());
-                    self.selected = Some(Box::new(SelectedParam::NewLevel(new_param)));
-                }
-                ParamKind::Struct(str) => {
-                    let taken = std::mem::take(str);
-                    let new_param =
-                        Param::new(ParamParent::Struct(taken), self.sorted_labels.clone());
-                    self.selected = Some(Box::new(SelectedParam::NewLevel(new_param)));
-                }
-                ParamKind::Bool(val) => {
+        let selected = match self.selected_index() {
+            Some(selected) => selected,
+            None => return false,
+        };
+        let input = self.root.with(|root| {
+            let level = ParamParent::find(root, &self.path);
+            let (index, param) = level.child(selected);
+            self.value_input(index.key(), param, root)
+        });
+        if let Some(input) = input {
+            self.recall = Recall::default();
+            self.selected = Some(Box::new(input));
+        } else if self.is_parent(selected) {
+            let mut path = self.path.clone();
+            path.push(selected);
+            let new_param = Param::with_path(
+                self.root.clone(),
+                path,
+                self.sorted_labels.clone(),
+                self.settings.clone(),
+                self.history.clone(),
+                self.undo.clone(),
+                self.levels.clone(),
+            );
+            self.selected = Some(Box::new(SelectedParam::NewLevel(new_param)));
+        } else {
+            self.with_child_mut(selected, |param| {
+                if let ParamKind::Bool(val) = param {
                     *val = !*val;
-                    return true;
-                }
-                ParamKind::I8(int) => {
-                    self.selected = Some(Box::new(SelectedParam::I8(SignedIntInput::new(*int))));
-                }
-                ParamKind::U8(int) => {
-                    self.selected = Some(Box::new(SelectedParam::U8(UnsignedIntInput::new(*int))));
-                }
-                ParamKind::I16(int) => {
-                    self.selected = Some(Box::new(SelectedParam::I16(SignedIntInput::new(*int))));
-                }
-                ParamKind::U16(int) => {
-                    self.selected = Some(Box::new(SelectedParam::U16(UnsignedIntInput::new(*int))));
-                }
-                ParamKind::I32(int) => {
-                    self.selected = Some(Box::new(SelectedParam::I32(SignedIntInput::new(*int))));
-                }
-                ParamKind::U32(int) => {
-                    self.selected = Some(Box::new(SelectedParam::U32(UnsignedIntInput::new(*int))));
-                }
-                ParamKind::Float(val) => {
-                    self.selected = Some(Box::new(SelectedParam::Float(
-                        FloatInput::new(*val).unwrap(),
-                    )));
                 }
-                ParamKind::Str(str) => {
-                    let mut input = Input::default();
-                    input.value = str.clone();
-                    input.focused = true;
-                    self.selected = Some(Box::new(SelectedParam::Str(input)));
-                }
-                ParamKind::Hash(hash) => {
-                    self.selected = Some(Box::new(SelectedParam::Hash(HashInput::new(
-                        *hash,
-                        self.sorted_labels.clone(),
-                    ))))
-                }
-            }
+            });
+            return true;
         }
         false
     }
 
+    /// Flips the selected param if it's a bool, returning whether it was
+    fn toggle_bool(&mut self) -> bool {
+        let selected = match self.selected_index() {
+            Some(selected) => selected,
+            None => return false,
+        };
+        if !self.with_level(|level| matches!(level.nth(selected), ParamKind::Bool(_))) {
+            return false;
+        }
+        self.with_child_mut(selected, |param| {
+            if let ParamKind::Bool(val) = param {
+                *val = !*val;
+            }
+        });
+        true
+    }
+
+    /// An input for editing a value, starting from the param's current value.
+    /// Lists, structs, and bools aren't edited by typing, so they have none, while integers whose
+    /// key's values have names are picked from them
+    fn value_input(
+        &self,
+        key: Option<Hash40>,
+        param: &ParamKind,
+        root: &ParamStruct,
+    ) -> Option<SelectedParam> {
+        if let (Some(value), Some(options)) = (int_value(param), key.and_then(enum_options)) {
+            let picker = EnumPicker::new(value, options, self.settings().theme);
+            return Some(SelectedParam::Enum(picker));
+        }
+        match param {
+            ParamKind::List(_) | ParamKind::Struct(_) | ParamKind::Bool(_) => None,
+            ParamKind::I8(int) => Some(SelectedParam::I8(SignedIntInput::new(*int))),
+            ParamKind::U8(int) => Some(SelectedParam::U8(UnsignedIntInput::new(*int))),
+            ParamKind::I16(int) => Some(SelectedParam::I16(SignedIntInput::new(*int))),
+            ParamKind::U16(int) => Some(SelectedParam::U16(UnsignedIntInput::new(*int))),
+            ParamKind::I32(int) => Some(SelectedParam::I32(SignedIntInput::new(*int))),
+            ParamKind::U32(int) => Some(SelectedParam::U32(UnsignedIntInput::new(*int))),
+            ParamKind::Float(val) => Some(SelectedParam::Float(FloatInput::new(*val).unwrap())),
+            ParamKind::Str(str) => {
+                let mut input = Input::default();
+                input.value = str.clone();
+                input.focused = true;
+                Some(SelectedParam::Str(input))
+            }
+            ParamKind::Hash(hash) => Some(SelectedParam::Hash(HashInput::new(
+                *hash,
+                self.sorted_labels.clone(),
+                &hash_usage(root),
+                self.settings().theme,
+            ))),
+        }
+    }
+
+    /// The history field of the nth child's value
+    fn value_field(&self, n: usize) -> HistoryField {
+        self.with_level(|level| {
+            let (index, param) = level.child(n);
+            let key = match index {
+                ParentIndex::List(_) => None,
+                ParentIndex::Struct(hash) => Some(hash),
+            };
+            HistoryField::Value {
+                key,
+                ty: param_type(param),
+            }
+        })
+    }
+
+    /// Replaces the value being edited with an older entry from its history,
+    /// or a newer one if not going back
+    fn recall(&mut self, back: bool) {
+        let history = self.history.clone();
+        let history = match history.lock() {
+            Ok(history) => history,
+            Err(err) => err.into_inner(),
+        };
+        let index = match self.selected_index() {
+            Some(index) => index,
+            None => return,
+        };
+        let field = self.value_field(index);
+        let text = if back {
+            let current = self
+                .selected
+                .as_deref()
+                .map(SelectedParam::text)
+                .unwrap_or_default();
+            self.recall.older(&history, field, &current)
+        } else {
+            self.recall.newer(&history, field)
+        };
+        // entries are parsed as the field's type, so the input for it can start from the value
+        let key = self.with_level(|level| level.index(index).key());
+        let param = text
+            .and_then(|text| self.with_level(|level| parse_value(level.nth(index), &text).ok()));
+        let input =
+            param.and_then(|param| self.root.with(|root| self.value_input(key, &param, root)));
+        if let Some(input) = input {
+            self.selected = Some(Box::new(input));
+        }
+    }
+
+    /// Whether typing the key switches the number being edited in this level to arithmetic.
+    /// Operators do for any number, and `x` does for integers, to type them in hex
+    fn starts_expr(&self, key: char) -> bool {
+        match self.selected.as_deref() {
+            Some(
+                SelectedParam::I8(_)
+                | SelectedParam::U8(_)
+                | SelectedParam::I16(_)
+                | SelectedParam::U16(_)
+                | SelectedParam::I32(_)
+                | SelectedParam::U32(_),
+            ) => true,
+            Some(SelectedParam::Float(_)) => key != 'x',
+            _ => false,
+        }
+    }
+
+    /// Switches the number being edited to arithmetic, starting with the key just typed.
+    /// If the number hadn't been changed yet, an operator applies to the current value,
+    /// while the `x` of a hex prefix always follows the digits typed before it
+    fn start_expr(&mut self, key: char) {
+        let index = match self.selected_index() {
+            Some(index) => index,
+            None => return,
+        };
+        let current = self.with_level(|level| level.nth(index).clone());
+        let typed = self
+            .selected
+            .as_deref()
+            .map(SelectedParam::text)
+            .unwrap_or_default();
+        let text = if key != 'x' && typed == format_value(&current) {
+            key.to_string()
+        } else {
+            format!("{}{}", typed, key)
+        };
+        let Settings {
+            float_format,
+            theme,
+            ..
+        } = self.settings();
+        let input = ExprInput::new(text, current, float_format, theme);
+        self.selected = Some(Box::new(SelectedParam::Expr(input)));
+    }
+
+    /// Shows the rows in the next sort order
+    fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.update_rows();
+    }
+
+    /// Reorders the children in the file to match the sort order, and goes back to showing them
+    /// in file order. Returns whether anything was reordered
+    fn apply_sort(&mut self) -> bool {
+        if self.sort == SortOrder::File {
+            return false;
+        }
+        // anything open below this level was found by its old position
+        self.exit(false);
+        let current = self.selected_index();
+        let hashes = self.settings().hash_display;
+        let sort = self.sort;
+        let order = self.with_level(|level| {
+            let mut order = (0..level.len()).collect();
+            sort.sort(level, &mut order, hashes);
+            order
+        });
+        self.record_edit(&self.path, |path| {
+            format!("Sorted {}", describe_level(path))
+        });
+        self.root.with_mut(|root| match self.path.split_last() {
+            None => reorder(&mut root.0, &order),
+            Some((last, parent)) => match child_mut(root, parent, *last) {
+                Some(ParamKind::List(list)) => reorder(&mut list.0, &order),
+                Some(ParamKind::Struct(str)) => reorder(&mut str.0, &order),
+                _ => {}
+            },
+        });
+        self.sort = SortOrder::File;
+        self.marks.clear();
+        self.update_rows();
+        let row = current.and_then(|current| order.iter().position(|child| *child == current));
+        let row = row.and_then(|row| self.rows.iter().position(|child| *child == row));
+        if row.is_some() {
+            self.state.select(row);
+        }
+        true
+    }
+
+    /// The selected child, if it's a struct in a list which new entries can be copied from
+    fn template(&self) -> Option<usize> {
+        let selected = self.selected_index()?;
+        self.with_level(|level| match level {
+            ParamParent::List(list) => matches!(list.0[selected], ParamKind::Struct(_)),
+            ParamParent::Struct(_) => false,
+        })
+        .then_some(selected)
+    }
+
+    /// Adds a struct after the selected one in a list, with the same keys and types
+    /// but zeroed values, and selects it. Returns whether anything was inserted
+    fn insert_from_template(&mut self) -> bool {
+        let template = match self.template() {
+            Some(template) => template,
+            None => return false,
+        };
+        let (last, parent) = match self.path.split_last() {
+            Some(split) => split,
+            None => return false,
+        };
+        self.record_edit(&self.path, |path| format!("Inserted into {}", path));
+        self.root.with_mut(|root| {
+            if let Some(ParamKind::List(list)) = child_mut(root, parent, *last) {
+                let entry = blank_value(&list.0[template]);
+                list.0.insert(template + 1, entry);
+            }
+        });
+        self.marks.clear();
+        self.update_rows();
+        // the new entry is shown even if it doesn't match the filter
+        let inserted = template + 1;
+        if !self.rows.contains(&inserted) {
+            self.rows.push(inserted);
+        }
+        let row = self.rows.iter().position(|row| *row == inserted);
+        self.state.select(row);
+        true
+    }
+
+    /// Whether the list or struct shown in the last column is a list, whose params have no key
+    pub fn in_list(&self) -> bool {
+        if let Some(SelectedParam::NewLevel(next)) = self.selected.as_deref() {
+            return next.in_list();
+        }
+        self.with_level(|level| matches!(level, ParamParent::List(_)))
+    }
+
+    /// Adds a param after the selected one in the last column and selects it. In a struct it's
+    /// added under the key, unless the struct already has a param with that key. Returns whether
+    /// anything was added
+    pub fn graft(&mut self, key: Option<Hash40>, param: ParamKind, source: &str) -> bool {
+        if let Some(next) = self.next_mut() {
+            return next.graft(key, param, source);
+        }
+        let fits = self.with_level(|level| match (level, key) {
+            (ParamParent::List(_), _) => true,
+            (ParamParent::Struct(str), Some(key)) => str.0.iter().all(|(child, _)| *child != key),
+            (ParamParent::Struct(_), None) => false,
+        });
+        if !fits {
+            return false;
+        }
+        let at = self.selected_index().map_or(0, |index| index + 1);
+        self.record_edit(&self.path, |path| {
+            format!("Imported {} into {}", source, describe_level(path))
+        });
+        self.root
+            .with_mut(|root| match (self.path.split_last(), key) {
+                (None, Some(key)) => root.0.insert(at, (key, param)),
+                (Some((last, parent)), key) => match (child_mut(root, parent, *last), key) {
+                    (Some(ParamKind::List(list)), _) => list.0.insert(at, param),
+                    (Some(ParamKind::Struct(str)), Some(key)) => str.0.insert(at, (key, param)),
+                    _ => {}
+                },
+                (None, None) => {}
+            });
+        self.marks.clear();
+        self.update_rows();
+        // the new param is shown even if it doesn't match the filter
+        if !self.rows.contains(&at) {
+            self.rows.push(at);
+        }
+        let row = self.rows.iter().position(|row| *row == at);
+        self.state.select(row);
+        true
+    }
+
+    /// Whether the nth child is a list or struct, which can be opened in a new column
+    fn is_parent(&self, n: usize) -> bool {
+        self.with_level(|level| matches!(level.nth(n), ParamKind::List(_) | ParamKind::Struct(_)))
+    }
+
     /// Removes selection from the current param.
     /// If the selected param was a value, update_value determines whether or not we update it
     fn exit(&mut self, update_value: bool) {
-        if let Some(index) = self.state.selected() {
+        if let Some(index) = self.selected_index() {
             if let Some(selected) = self.selected.take() {
-                let nth = self.param.nth_mut(index);
-                if let SelectedParam::NewLevel(level) = *selected {
-                    match level.param {
-                        ParamParent::List(list) => *nth = list.into(),
-                        ParamParent::Struct(str) => *nth = str.into(),
+                if let SelectedParam::NewLevel(next) = selected.as_ref() {
+                    next.keep_views();
+                }
+                let value: Option<ParamKind> = match *selected {
+                    _ if !update_value => None,
+                    SelectedParam::NewLevel(..) => None,
+                    SelectedParam::I8(int) => Some(int.value().into()),
+                    SelectedParam::U8(int) => Some(int.value().into()),
+                    SelectedParam::I16(int) => Some(int.value().into()),
+                    SelectedParam::U16(int) => Some(int.value().into()),
+                    SelectedParam::I32(int) => Some(int.value().into()),
+                    SelectedParam::U32(int) => Some(int.value().into()),
+                    SelectedParam::Float(val) => Some(val.value().into()),
+                    SelectedParam::Hash(hash) => Some(hash.value().into()),
+                    SelectedParam::Enum(picker) => {
+                        self.with_level(|level| with_int(level.nth(index), picker.value()))
                     }
-                } else if update_value {
-                    match *selected {
-                        SelectedParam::NewLevel(..) => unreachable!(),
-                        SelectedParam::I8(int) => *nth = int.value().into(),
-                        SelectedParam::U8(int) => *nth = int.value().into(),
-                        SelectedParam::I16(int) => *nth = int.value().into(),
-                        SelectedParam::U16(int) => *nth = int.value().into(),
-                        SelectedParam::I32(int) => *nth = int.value().into(),
-                        SelectedParam::U32(int) => *nth = int.value().into(),
-                        SelectedParam::Float(val) => *nth = val.value().into(),
-                        SelectedParam::Hash(hash) => *nth = hash.value().into(),
-                        SelectedParam::Str(str) => *nth = str.value.into(),
+                    SelectedParam::Expr(expr) => Some(expr.value()),
+                    SelectedParam::Str(str) => Some(str.value.into()),
+                };
+                if let Some(value) = value {
+                    let field = self.value_field(index);
+                    if let Ok(mut history) = self.history.lock() {
+                        history.push(field, format_value(&value));
+                    }
+                    if self.with_level(|level| *level.nth(index) != value) {
+                        self.with_child_mut(index, |nth| *nth = value);
                     }
                 }
             }
         }
     }
 
-    fn next(&self) -> Option<&Param> {
+    /// The level whose rows are drawn at the given cell, if any
+    fn level_at(&mut self, column: u16, row: u16) -> Option<&mut Param> {
+        let contains = self.area.is_some_and(|area| {
+            column >= area.x
+                && column < area.x + area.width
+                && row >= area.y
+                && row < area.y + area.height
+        });
+        if contains {
+            Some(self)
+        } else {
+            self.next_mut()?.level_at(column, row)
+        }
+    }
+
+    /// Moves where this level and every level below it were drawn, once the columns
+    /// to their left have been laid out
+    fn shift_areas(&mut self, x: u16) {
+        if let Some(area) = &mut self.area {
+            area.x += x;
+        }
+        if let Some(next) = self.next_mut() {
+            next.shift_areas(x);
+        }
+    }
+
+    /// Selects a row in this level, closing anything open below it if the row changed
+    fn select_row(&mut self, row: usize) {
+        if self.state.selected() != Some(row) {
+            self.exit(false);
+            self.state.select(Some(row));
+        }
+    }
+
+    /// Adds the letter to the name being typed, and selects the first row whose name starts
+    /// with it
+    fn type_ahead(&mut self, c: char) {
+        self.refresh_texts();
+        let prefix = self.type_ahead.push(c).to_owned();
+        let rows = &self.rows;
+        let texts = &self.texts;
+        let found = self.with_level(|level| {
+            rows.iter().position(|child| {
+                texts
+                    .name(*child, level.index(*child))
+                    .to_lowercase()
+                    .starts_with(&prefix)
+            })
+        });
+        if let Some(row) = found {
+            self.select_row(row);
+        }
+    }
+
+    /// Moves the selection by as many rows as fit in the level, stopping at either end
+    fn page(&mut self, down: bool) {
+        let last = match self.rows.len().checked_sub(1) {
+            Some(last) => last,
+            None => return,
+        };
+        let page = self.area.map_or(1, |area| (area.height as usize).max(1));
+        let selected = self.state.selected().unwrap_or(0);
+        self.select_row(if down {
+            (selected + page).min(last)
+        } else {
+            selected.saturating_sub(page)
+        });
+    }
+
+    /// Handles the mouse over the level under the cursor. A click selects a row, closing
+    /// anything open below that level, and a double click enters the row like pressing Enter.
+    /// The scroll wheel moves the selection a few rows at a time
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> ParamResponse {
+        let level = match self.level_at(mouse.column, mouse.row) {
+            Some(level) => level,
+            None => return ParamResponse::None,
+        };
+        let last = match level.rows.len().checked_sub(1) {
+            Some(last) => last,
+            None => return ParamResponse::Handled { edited: false },
+        };
+        let selected = level.state.selected().unwrap_or(0);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let top = level.area.map_or(0, |area| area.y);
+                let row = level.offset + (mouse.row - top) as usize;
+                if row > last {
+                    return ParamResponse::Handled { edited: false };
+                }
+                let double = level.clicks.click(mouse.column, mouse.row);
+                level.select_row(row);
+                let edited = double && level.selected.is_none() && level.enter();
+                ParamResponse::Handled { edited }
+            }
+            MouseEventKind::ScrollUp => {
+                level.select_row(selected.saturating_sub(SCROLL_ROWS));
+                ParamResponse::Handled { edited: false }
+            }
+            MouseEventKind::ScrollDown => {
+                level.select_row((selected + SCROLL_ROWS).min(last));
+                ParamResponse::Handled { edited: false }
+            }
+            _ => ParamResponse::None,
+        }
+    }
+
+    /// Whether a value or filter is being typed at the deepest level
+    fn is_typing(&self) -> bool {
         match self.selected.as_deref() {
-            Some(SelectedParam::NewLevel(level)) => Some(level),
-            _ => None,
+            Some(SelectedParam::NewLevel(next)) => next.is_typing(),
+            Some(_) => true,
+            None => self.filter_editing,
         }
     }
 
@@ -191,6 +1069,377 @@ impl Param {
         }
     }
 
+    /// The hash under the cursor at the deepest level: the selected param's value if it's a hash,
+    /// otherwise its key if the level is a struct
+    pub fn selected_hash(&self) -> Option<Hash40> {
+        if let Some(SelectedParam::NewLevel(level)) = self.selected.as_deref() {
+            return level.selected_hash();
+        }
+        let index = self.selected_index()?;
+        self.with_level(|level| match (level, level.nth(index)) {
+            (_, ParamKind::Hash(hash)) => Some(*hash),
+            (ParamParent::Struct(str), _) => Some(str.0[index].0),
+            (ParamParent::List(_), _) => None,
+        })
+    }
+
+    /// What's known about the selected param at the deepest level which has a selection
+    pub fn selected_details(&self) -> Option<ParamDetails> {
+        if let Some(SelectedParam::NewLevel(level)) = self.selected.as_deref() {
+            if let Some(details) = level.selected_details() {
+                return Some(details);
+            }
+        }
+        let index = self.selected_index()?;
+        let Settings {
+            hash_display: hashes,
+            float_format: floats,
+            ..
+        } = self.settings();
+        Some(self.with_level(|level| {
+            let (key, param) = level.child(index);
+            let (value, children) = match param {
+                ParamKind::List(list) => (None, Some(list.0.len())),
+                ParamKind::Struct(str) => (None, Some(str.0.len())),
+                _ => (Some(keyed_value(key.key(), param, hashes, floats)), None),
+            };
+            ParamDetails {
+                key,
+                ty: param_type(param),
+                value,
+                value_hash: match param {
+                    ParamKind::Hash(hash) => Some(*hash),
+                    _ => None,
+                },
+                children,
+            }
+        }))
+    }
+
+    /// The path of the selected param, through every open level
+    pub fn selected_path(&self) -> ParamPath {
+        let mut path = ParamPath::default();
+        self.push_selected_path(&mut path);
+        path
+    }
+
+    fn push_selected_path(&self, path: &mut ParamPath) {
+        if let Some(index) = self.selected_index() {
+            let segment = self.with_level(|level| match level.index(index) {
+                ParentIndex::List(index) => PathSegment::Index(index),
+                ParentIndex::Struct(hash) => PathSegment::Key(hash),
+            });
+            path.0.push(segment);
+            if let Some(SelectedParam::NewLevel(next)) = self.selected.as_deref() {
+                next.push_selected_path(path);
+            }
+        }
+    }
+
+    /// The paths of the rows shown in the last column, after filtering
+    pub fn shown_paths(&self) -> Vec<ParamPath> {
+        if let Some(SelectedParam::NewLevel(next)) = self.selected.as_deref() {
+            return next.shown_paths();
+        }
+        self.root.with(|root| {
+            let base = position_path(root, &self.path);
+            let level = ParamParent::find(root, &self.path);
+            self.rows
+                .iter()
+                .map(|row| {
+                    let mut path = base.clone();
+                    path.0.push(match level.index(*row) {
+                        ParentIndex::List(index) => PathSegment::Index(index),
+                        ParentIndex::Struct(hash) => PathSegment::Key(hash),
+                    });
+                    path
+                })
+                .collect()
+        })
+    }
+
+    /// The path of the list or struct shown in the last column, which is empty at the top level
+    pub fn level_path(&self) -> ParamPath {
+        let mut path = ParamPath::default();
+        self.push_level_path(&mut path);
+        path
+    }
+
+    fn push_level_path(&self, path: &mut ParamPath) {
+        if let (Some(SelectedParam::NewLevel(next)), Some(index)) =
+            (self.selected.as_deref(), self.selected_index())
+        {
+            let segment = self.with_level(|level| match level.index(index) {
+                ParentIndex::List(index) => PathSegment::Index(index),
+                ParentIndex::Struct(hash) => PathSegment::Key(hash),
+            });
+            path.0.push(segment);
+            next.push_level_path(path);
+        }
+    }
+
+    /// Sets the replaced values in the file, returning how many were set.
+    /// The rows of every level are filtered and sorted again, since the values they show changed
+    pub fn replace_values(&mut self, description: String, replacements: &[Replacement]) -> usize {
+        let changes = self.root.with(|root| {
+            replacements
+                .iter()
+                .filter(|replacement| replacement.new.is_ok())
+                .filter_map(|replacement| Change::of(root, replacement.path.clone()))
+                .collect()
+        });
+        if let Ok(mut undo) = self.undo.lock() {
+            undo.push(description, changes);
+        }
+        let applied = self
+            .root
+            .with_mut(|root| apply_replacements(root, replacements));
+        self.refresh_rows();
+        applied
+    }
+
+    /// Puts other params in place of the whole file, like going back to a snapshot, as an edit
+    /// which can be undone
+    pub fn replace_root(&mut self, description: String, root: Arc<ParamStruct>) {
+        let change = self
+            .root
+            .with(|current| Change::of(current, ParamPath::default()));
+        if let Ok(mut undo) = self.undo.lock() {
+            undo.push(description, change.into_iter().collect());
+        }
+        self.root.replace(root);
+        self.refresh_rows();
+    }
+
+    fn refresh_rows(&mut self) {
+        self.update_rows();
+        if let Some(next) = self.next_mut() {
+            next.refresh_rows();
+        }
+    }
+
+    /// Closes everything open below the level at the given depth, where 0 is the top level
+    pub fn close_below(&mut self, depth: usize) {
+        match depth.checked_sub(1) {
+            None => self.exit(false),
+            Some(depth) => {
+                if let Some(next) = self.next_mut() {
+                    next.close_below(depth);
+                }
+            }
+        }
+    }
+
+    /// The keys which do something at the deepest level, for the status bar.
+    /// The save key is configurable, so it's passed in
+    pub fn key_hints(&self, save: Key) -> String {
+        match self.selected.as_deref() {
+            Some(SelectedParam::NewLevel(next)) => next.key_hints(save),
+            Some(SelectedParam::Float(_)) => {
+                "Enter: confirm  Left/Right: step  Ctrl+Up/Down: history  Esc: cancel".into()
+            }
+            Some(SelectedParam::Enum(_)) => {
+                "Up/Down: choose  Enter: confirm  Ctrl+Up/Down: history  Esc: cancel".into()
+            }
+            Some(_) => "Enter: confirm  Ctrl+Up/Down: history  Esc: cancel".into(),
+            None if self.filter_editing => {
+                "Enter: keep filter  Up/Down: history  Esc: clear filter".into()
+            }
+            None => {
+                let insert = if self.template().is_some() {
+                    "i: insert from template  "
+                } else {
+                    ""
+                };
+                if self.sort != SortOrder::File {
+                    format!(
+                        "Enter: open/edit  Backspace: back  /: filter  'name: jump  s: sort  S: apply sort  {}Esc: quit",
+                        insert
+                    )
+                } else {
+                    format!(
+                        "Enter: open/edit  Backspace: back  /: filter  'name: jump  s: sort  {}{}: save  Esc: quit",
+                        insert, save
+                    )
+                }
+            }
+        }
+    }
+
+    /// The selection and filter of this level and every open level after it
+    pub fn session(&self) -> Vec<LevelSession> {
+        let mut levels = vec![LevelSession {
+            selected: self.selected_index(),
+            filter: self.filter.as_ref().map(|filter| filter.text().to_owned()),
+        }];
+        if let Some(SelectedParam::NewLevel(next)) = self.selected.as_deref() {
+            levels.extend(next.session());
+        }
+        levels
+    }
+
+    /// Re-applies a saved session, stopping at any level which no longer matches the file
+    pub fn restore(&mut self, levels: &[LevelSession]) {
+        let (level, rest) = match levels.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        if let Some(text) = &level.filter {
+            self.set_filter(text);
+            self.keep_filter();
+        }
+        let row = level
+            .selected
+            .and_then(|selected| self.rows.iter().position(|row| *row == selected));
+        if row.is_none() {
+            return;
+        }
+        self.state.select(row);
+        if rest.is_empty() || self.selected.is_some() {
+            return;
+        }
+        if let Some(index) = self.selected_index() {
+            if self.is_parent(index) {
+                self.enter();
+                if let Some(next) = self.next_mut() {
+                    next.restore(rest);
+                }
+            }
+        }
+    }
+
+    /// Moves to the same place as the other param, matching struct children by key and list
+    /// children by index, and marks the children which differ between the two at each level
+    pub fn follow(&mut self, other: &mut Param) {
+        self.marks = self.with_level(|level| other.with_level(|other| level.marks(other)));
+        other.marks = other.with_level(|level| self.with_level(|other| level.marks(other)));
+        self.marked_against = None;
+        other.marked_against = None;
+
+        let other_entered = other.entered_index();
+        let matching = other.selected_index().and_then(|index| {
+            let index = other.with_level(|level| level.index(index));
+            self.with_level(|level| level.position(index))
+        });
+        if self.selected.is_some() && (other_entered.is_none() || self.entered_index() != matching)
+        {
+            self.exit(false);
+        }
+        let row = matching.and_then(|matching| self.rows.iter().position(|row| *row == matching));
+        self.state.select(row);
+        if let (Some(_), Some(matching), None) = (other_entered, matching, &self.selected) {
+            if self.is_parent(matching) {
+                self.enter();
+            }
+        }
+        if let (Some(next), Some(other_next)) = (self.next_mut(), other.next_mut()) {
+            next.follow(other_next);
+        }
+    }
+
+    /// Opens every level down to the param at the path and selects it, clearing any filter
+    /// which hides part of the way there
+    pub fn reveal(&mut self, path: &[PathSegment]) {
+        let (segment, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        let index = match segment {
+            PathSegment::Key(hash) => ParentIndex::Struct(*hash),
+            PathSegment::Index(index) => ParentIndex::List(*index),
+        };
+        let position = match self.with_level(|level| level.position(index)) {
+            Some(position) => position,
+            None => return,
+        };
+        if !self.rows.contains(&position) {
+            self.filter = None;
+            self.filter_editing = false;
+            self.keep_filter();
+            self.update_rows();
+        }
+        if let Some(row) = self.rows.iter().position(|row| *row == position) {
+            self.select_row(row);
+        }
+        if rest.is_empty() || !self.is_parent(position) {
+            return;
+        }
+        if self.entered_index().is_none() {
+            self.exit(false);
+            self.enter();
+        }
+        if let Some(next) = self.next_mut() {
+            next.reveal(rest);
+        }
+    }
+
+    /// Marks the children which differ from the same params in another version of the file,
+    /// like the saved one, at every open level
+    pub fn mark_against(&mut self, other: &Arc<ParamStruct>) {
+        let edits = self.root.edits();
+        let current = matches!(
+            &self.marked_against,
+            Some(marked) if marked.0 == edits && Arc::ptr_eq(&marked.1, other)
+        );
+        if !current {
+            self.mark_level(other);
+            self.marked_against = Some(Box::new((edits, other.clone())));
+        }
+        if let Some(next) = self.next_mut() {
+            next.mark_against(other);
+        }
+    }
+
+    fn mark_level(&mut self, other: &ParamStruct) {
+        self.marks = self.root.with(|root| {
+            let level = ParamParent::find(root, &self.path);
+            let path = position_path(root, &self.path);
+            let other_level = match path.get(other) {
+                _ if path.0.is_empty() => Some(ParamParent::Struct(other)),
+                Some(ParamKind::List(list)) => Some(ParamParent::List(list)),
+                Some(ParamKind::Struct(str)) => Some(ParamParent::Struct(str)),
+                _ => None,
+            };
+            match other_level {
+                Some(other_level) => level.marks(other_level),
+                None => vec![true; level.len()],
+            }
+        });
+    }
+
+    /// Removes the filter of the last column, showing every row again with the same one selected.
+    /// Returns whether there was a filter
+    pub fn clear_filter(&mut self) -> bool {
+        if let Some(next) = self.next_mut() {
+            return next.clear_filter();
+        }
+        if self.filter.is_none() {
+            return false;
+        }
+        self.filter = None;
+        self.filter_editing = false;
+        self.keep_filter();
+        self.update_rows();
+        true
+    }
+
+    /// Removes the comparison marks from every level
+    pub fn clear_marks(&mut self) {
+        self.marks.clear();
+        self.marked_against = None;
+        if let Some(next) = self.next_mut() {
+            next.clear_marks();
+        }
+    }
+
+    /// The index of the child which is open in the next column, if any
+    fn entered_index(&self) -> Option<usize> {
+        match self.selected.as_deref() {
+            Some(SelectedParam::NewLevel(_)) => self.selected_index(),
+            _ => None,
+        }
+    }
+
     fn get_selected_span<'a, 'b: 'a>(&'a self) -> Option<(usize, Spans<'b>)> {
         self.state
             .selected()
@@ -205,42 +1454,133 @@ impl Param {
                     SelectedParam::U32(int) => int.get_spans(),
                     SelectedParam::Float(val) => val.get_spans(),
                     SelectedParam::Hash(hash) => hash.get_spans(),
+                    SelectedParam::Enum(picker) => picker.get_spans(),
+                    SelectedParam::Expr(expr) => expr.get_spans(),
                     SelectedParam::Str(str) => str.get_spans(),
-                    SelectedParam::NewLevel(param) => match &param.param {
-                        ParamParent::List(list) => {
-                            Spans::from(format!("({} children)", list.0.len()))
-                        }
-                        ParamParent::Struct(str) => {
-                            Spans::from(format!("({} children)", str.0.len()))
-                        }
-                    },
+                    SelectedParam::NewLevel(param) => {
+                        let len = param.with_level(|level| level.len());
+                        Spans::from(format!("({} children)", len))
+                    }
                 };
                 (index, spans)
             })
     }
+}
 
-    pub fn recreate_param(&self) -> ParamKind {
-        match &self.param {
-            ParamParent::List(list) => {
-                let mut level = list.clone();
-                let index = self.state.selected();
-                let selected = &self.selected.as_deref();
-                if let Some((SelectedParam::NewLevel(children), index)) = selected.zip(index) {
-                    level.0[index] = children.recreate_param();
-                }
-                level.into()
-            }
-            ParamParent::Struct(str) => {
-                let mut level = str.clone();
-                let index = self.state.selected();
-                let selected = self.selected.as_deref();
-                if let Some((SelectedParam::NewLevel(children), index)) = selected.zip(index) {
-                    level.0[index].1 = children.recreate_param();
-                }
-                level.into()
-            }
+/// Moves each child to its position in the order, where order[n] is the old position of the
+/// child which ends up at n
+fn reorder<T>(children: &mut Vec<T>, order: &[usize]) {
+    let mut old = std::mem::take(children)
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+    children.extend(order.iter().filter_map(|position| old[*position].take()));
+}
+
+/// Ctrl+Up steps back through the history of whatever is being edited, and Ctrl+Down forward.
+/// Returns whether the key goes back, or None for any other event
+fn recall_key(event: Event) -> Option<bool> {
+    match event {
+        Event::Key(key) if key.modifiers.contains(KeyModifiers::CONTROL) => match key.code {
+            KeyCode::Up => Some(true),
+            KeyCode::Down => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Left and Right step a float being edited down and up, ten times as far with Shift.
+/// Returns how many steps the key moves, or None for any other event
+fn slide_key(event: Event) -> Option<f32> {
+    let key = match event {
+        Event::Key(key) => key,
+        _ => return None,
+    };
+    let steps = match key.code {
+        KeyCode::Left => -1.0,
+        KeyCode::Right => 1.0,
+        _ => return None,
+    };
+    if key.modifiers.contains(KeyModifiers::SHIFT) {
+        Some(steps * 10.0)
+    } else {
+        Some(steps)
+    }
+}
+
+/// The keys which start arithmetic while editing a number. Minus isn't one of them,
+/// since the number inputs already use it to flip the sign
+fn expr_key(event: Event) -> Option<char> {
+    match event {
+        Event::Key(key) if !key.modifiers.contains(KeyModifiers::CONTROL) => match key.code {
+            KeyCode::Char(c @ ('+' | '*' | '/' | '(' | 'x')) => Some(c),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+static EMPTY_STRUCT: ParamStruct = ParamStruct(Vec::new());
+
+/// The path of the param at the positions from the root
+fn position_path(root: &ParamStruct, positions: &[usize]) -> ParamPath {
+    let mut path = ParamPath::default();
+    let mut level = ParamParent::Struct(root);
+    for position in positions {
+        if *position >= level.len() {
+            break;
         }
+        path.0.push(match level.index(*position) {
+            ParentIndex::List(index) => PathSegment::Index(index),
+            ParentIndex::Struct(hash) => PathSegment::Key(hash),
+        });
+        level = match level.get(*position) {
+            Some(ParamKind::List(list)) => ParamParent::List(list),
+            Some(ParamKind::Struct(str)) => ParamParent::Struct(str),
+            _ => break,
+        };
+    }
+    path
+}
+
+/// How a level's path is written in an edit's description
+fn describe_level(path: &ParamPath) -> String {
+    if path.0.is_empty() {
+        String::from("the file")
+    } else {
+        path.to_string()
+    }
+}
+
+/// Follows the path of child positions from the root, for editing the nth child at the end of it
+fn child_mut<'a>(root: &'a mut ParamStruct, path: &[usize], n: usize) -> Option<&'a mut ParamKind> {
+    let (first, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return root.0.get_mut(n).map(|(_, child)| child),
+    };
+    let mut param = &mut root.0.get_mut(*first)?.1;
+    for position in rest.iter().chain(std::iter::once(&n)) {
+        param = match param {
+            ParamKind::List(list) => list.0.get_mut(*position)?,
+            ParamKind::Struct(str) => &mut str.0.get_mut(*position)?.1,
+            _ => return None,
+        };
     }
+    Some(param)
+}
+
+/// The selected param, as shown in the details pane
+#[derive(Debug, Clone)]
+pub struct ParamDetails {
+    pub key: ParentIndex,
+    pub ty: &'static str,
+    /// The whole value, or None for lists and structs
+    pub value: Option<String>,
+    /// The value, if it's a hash
+    pub value_hash: Option<Hash40>,
+    /// The number of children, for lists and structs
+    pub children: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -249,39 +1589,58 @@ pub enum ParentIndex {
     Struct(Hash40),
 }
 
-impl ParamParent {
-    pub fn children_mut(&mut self) -> Vec<(ParentIndex, &mut ParamKind)> {
+impl<'a> ParamParent<'a> {
+    /// Follows the path of child positions from the root. If the path no longer leads to a list
+    /// or struct, the level is shown as empty
+    pub fn find(root: &'a ParamStruct, path: &[usize]) -> Self {
+        let mut level = ParamParent::Struct(root);
+        for position in path {
+            level = match level.get(*position) {
+                Some(ParamKind::List(list)) => ParamParent::List(list),
+                Some(ParamKind::Struct(str)) => ParamParent::Struct(str),
+                _ => return ParamParent::Struct(&EMPTY_STRUCT),
+            };
+        }
+        level
+    }
+
+    /// The nth child and its index within the parent, without collecting every child
+    pub fn child(&self, n: usize) -> (ParentIndex, &'a ParamKind) {
         match self {
-            ParamParent::List(list) => list
-                .0
-                .iter_mut()
-                .enumerate()
-                .map(|(index, param)| (ParentIndex::List(index), param))
-                .collect(),
-            ParamParent::Struct(str) => str
-                .0
-                .iter_mut()
-                .map(|(hash, param)| (ParentIndex::Struct(*hash), param))
-                .collect(),
+            ParamParent::List(list) => (ParentIndex::List(n), &list.0[n]),
+            ParamParent::Struct(str) => (ParentIndex::Struct(str.0[n].0), &str.0[n].1),
         }
     }
 
-    pub fn children(&self) -> Vec<(ParentIndex, &ParamKind)> {
+    /// The index of the nth child within its parent
+    pub fn index(&self, n: usize) -> ParentIndex {
         match self {
-            ParamParent::List(list) => list
-                .0
-                .iter()
-                .enumerate()
-                .map(|(index, param)| (ParentIndex::List(index), param))
-                .collect(),
-            ParamParent::Struct(str) => str
-                .0
-                .iter()
-                .map(|(hash, param)| (ParentIndex::Struct(*hash), param))
-                .collect(),
+            ParamParent::List(_) => ParentIndex::List(n),
+            ParamParent::Struct(str) => ParentIndex::Struct(str.0[n].0),
         }
     }
 
+    /// The position of the child with the given index, if the parent has one
+    pub fn position(&self, index: ParentIndex) -> Option<usize> {
+        match (self, index) {
+            (ParamParent::List(list), ParentIndex::List(n)) if n < list.0.len() => Some(n),
+            (ParamParent::Struct(str), ParentIndex::Struct(hash)) => {
+                str.0.iter().position(|(key, _)| *key == hash)
+            }
+            _ => None,
+        }
+    }
+
+    /// Marks each child which is missing from the other parent or has a different value there
+    fn marks(&self, other: ParamParent) -> Vec<bool> {
+        (0..self.len())
+            .map(|n| match other.position(self.index(n)) {
+                Some(m) => self.nth(n) != other.nth(m),
+                None => true,
+            })
+            .collect()
+    }
+
     pub fn len(&self) -> usize {
         match self {
             ParamParent::List(list) => list.0.len(),
@@ -293,17 +1652,34 @@ impl ParamParent {
         self.len() == 0
     }
 
-    pub fn nth(&self, n: usize) -> &ParamKind {
+    pub fn get(&self, n: usize) -> Option<&'a ParamKind> {
+        match self {
+            ParamParent::List(list) => list.0.get(n),
+            ParamParent::Struct(str) => str.0.get(n).map(|(_, child)| child),
+        }
+    }
+
+    pub fn nth(&self, n: usize) -> &'a ParamKind {
         match self {
             ParamParent::List(list) => &list.0[n],
             ParamParent::Struct(str) => &str.0[n].1,
         }
     }
+}
+
+impl ParentIndex {
+    /// The key of a struct's child, which a list's children don't have
+    pub fn key(&self) -> Option<Hash40> {
+        match self {
+            ParentIndex::List(_) => None,
+            ParentIndex::Struct(hash) => Some(*hash),
+        }
+    }
 
-    pub fn nth_mut(&mut self, n: usize) -> &mut ParamKind {
+    pub fn name(&self, hashes: HashDisplay) -> Arc<str> {
         match self {
-            ParamParent::List(list) => &mut list.0[n],
-            ParamParent::Struct(str) => &mut str.0[n].1,
+            ParentIndex::List(index) => index.to_string().into(),
+            ParentIndex::Struct(hash) => hashes.text(*hash),
         }
     }
 }
@@ -329,6 +1705,18 @@ impl Component for Param {
     type DrawResponse = Buffer;
 
     fn handle_event(&mut self, event: Event) -> Self::Response {
+        if let Event::Mouse(mouse) = event {
+            return self.handle_mouse(mouse);
+        }
+        if let Event::Key(key) = event {
+            if is_paste(&key) && self.is_typing() {
+                // the clipboard is typed into whichever value or filter is being edited
+                for event in paste_events(&clipboard_text().unwrap_or_default()) {
+                    self.handle_event(event);
+                }
+                return ParamResponse::Handled { edited: false };
+            }
+        }
         // if the param has a child, see what it returns
         //    if it returns an "Exit" event, unselect and call the exit function
         //    if it returns a "None" event, do nothing
@@ -339,6 +1727,20 @@ impl Component for Param {
                 ParamResponse::Handled { edited } => return ParamResponse::Handled { edited },
                 ParamResponse::None => return ParamResponse::None,
             }
+        } else if let Some(back) = recall_key(event).filter(|_| self.selected.is_some()) {
+            self.recall(back);
+        } else if let Some(key) = expr_key(event).filter(|key| self.starts_expr(*key)) {
+            self.start_expr(key);
+        } else if let (Some(steps), Some(SelectedParam::Float(float))) =
+            (slide_key(event), self.selected.as_deref_mut())
+        {
+            let step = match self.settings.lock() {
+                Ok(settings) => settings.float_step,
+                Err(err) => err.into_inner().float_step,
+            };
+            if let Ok(input) = FloatInput::new(step_float(float.value(), step, steps)) {
+                *float = input;
+            }
         } else if let Some(selected) = self.selected.as_deref_mut() {
             let response = match selected {
                 SelectedParam::I8(int) => int.handle_event(event),
@@ -359,6 +1761,17 @@ impl Component for Param {
                     }
                     return ParamResponse::Handled { edited: false };
                 }
+                SelectedParam::Expr(expr) => {
+                    match expr.handle_event(event) {
+                        ExprInputResponse::Submit => {
+                            self.exit(true);
+                            return ParamResponse::Handled { edited: true };
+                        }
+                        ExprInputResponse::Cancel => self.exit(false),
+                        _ => {}
+                    }
+                    return ParamResponse::Handled { edited: false };
+                }
                 SelectedParam::Hash(hash) => {
                     match hash.handle_event(event) {
                         HashInputResponse::Submit => {
@@ -370,6 +1783,17 @@ impl Component for Param {
                     }
                     return ParamResponse::Handled { edited: false };
                 }
+                SelectedParam::Enum(picker) => {
+                    match picker.handle_event(event) {
+                        EnumPickerResponse::Submit => {
+                            self.exit(true);
+                            return ParamResponse::Handled { edited: true };
+                        }
+                        EnumPickerResponse::Cancel => self.exit(false),
+                        _ => {}
+                    }
+                    return ParamResponse::Handled { edited: false };
+                }
                 SelectedParam::NewLevel(_) => unreachable!(),
             };
             match response {
@@ -380,10 +1804,59 @@ impl Component for Param {
                 NumInputResponse::Cancel => self.exit(false),
                 _ => {}
             }
+        } else if self.filter_editing {
+            let theme = self.settings().theme;
+            let history = self.history.clone();
+            let filter = self
+                .filter
+                .get_or_insert_with(|| Box::new(Filter::new(theme).with_history(history)));
+            match filter.handle_event(event) {
+                FilterResponse::Changed { narrowed: true } => self.narrow_rows(),
+                FilterResponse::Changed { narrowed: false } => self.update_rows(),
+                FilterResponse::Submit => {
+                    filter.set_focused(false);
+                    self.filter_editing = false;
+                    if filter.expr().is_none() {
+                        self.filter = None;
+                        self.update_rows();
+                    }
+                    self.keep_filter();
+                }
+                FilterResponse::Cancel => {
+                    self.filter = None;
+                    self.filter_editing = false;
+                    self.keep_filter();
+                    self.update_rows();
+                }
+                FilterResponse::None => {}
+            }
         } else if let Event::Key(key) = event {
-            match key.code {
+            let plain = is_plain(&key);
+            // while a name is being typed, every letter is part of it rather than a command
+            if let KeyCode::Char(c) = key.code {
+                if plain && TypeAhead::accepts(c) && self.type_ahead.is_typing() {
+                    self.type_ahead(c);
+                    return ParamResponse::Handled { edited: false };
+                }
+            }
+            match vim_code(&key) {
                 KeyCode::Up => self.up(),
                 KeyCode::Down => self.down(),
+                KeyCode::Char('g') if plain => self.select_row(0),
+                KeyCode::Char('G') if plain => self.select_row(self.rows.len().saturating_sub(1)),
+                KeyCode::Home => self.select_row(0),
+                KeyCode::End => self.select_row(self.rows.len().saturating_sub(1)),
+                KeyCode::PageUp => self.page(false),
+                KeyCode::PageDown => self.page(true),
+                KeyCode::Right => {
+                    if self
+                        .selected_index()
+                        .is_some_and(|index| self.is_parent(index))
+                    {
+                        self.enter();
+                    }
+                }
+                KeyCode::Left => return ParamResponse::Exit,
                 KeyCode::Enter => {
                     let enter_result = self.enter();
                     if enter_result {
@@ -391,12 +1864,39 @@ impl Component for Param {
                     }
                 }
                 KeyCode::Backspace => return ParamResponse::Exit,
+                KeyCode::Char('s') if plain => self.cycle_sort(),
+                KeyCode::Char('S') if plain => {
+                    if self.apply_sort() {
+                        return ParamResponse::Handled { edited: true };
+                    }
+                }
+                KeyCode::Char(' ') => {
+                    if self.toggle_bool() {
+                        return ParamResponse::Handled { edited: true };
+                    }
+                }
+                KeyCode::Char('i') if plain => {
+                    if self.insert_from_template() {
+                        return ParamResponse::Handled { edited: true };
+                    }
+                }
+                KeyCode::Char('/') => {
+                    let theme = self.settings().theme;
+                    let history = self.history.clone();
+                    self.filter
+                        .get_or_insert_with(|| Box::new(Filter::new(theme).with_history(history)))
+                        .set_focused(true);
+                    self.filter_editing = true;
+                }
+                KeyCode::Char(TypeAhead::START) => self.type_ahead.start(),
                 _ => return ParamResponse::None,
             }
         }
         ParamResponse::Handled { edited: false }
     }
 
+    // the buffer is only handed down to the next column, which draws into one of its own
+    #[allow(clippy::only_used_in_recursion)]
     fn draw(&mut self, rect: tui_components::tui::layout::Rect, buffer: &mut Buffer) -> Buffer {
         let child_buffer = self.next_mut().map(|child| child.draw(rect, buffer));
         let is_last_column = child_buffer.is_none();
@@ -408,40 +1908,108 @@ impl Component for Param {
         // the 2nd condition makes it so we always draw the deepest param
         if remaining_space < MIN_PARAM_TABLE_WIDTH {
             if let Some(child_buf) = child_buffer {
+                self.area = None;
                 return child_buf;
             }
         }
 
         let selected_info = self.get_selected_span();
+        let Settings {
+            theme,
+            hex_column,
+            row_numbers,
+            ..
+        } = self.settings();
+        let hex_column = hex_column && is_last_column;
 
-        let children = self.param.children();
-        let columns = children
-            .iter()
-            .enumerate()
-            .map(|(list_index, (index, param))| {
-                let name = Spans::from(format!("{}", index));
-                let ty = Spans::from(param_type(param));
-
-                let value = match &selected_info {
-                    Some((selected_index, spans)) if list_index == *selected_index => {
-                        spans.to_owned()
-                    }
-                    _ => Spans::from(param_value(param)),
-                };
-                [name, ty, value]
-            })
-            .collect::<Vec<_>>();
+        // only the rows which fit in the table are built, plus a margin for measuring widths
+        self.refresh_texts();
+        let height = rect.height.saturating_sub(2) as usize;
+        self.scroll_to_selected(height);
+        let visible = self.offset..(self.offset + height).min(self.rows.len());
+        let measured = visible.start.saturating_sub(WIDTH_MARGIN)
+            ..(visible.end + WIDTH_MARGIN).min(self.rows.len());
+
+        let columns = self.with_level(|level| {
+            measured
+                .clone()
+                .map(|row| {
+                    let (index, param) = level.child(self.rows[row]);
+                    let name = Spans::from(self.texts.name(self.rows[row], index).to_string());
+                    // the parent columns are dimmed, and marked rows are colored as a whole
+                    let marked = self.marks.get(self.rows[row]).copied().unwrap_or_default();
+                    let ty = if is_last_column && !marked {
+                        let style = Style::default().fg(RowKind::of(param).color(&theme));
+                        Spans::from(Span::styled(param_type(param), style))
+                    } else {
+                        Spans::from(param_type(param))
+                    };
 
-        let widths = columns.iter().fold([0, 0, 0], |current, col| {
+                    let value = match &selected_info {
+                        Some((selected_index, spans)) if row == *selected_index => spans.to_owned(),
+                        _ => {
+                            Spans::from(self.texts.value(self.rows[row], index, param).to_string())
+                        }
+                    };
+                    let hex = if hex_column {
+                        let key = match index {
+                            ParentIndex::Struct(hash) => Some(hash),
+                            ParentIndex::List(_) => None,
+                        };
+                        let value = match param {
+                            ParamKind::Hash(hash) => Some(*hash),
+                            _ => None,
+                        };
+                        hex_pair(key, value)
+                    } else {
+                        String::new()
+                    };
+                    ([name, ty, value], hex)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let widths = columns.iter().fold([0, 0, 0], |current, (col, _)| {
             [
                 current[0].max(col[0].width() as u16),
                 current[1].max(col[1].width() as u16),
                 current[2].max(col[2].width() as u16),
             ]
         });
-        // each column has 1 left border, and the last one has an extra right border
-        let desired_width = widths.iter().sum::<u16>() + if child_buffer.is_some() { 3 } else { 4 };
+        // the hex column keeps its width, and takes one more cell of spacing
+        let hex_width = columns
+            .iter()
+            .map(|(_, hex)| hex.len() as u16)
+            .max()
+            .unwrap_or_default();
+        let hex_space = if hex_width > 0 { hex_width + 1 } else { 0 };
+        // row numbers are as wide as the last one, so the column doesn't change width when
+        // scrolling, and take one more cell of spacing
+        let number_width = if row_numbers {
+            self.with_level(|level| number_width(level.len()))
+        } else {
+            0
+        };
+        let number_space = if row_numbers { number_width + 1 } else { 0 };
+        let mut title = self.filter.as_ref().map(|filter| filter.get_spans());
+        if self.sort != SortOrder::File {
+            let label = Span::styled(
+                format!("[sort: {}]", self.sort.label()),
+                Style::default().fg(theme.hint),
+            );
+            match &mut title {
+                Some(title) => title.0.insert(0, label),
+                None => title = Some(Spans::from(label)),
+            }
+        }
+        let title_width = title.as_ref().map_or(0, |title| title.width() as u16 + 2);
+        // each column has 1 left border, and the last one has an extra right border.
+        // The other 2 cells are the spacing between the name, type, and value
+        let borders = if child_buffer.is_some() { 3 } else { 4 };
+        let extra_space = hex_space + number_space;
+        let desired_width = (widths.iter().sum::<u16>() + borders + extra_space).max(title_width);
         let true_width = desired_width.min(remaining_space);
+        let widths = balance_widths(widths, true_width.saturating_sub(borders + extra_space));
         let draw_area = Rect {
             x: 0,
             y: rect.y,
@@ -449,31 +2017,80 @@ impl Component for Param {
             height: rect.height,
         };
 
-        let block = if is_last_column {
+        let mut block = if is_last_column {
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Blue))
+                .border_style(Style::default().fg(theme.highlight))
         } else {
             Block::default()
                 .borders(Borders::TOP | Borders::LEFT | Borders::BOTTOM)
-                .border_style(Style::default().fg(Color::DarkGray))
+                .border_style(Style::default().fg(theme.inactive))
         };
+        if let Some(title) = title {
+            block = block.title(title);
+        }
         let table_area = block.inner(draw_area);
+        self.area = Some(Rect {
+            x: rect.x + table_area.x,
+            ..table_area
+        });
+        if let Some(next) = self.next_mut() {
+            next.shift_areas(true_width);
+        }
 
-        let rows = columns.into_iter().map(Row::new);
+        let rows = columns
+            .into_iter()
+            .zip(measured)
+            .filter(|(_, row)| visible.contains(row))
+            .map(|((columns, hex), row)| {
+                // the value being edited is left whole, so the end of it stays in view
+                let editing = matches!(&selected_info, Some((selected, _)) if *selected == row);
+                let [name, ty, value] = columns;
+                let value = if editing {
+                    value
+                } else {
+                    truncate(value, widths[2])
+                };
+                let mut columns = vec![truncate(name, widths[0]), truncate(ty, widths[1]), value];
+                if hex_space > 0 {
+                    let style = Style::default().fg(theme.hint);
+                    columns.push(Spans::from(Span::styled(hex, style)));
+                }
+                if row_numbers {
+                    let number = format!("{:>1$}", self.rows[row], number_width as usize);
+                    let style = Style::default().fg(theme.hint);
+                    columns.insert(0, Spans::from(Span::styled(number, style)));
+                }
+                let marked = self.marks.get(self.rows[row]).copied().unwrap_or_default();
+                if marked {
+                    Row::new(columns).style(Style::default().fg(theme.accent))
+                } else {
+                    Row::new(columns)
+                }
+            });
 
-        let constraints = widths.map(Constraint::Length);
+        let mut constraints = widths.map(Constraint::Length).to_vec();
+        if hex_space > 0 {
+            constraints.push(Constraint::Length(hex_width));
+        }
+        if row_numbers {
+            constraints.insert(0, Constraint::Length(number_width));
+        }
         let table = if is_last_column {
             Table::new(rows)
                 .widths(&constraints)
                 .column_spacing(1)
-                .highlight_style(Style::default().bg(Color::Blue))
+                .highlight_style(Style::default().bg(theme.highlight))
         } else {
             Table::new(rows)
                 .widths(&constraints)
                 .column_spacing(1)
-                .style(Style::default().fg(Color::DarkGray))
-                .highlight_style(Style::default().fg(Color::Gray).bg(Color::Blue))
+                .style(Style::default().fg(theme.inactive))
+                .highlight_style(
+                    Style::default()
+                        .fg(theme.inactive_highlight)
+                        .bg(theme.highlight),
+                )
         };
 
         let mut draw_buffer = child_buffer
@@ -487,30 +2104,164 @@ impl Component for Param {
             .unwrap_or_else(|| Buffer::empty(draw_area));
 
         Widget::render(block, draw_area, &mut draw_buffer);
-        StatefulWidget::render(table, table_area, &mut draw_buffer, &mut self.state);
+        // the table is only given the visible rows, so its selection is relative to them
+        let mut visible_state = TableState::default();
+        visible_state.select(
+            self.state
+                .selected()
+                .and_then(|selected| selected.checked_sub(self.offset)),
+        );
+        StatefulWidget::render(table, table_area, &mut draw_buffer, &mut visible_state);
+
+        if let Some(row) = visible_state.selected() {
+            let value_x = table_area.x + number_space + widths[0] + widths[1] + 2;
+            let row_y = table_area.y + row as u16;
+            match self.selected.as_deref() {
+                Some(SelectedParam::Hash(hash)) => {
+                    let size = (hash.suggestions_width(), hash.suggestions_height());
+                    if let Some(area) = popup_area(size, draw_area, table_area, value_x, row_y) {
+                        hash.draw_suggestions(area, &mut draw_buffer);
+                    }
+                }
+                Some(SelectedParam::Enum(picker)) => {
+                    let size = (picker.options_width(), picker.options_height());
+                    if let Some(area) = popup_area(size, draw_area, table_area, value_x, row_y) {
+                        picker.draw_options(area, &mut draw_buffer);
+                    }
+                }
+                _ => {}
+            }
+        }
 
         draw_buffer
     }
 }
 
-fn param_type(param: &ParamKind) -> &'static str {
-    match param {
-        ParamKind::Bool(_) => "bool",
-        ParamKind::I8(_) => "i8",
-        ParamKind::U8(_) => "u8",
-        ParamKind::I16(_) => "i16",
-        ParamKind::U16(_) => "u16",
-        ParamKind::I32(_) => "i32",
-        ParamKind::U32(_) => "u32",
-        ParamKind::Float(_) => "f32",
-        ParamKind::Hash(_) => "hash",
-        ParamKind::Str(_) => "string",
-        ParamKind::List(_) => "list",
-        ParamKind::Struct(_) => "struct",
+/// Where a list shown while editing a value goes, like a hash input's suggestions: below the
+/// row being edited, lined up with the value, or above it if there's more room there.
+/// Returns None if the list is empty, or there's no room for any of it
+pub(crate) fn popup_area(
+    (width, height): (u16, u16),
+    column: Rect,
+    table: Rect,
+    x: u16,
+    row_y: u16,
+) -> Option<Rect> {
+    let below = table.bottom().saturating_sub(row_y + 1);
+    let above = row_y.saturating_sub(table.y);
+    let (y, height) = if below >= height || below >= above {
+        (row_y + 1, height.min(below))
+    } else {
+        let height = height.min(above);
+        (row_y - height, height)
+    };
+    // a border on each side and at least one suggestion
+    if height < 3 {
+        return None;
+    }
+    let width = width.min(column.width);
+    let x = x.min(column.right() - width).max(column.x);
+    Some(Rect {
+        x,
+        y,
+        width,
+        height,
+    })
+}
+
+/// Shrinks the name and value columns to fit in the available width, if they don't already.
+/// Whichever is shorter keeps its width if it fits in half the space, and the other gets the rest
+fn balance_widths([name, ty, value]: [u16; 3], available: u16) -> [u16; 3] {
+    let available = available.saturating_sub(ty);
+    if name + value <= available {
+        return [name, ty, value];
+    }
+    let half = available / 2;
+    if name <= half {
+        [name, ty, available - name]
+    } else if value <= available - half {
+        [available - value, ty, value]
+    } else {
+        [half, ty, available - half]
+    }
+}
+
+/// Cuts text down to the given width, ending it with an ellipsis if anything was cut
+fn truncate(spans: Spans<'static>, width: u16) -> Spans<'static> {
+    let width = width as usize;
+    if spans.width() <= width {
+        return spans;
+    }
+    let mut remaining = width.saturating_sub(1);
+    let mut truncated = vec![];
+    'spans: for span in spans.0 {
+        let mut content = String::new();
+        for c in span.content.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if char_width > remaining {
+                truncated.push(Span::styled(content, span.style));
+                break 'spans;
+            }
+            remaining -= char_width;
+            content.push(c);
+        }
+        truncated.push(Span::styled(content, span.style));
+    }
+    if width > 0 {
+        truncated.push(Span::raw("…"));
+    }
+    Spans::from(truncated)
+}
+
+/// The broad kind of a param, which its type is colored by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    /// Lists and structs
+    Parent,
+    /// Numbers and bools
+    Number,
+    /// Hashes and strings
+    Text,
+}
+
+impl RowKind {
+    pub fn of(param: &ParamKind) -> Self {
+        match param {
+            ParamKind::List(_) | ParamKind::Struct(_) => RowKind::Parent,
+            ParamKind::Hash(_) | ParamKind::Str(_) => RowKind::Text,
+            _ => RowKind::Number,
+        }
+    }
+
+    pub fn color(self, theme: &Theme) -> Color {
+        match self {
+            RowKind::Parent => theme.parent_type,
+            RowKind::Number => theme.number_type,
+            RowKind::Text => theme.text_type,
+        }
+    }
+}
+
+/// How many cells the row numbers of a list or struct with this many children take
+pub fn number_width(len: usize) -> u16 {
+    len.saturating_sub(1).to_string().len() as u16
+}
+
+/// The param's value as it's shown, with its name first if its key's values have names
+pub fn keyed_value(
+    key: Option<Hash40>,
+    param: &ParamKind,
+    hashes: HashDisplay,
+    floats: FloatFormat,
+) -> String {
+    let value = param_value(param, hashes, floats);
+    match enum_name(key, param) {
+        Some(name) => format!("{} ({})", name, value),
+        None => value,
     }
 }
 
-fn param_value(param: &ParamKind) -> String {
+pub fn param_value(param: &ParamKind, hashes: HashDisplay, floats: FloatFormat) -> String {
     match param {
         ParamKind::Bool(v) => if *v { TRUE_CHAR } else { FALSE_CHAR }.into(),
         ParamKind::I8(v) => format!("{}", v),
@@ -519,8 +2270,8 @@ fn param_value(param: &ParamKind) -> String {
         ParamKind::U16(v) => format!("{}", v),
         ParamKind::I32(v) => format!("{}", v),
         ParamKind::U32(v) => format!("{}", v),
-        ParamKind::Float(v) => format!("{}", v),
-        ParamKind::Hash(v) => format!("{}", v),
+        ParamKind::Float(v) => floats.format(*v),
+        ParamKind::Hash(v) => hashes.format(*v),
         ParamKind::Str(v) => v.to_string(),
         ParamKind::List(v) => format!("({} children)", v.0.len()),
         ParamKind::Struct(v) => format!("({} children)", v.0.len()),
@@ -0,0 +1,301 @@
+use std::sync::{Arc, Mutex};
+
+use prc::{ParamKind, ParamStruct};
+use rayon::prelude::*;
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Direction, Layout, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::history::InputHistory;
+use crate::path::{visit, ParamPath};
+use crate::settings::Settings;
+use crate::task::Cancellable;
+use crate::utils::modulo::{add_mod, sub_mod};
+use crate::utils::paste::{clipboard_text, is_paste, paste_events};
+
+use super::busy::{percent, spinner};
+use super::filter::{Filter, FilterResponse};
+use super::param::keyed_value;
+
+/// Every value in the file in one list, written like `fighter_param_table[3].walk_speed = 1.2`.
+/// The list is filtered in the background, across several threads, showing the rows from before
+/// until it's done
+#[derive(Debug)]
+pub struct PathList {
+    /// The file when the list was opened
+    root: Arc<ParamStruct>,
+    /// The path of each value, and how it's written
+    leaves: Arc<Vec<(ParamPath, String)>>,
+    /// The indices of the leaves shown, after filtering
+    rows: Vec<usize>,
+    /// The leaves matching the filter typed now, until they're all checked
+    filtering: Option<Cancellable<Vec<usize>>>,
+    selected: usize,
+    offset: usize,
+    filter: Option<Box<Filter>>,
+    filter_editing: bool,
+    history: Arc<Mutex<InputHistory>>,
+    settings: Settings,
+}
+
+#[derive(Debug)]
+pub enum PathListResponse {
+    None,
+    Handled,
+    /// The user chose to go to the param at this path
+    Open(ParamPath),
+    Exit,
+}
+
+impl PathList {
+    pub fn new(
+        root: Arc<ParamStruct>,
+        history: Arc<Mutex<InputHistory>>,
+        settings: Settings,
+    ) -> Self {
+        let mut leaves = vec![];
+        visit(&root, |path, param| {
+            if !matches!(param, ParamKind::List(_) | ParamKind::Struct(_)) {
+                leaves.push((path.clone(), path.to_string()));
+            }
+        });
+        Self {
+            root,
+            rows: (0..leaves.len()).collect(),
+            leaves: Arc::new(leaves),
+            filtering: None,
+            selected: 0,
+            offset: 0,
+            filter: None,
+            filter_editing: false,
+            history,
+            settings,
+        }
+    }
+
+    pub fn key_hints(&self) -> &'static str {
+        if self.filter_editing {
+            "Enter: keep filter  Up/Down: history  Esc: clear filter"
+        } else {
+            "Enter: go to the param  /: filter  Esc: back"
+        }
+    }
+
+    fn value(&self, leaf: usize) -> String {
+        let path = &self.leaves[leaf].0;
+        path.get(&self.root)
+            .map(|param| leaf_value(path, param, self.settings))
+            .unwrap_or_default()
+    }
+
+    /// Shows the rows once the filter has checked every leaf.
+    /// Returns whether anything needs to be redrawn, which it does while the spinner turns
+    pub fn update(&mut self) -> bool {
+        match &self.filtering {
+            Some(filtering) if filtering.is_finished() => {
+                if let Some(rows) = self.filtering.take().and_then(Cancellable::join) {
+                    self.show_rows(rows);
+                }
+                true
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Whether the filter is still being applied
+    pub fn is_busy(&self) -> bool {
+        self.filtering.is_some()
+    }
+
+    /// Starts applying the filter to the candidate leaves again, which stops applying the
+    /// filter typed before
+    fn filter_rows(&mut self, candidates: Vec<usize>) {
+        let expr = match self.filter.as_ref().and_then(|filter| filter.expr()) {
+            Some(expr) => expr.clone(),
+            None => {
+                self.filtering = None;
+                self.show_rows((0..self.leaves.len()).collect());
+                return;
+            }
+        };
+        let root = self.root.clone();
+        let leaves = self.leaves.clone();
+        let settings = self.settings;
+        self.filtering = Some(Cancellable::spawn(move |progress| {
+            progress.set_total(candidates.len());
+            let rows = candidates
+                .into_par_iter()
+                .filter(|leaf| {
+                    let (path, text) = &leaves[*leaf];
+                    progress.step()
+                        && path.get(&root).is_some_and(|param| {
+                            expr.matches(
+                                || text.clone(),
+                                param,
+                                || leaf_value(path, param, settings),
+                            )
+                        })
+                })
+                .collect();
+            Some(rows).filter(|_| !progress.is_cancelled())
+        }));
+    }
+
+    /// Shows the filtered rows, keeping the same one selected if it's still shown
+    fn show_rows(&mut self, rows: Vec<usize>) {
+        let current = self.rows.get(self.selected).copied();
+        self.rows = rows;
+        self.selected = current
+            .and_then(|current| self.rows.iter().position(|row| *row == current))
+            .unwrap_or(0);
+    }
+
+    fn handle_filter(&mut self, event: Event) -> PathListResponse {
+        let theme = self.settings.theme;
+        let history = self.history.clone();
+        let filter = self
+            .filter
+            .get_or_insert_with(|| Box::new(Filter::new(theme).with_history(history)));
+        match filter.handle_event(event) {
+            FilterResponse::Changed { narrowed } => {
+                // the rows shown are only the ones to narrow down once they're filtered
+                if narrowed && self.filtering.is_none() {
+                    self.filter_rows(self.rows.clone());
+                } else {
+                    self.filter_rows((0..self.leaves.len()).collect());
+                }
+            }
+            FilterResponse::Submit => {
+                filter.set_focused(false);
+                self.filter_editing = false;
+                if filter.expr().is_none() {
+                    self.filter = None;
+                }
+            }
+            FilterResponse::Cancel => {
+                self.filter = None;
+                self.filter_editing = false;
+                self.filtering = None;
+                self.show_rows((0..self.leaves.len()).collect());
+            }
+            FilterResponse::None => return PathListResponse::None,
+        }
+        PathListResponse::Handled
+    }
+}
+
+/// How the value at the path is shown in the list
+fn leaf_value(path: &ParamPath, param: &ParamKind, settings: Settings) -> String {
+    keyed_value(
+        path.key(),
+        param,
+        settings.hash_display,
+        settings.float_format,
+    )
+}
+
+impl Component for PathList {
+    type Response = PathListResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return PathListResponse::None,
+        };
+        if self.filter_editing {
+            if is_paste(&key) {
+                for event in paste_events(&clipboard_text().unwrap_or_default()) {
+                    self.handle_filter(event);
+                }
+                return PathListResponse::Handled;
+            }
+            return self.handle_filter(event);
+        }
+        let len = self.rows.len();
+        match key.code {
+            KeyCode::Char('/') => {
+                self.filter_editing = true;
+                match self.filter.as_deref_mut() {
+                    Some(filter) => filter.set_focused(true),
+                    None => {
+                        let filter = Filter::new(self.settings.theme);
+                        self.filter = Some(Box::new(filter.with_history(self.history.clone())));
+                    }
+                }
+            }
+            KeyCode::Up if len > 0 => self.selected = sub_mod(self.selected, 1, len),
+            KeyCode::Down if len > 0 => self.selected = add_mod(self.selected, 1, len),
+            KeyCode::Enter => {
+                if let Some(leaf) = self.rows.get(self.selected) {
+                    return PathListResponse::Open(self.leaves[*leaf].0.clone());
+                }
+            }
+            KeyCode::Esc => return PathListResponse::Exit,
+            _ => return PathListResponse::None,
+        }
+        PathListResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let theme = self.settings.theme;
+        let title = match &self.filtering {
+            Some(filtering) => format!(
+                "Values {} Filtering...{}",
+                spinner(filtering.started()),
+                percent(filtering.progress())
+            ),
+            None => format!("Values ({} of {})", self.rows.len(), self.leaves.len()),
+        };
+        let block = Block::default()
+            .title(Span::styled(title, Style::default().fg(theme.title)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent));
+        let inner = block.inner(rect);
+        let filter_height = if self.filter.is_some() { 1 } else { 0 };
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(filter_height)])
+            .split(inner);
+
+        let height = areas[0].height as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+        self.offset = self.offset.min(self.rows.len().saturating_sub(height));
+
+        let lines = self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(height)
+            .map(|(row, leaf)| {
+                let style = if row == self.selected {
+                    Style::default().bg(theme.highlight)
+                } else {
+                    Style::default()
+                };
+                Spans::from(vec![
+                    Span::styled(self.leaves[*leaf].1.clone(), style),
+                    Span::styled(" = ", style.fg(theme.hint)),
+                    Span::styled(self.value(*leaf), style),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(block, rect, buffer);
+        Widget::render(Paragraph::new(lines), areas[0], buffer);
+        if let Some(filter) = &self.filter {
+            Widget::render(Paragraph::new(filter.get_spans()), areas[1], buffer);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Direction, Layout, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Paragraph, Row, StatefulWidget, Table, TableState, Widget};
+
+use crate::replace::Replacement;
+use crate::theme::Theme;
+use crate::value::format_value;
+
+/// The values an edit to many params would change, listed before it's applied
+#[derive(Debug)]
+pub struct ReplacementPreview {
+    /// The values which would change, or why the edit is invalid
+    replacements: Result<Vec<Replacement>, String>,
+    /// Shown in place of the summary, like how far the search for the values has got
+    status: Option<String>,
+    state: TableState,
+}
+
+impl Default for ReplacementPreview {
+    fn default() -> Self {
+        Self {
+            replacements: Ok(vec![]),
+            status: None,
+            state: TableState::default(),
+        }
+    }
+}
+
+impl ReplacementPreview {
+    pub fn set(&mut self, replacements: Result<Vec<Replacement>, String>) {
+        let len = replacements.as_ref().map_or(0, Vec::len);
+        self.replacements = replacements;
+        self.state.select(if len > 0 { Some(0) } else { None });
+    }
+
+    pub fn set_status(&mut self, status: Option<String>) {
+        self.status = status;
+    }
+
+    pub fn move_selection(&mut self, up: bool) {
+        let len = self.replacements.as_ref().map_or(0, Vec::len);
+        if let Some(selected) = self.state.selected() {
+            let row = if up {
+                selected.saturating_sub(1)
+            } else {
+                (selected + 1).min(len.saturating_sub(1))
+            };
+            self.state.select(Some(row));
+        }
+    }
+
+    /// The changes which fit their params, which is what applying sets
+    pub fn valid_count(&self) -> usize {
+        self.replacements.as_ref().map_or(0, |replacements| {
+            replacements.iter().filter(|r| r.new.is_ok()).count()
+        })
+    }
+
+    /// Takes the changes for applying them, leaving the preview empty
+    pub fn take(&mut self) -> Vec<Replacement> {
+        let replacements = std::mem::replace(&mut self.replacements, Ok(vec![]));
+        replacements.unwrap_or_default()
+    }
+
+    /// Draws a line summarizing the changes, with the changes listed below it
+    pub fn draw(&mut self, rect: Rect, buffer: &mut Buffer, theme: Theme) {
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(rect);
+        let summary = match (&self.status, &self.replacements) {
+            (Some(status), _) => Span::styled(status.clone(), Style::default().fg(theme.hint)),
+            (None, Ok(replacements)) => {
+                let invalid = replacements.len() - self.valid_count();
+                let text = match invalid {
+                    0 => format!("{} value(s) will change", replacements.len()),
+                    _ => format!(
+                        "{} value(s) will change, {} don't fit their type and are skipped",
+                        replacements.len() - invalid,
+                        invalid
+                    ),
+                };
+                Span::styled(text, Style::default().fg(theme.hint))
+            }
+            (None, Err(err)) => Span::styled(err.clone(), Style::default().fg(theme.error)),
+        };
+
+        let rows = self
+            .replacements
+            .as_ref()
+            .map(|replacements| {
+                replacements
+                    .iter()
+                    .map(|replacement| {
+                        let new = match &replacement.new {
+                            Ok(new) => Span::raw(format_value(new)),
+                            Err(err) => Span::styled(err.clone(), Style::default().fg(theme.error)),
+                        };
+                        Row::new(vec![
+                            Spans::from(replacement.path.to_string()),
+                            Spans::from(format_value(&replacement.old)),
+                            Spans::from(new),
+                        ])
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let table = Table::new(rows)
+            .header(Row::new(vec!["path", "old", "new"]).style(Style::default().fg(theme.hint)))
+            .widths(&[
+                Constraint::Percentage(50),
+                Constraint::Percentage(25),
+                Constraint::Percentage(25),
+            ])
+            .column_spacing(1)
+            .highlight_style(Style::default().bg(theme.highlight));
+
+        Widget::render(Paragraph::new(Spans::from(summary)), areas[0], buffer);
+        StatefulWidget::render(table, areas[1], buffer, &mut self.state);
+    }
+}
@@ -1,31 +1,266 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     env::current_dir,
-    path::PathBuf,
+    fs::{canonicalize, metadata, write},
+    io,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
 };
 
-use prc::ParamKind;
+use prc::hash40::{hash40, Hash40};
+use prc::{ParamKind, ParamStruct};
 use tui_components::{
-    components::{Confirm, ConfirmResponse, Explorer, ExplorerMode, ExplorerResponse},
-    crossterm::event::{KeyCode, KeyModifiers},
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     rect_ext::RectExt,
     tui::{
         buffer::Buffer,
-        widgets::{Clear, Widget},
+        layout::{Alignment, Constraint, Direction, Layout, Rect},
+        widgets::{Clear, Paragraph, Widget, Wrap},
     },
     App, AppResponse, Component, Event,
 };
 
+use crate::config::{Config, ExplorerConfig};
+use crate::diff::{diff, diff_structure, Change};
+use crate::format::{self, Format};
+use crate::history::InputHistory;
+use crate::keys::KeyBindings;
+use crate::labels::{default_labels_path, download_labels, install_labels, load_labels, Collision};
+use crate::path::{visit, ParamPath};
+use crate::plugin::{Builtin, Exporter, Plugin};
+use crate::replace::{find_replacements_in, Pattern, Replacement};
+use crate::report::{hash_usage, unknown_hashes};
+use crate::schema::Schema;
+use crate::session::{LevelSession, Session};
+use crate::settings::{FloatFormat, Settings, DEFAULT_FLOAT_STEP};
+use crate::sidecar::{load_sidecar, save_sidecar, sidecar_path, Sidecar, ViewSettings};
+use crate::stats::file_stats;
+use crate::subtree;
+use crate::task::{Cancellable, Task};
+use crate::theme::Theme;
+use crate::utils::modulo::{add_mod, sub_mod};
+use crate::utils::paste::ClipboardWriter;
+use crate::utils::shell::shell_quote;
+use crate::utils::vim::{SetOption, VimCommand};
+
 use super::{
+    arc_prompt::{ArcPrompt, ArcPromptResponse},
+    busy::{Busy, SPINNER_FRAME},
+    command_line::{CommandLine, CommandLineResponse},
+    confirm::{Confirm, ConfirmResponse},
+    details::Details,
     empty::Empty,
-    param::{Param, ParamParent, ParamResponse},
+    error_popup::{ErrorPopup, ErrorPopupResponse},
+    explorer::{Explorer, ExplorerMode, ExplorerResponse},
+    find_replace::{FindReplace, FindReplaceResponse},
+    hash_cracker::{HashCracker, HashCrackerResponse},
+    hash_input::HashInput,
+    hash_report::{HashReport, HashReportResponse},
+    history_panel::{HistoryPanel, HistoryPanelResponse},
+    key_prompt::{KeyPrompt, KeyPromptResponse},
+    note_prompt::{NotePrompt, NotePromptResponse},
+    notification::{Notification, NotificationKind},
+    palette::{Palette, PaletteEntry, PaletteResponse},
+    param::{Param, ParamResponse},
+    path_list::{PathList, PathListResponse},
+    script_input::{ScriptInput, ScriptInputResponse},
+    snapshot_panel::{Snapshot, SnapshotEntry, SnapshotPanel, SnapshotPanelResponse},
+    stats_panel::{StatsPanel, StatsPanelResponse},
+    status_bar::StatusBar,
+    structure_panel::{StructurePanel, StructurePanelResponse},
+    transform_input::{TransformInput, TransformInputResponse},
+    tree_view::{TreeResponse, TreeView, ViewMode},
+    warnings::{Warning, WarningsPanel, WarningsPanelResponse},
 };
 
+const DOWNLOAD_LABELS_MSG: &str = "Download the latest param labels from GitHub?";
+const NO_GAME_ROOT_MSG: &str =
+    "Set game_root in the config to the folder the game was extracted to, to open files by their path in the game";
+/// When a command needs the file to have been saved before
+const EXIT_MSG: &str = "You have unsaved changes. Are you sure you want to exit?";
+const NOT_SAVED_MSG: &str = "The file hasn't been saved yet. Save it with :w <path>";
+const ARC_HINTS: &str = "Tab: complete  Enter: open  Esc: cancel";
+const OPEN_HINTS: &str =
+    "Enter: open  Backspace: parent folder  /: search  Ctrl+L: type a path  s/r: sort  Ctrl+A: all files  Ctrl+G: bookmarks  Esc: cancel";
+const CONFIRM_HINTS: &str = "Left/Right: choose  Enter: confirm  Esc: cancel";
+const SAVE_HINTS: &str =
+    "/: type a name  Enter: save  Ctrl+N: new folder  Ctrl+G: bookmarks  Esc: cancel";
+/// How often the open file is checked for changes made by other programs
+const CHANGE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// The longest the app waits for input when nothing is due to change without it
+const IDLE_WAIT: Duration = Duration::from_secs(1);
+/// The smallest terminal the editor is laid out in. Anything smaller only shows a message
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
+/// The widest the details pane gets, taking at most half the screen
+const DETAILS_WIDTH: u16 = 40;
+
+/// A file being written in the background
+#[derive(Debug)]
+struct Saving {
+    path: PathBuf,
+    /// The file as it's being written
+    param: Arc<ParamStruct>,
+    task: Task<Result<(), io::Error>>,
+}
+
+/// The open file as it is on disk, for highlighting the params a save would change
+#[derive(Debug)]
+enum DiskBaseline {
+    /// The file is being read again
+    Reading(Task<Result<ParamStruct, io::Error>>),
+    Loaded(Arc<ParamStruct>),
+}
+
+/// One of the files opened together, which keeps its params, edits and all, while another one
+/// is shown
+#[derive(Debug)]
+struct FileSlot {
+    path: PathBuf,
+    /// The file as it was left, or None while it's the one shown or hasn't loaded
+    file: Option<Box<SlotFile>>,
+}
+
+/// Everything about a file which goes with it when it's put aside for another
+#[derive(Debug)]
+struct SlotFile {
+    param: Param,
+    tree: Option<Box<TreeView>>,
+    compare: Option<Box<Param>>,
+    edited: bool,
+    saved: Option<Arc<ParamStruct>>,
+    disk: Option<DiskBaseline>,
+    modified: Option<SystemTime>,
+    sidecar: Sidecar,
+}
+
+/// Background work the app is waiting on, with the popup shown meanwhile
+#[derive(Debug)]
+struct Job {
+    busy: Busy,
+    kind: JobKind,
+}
+
+#[derive(Debug)]
+enum JobKind {
+    Open {
+        path: PathBuf,
+        task: Task<Result<ParamStruct, io::Error>>,
+        /// The session to restore once the file is loaded, if any
+        levels: Vec<LevelSession>,
+        /// The param to go to once the file is loaded, if any
+        at: Option<ParamPath>,
+    },
+    OpenCompare {
+        path: PathBuf,
+        task: Task<Result<ParamStruct, io::Error>>,
+    },
+    /// One of the files opened together, loaded into its slot to switch to
+    OpenSlot {
+        path: PathBuf,
+        task: Task<Result<ParamStruct, io::Error>>,
+    },
+    /// A file being read to add to the open one
+    Import {
+        path: PathBuf,
+        task: Task<Result<ParamStruct, io::Error>>,
+    },
+    /// A param being saved to a file of its own, or the file exported by a plugin
+    Export {
+        path: PathBuf,
+        task: Task<Result<(), io::Error>>,
+    },
+    /// Values being searched for to replace, from a `:s` command
+    Search {
+        find: String,
+        task: Cancellable<Vec<Replacement>>,
+    },
+    /// The latest param labels being downloaded, which replace the labels file once they're in
+    DownloadLabels {
+        task: Task<Result<Vec<u8>, io::Error>>,
+    },
+}
+
+impl JobKind {
+    fn is_finished(&self) -> bool {
+        match self {
+            JobKind::Open { task, .. } => task.is_finished(),
+            JobKind::OpenCompare { task, .. } => task.is_finished(),
+            JobKind::OpenSlot { task, .. } => task.is_finished(),
+            JobKind::Import { task, .. } => task.is_finished(),
+            JobKind::Export { task, .. } => task.is_finished(),
+            JobKind::Search { task, .. } => task.is_finished(),
+            JobKind::DownloadLabels { task } => task.is_finished(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Root {
     state: State,
     sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+    settings: Arc<Mutex<Settings>>,
+    /// What was typed into filters and values, kept between files
+    history: Arc<Mutex<InputHistory>>,
+    keys: KeyBindings,
+    /// How the params of each opened file are laid out
+    layout: ViewMode,
+    /// The labels files from the command line or the config, read instead of the usual places
+    labels: Vec<PathBuf>,
+    /// The labels file from the config, which downloaded labels are written to instead of the
+    /// application directory. Files from the command line are never written over
+    labels_target: Option<PathBuf>,
+    /// The hashes given more than one label by the labels file
+    label_collisions: Vec<Collision>,
+    /// What files are expected to look like, if there's a schema file
+    schema: Option<Schema>,
+    /// How the explorer starts out when it's opened
+    explorer: ExplorerConfig,
+    /// The folder the game's files were extracted to, for opening files by their arc path
+    game_root: Option<PathBuf>,
+    /// The format of files saved without an extension
+    export_format: Option<Format>,
+    /// How often edits to the open file are saved on their own, if at all
+    autosave: Option<Duration>,
+    /// When the file was last saved or opened, to know when to autosave next
+    last_save: Instant,
+    /// The path of the open file, once it has been opened or saved
+    file: Option<PathBuf>,
+    /// The files opened together, like from the command line, which can be switched between
+    files: Vec<FileSlot>,
+    /// What the open file is called until it's saved, when it wasn't opened from a path
+    unsaved_name: Option<String>,
+    /// When the open file was last changed on disk, as of opening or saving it or the user
+    /// deciding what to do about another program changing it
+    modified: Option<SystemTime>,
+    /// When the open file was last checked for changes by other programs
+    last_check: Instant,
+    /// Background work which has to finish before the user can continue
+    jobs: VecDeque<Job>,
+    /// A save in progress, which the user can keep working during
+    saving: Option<Saving>,
+    /// The saved file to highlight differences from, while that's turned on
+    disk: Option<DiskBaseline>,
+    /// The file as it was opened or last saved, to find the params edited since
+    saved: Option<Arc<ParamStruct>>,
+    /// The named snapshots taken of any file in this session
+    snapshots: Vec<Snapshot>,
+    /// What's kept about the open file next to it, like notes about its params
+    sidecar: Sidecar,
+    notification: Option<Notification>,
+    /// Something which failed, shown above everything until the user acknowledges it
+    error: Option<ErrorPopup>,
+    /// Whether the details of the selected param are shown next to the params
+    details: bool,
+    /// Where each segment of the path in the status bar was last drawn
+    breadcrumbs: Vec<Rect>,
+    /// The terminal title as it was last set, to only set it again when it changes
+    title: String,
+    /// Holds on to what was copied, so it stays in the clipboard
+    clipboard: ClipboardWriter,
+    /// What extends the editor, listed in the command palette
+    plugins: Vec<Arc<dyn Plugin>>,
     open_dir: PathBuf,
     save_dir: PathBuf,
 }
@@ -35,6 +270,10 @@ enum State {
     Empty(EmptyState),
     Normal {
         param: Param,
+        /// The params as one tree, which the columns follow, if that's the layout
+        tree: Option<Box<TreeView>>,
+        /// A second file shown next to the first, which follows its navigation
+        compare: Option<Box<Param>>,
         edited: bool,
         state: Box<NormalState>,
     },
@@ -44,6 +283,10 @@ enum State {
 enum EmptyState {
     View,
     Open(Box<Explorer>),
+    OpenArc(Box<ArcPrompt>),
+    ConfirmDownload(Confirm),
+    ConfirmRestore(Confirm, Session),
+    Warnings(WarningsPanel),
 }
 
 #[derive(Debug)]
@@ -51,243 +294,2893 @@ enum NormalState {
     View,
     Open(Explorer),
     Save(Explorer),
+    /// Picking where to save the selected param on its own
+    Export(Explorer, Box<ParamStruct>),
+    OpenCompare(Explorer),
+    /// Picking a file to add to the open one
+    Import(Explorer),
+    /// Asking for the key of an imported file's root struct, with the name of the file
+    ImportKey(Box<KeyPrompt>, Box<ParamStruct>, String),
     ConfirmExit(Confirm),
     ConfirmOpen(Confirm),
+    /// Typing the path of a file in the game to open
+    OpenArc(Box<ArcPrompt>),
+    /// Asking before opening another file, like one of the files opened together
+    ConfirmSwitch(Confirm, PathBuf),
+    ConfirmNew(Confirm),
+    ConfirmRevert(Confirm),
+    ConfirmDownload(Confirm),
+    Crack(HashCracker),
+    Report(HashReport),
+    Replace(Box<FindReplace>),
+    Transform(Box<TransformInput>),
+    Script(Box<ScriptInput>),
+    Palette(Box<Palette>),
+    /// Picking where to export the file with a plugin's format
+    PluginExport(Explorer, Arc<dyn Exporter>),
+    Stats(StatsPanel),
+    /// The structural differences from the compared file
+    Structure(Box<StructurePanel>),
+    Warnings(WarningsPanel),
+    History(Box<HistoryPanel>),
+    Snapshots(Box<SnapshotPanel>),
+    /// Writing the note for the param at the path
+    Note(Box<NotePrompt>, ParamPath),
+    Paths(Box<PathList>),
+    /// Typing a vim command after `:`
+    Command(Box<CommandLine>),
 }
 
 impl Root {
-    pub fn new(param: Option<ParamKind>, sorted_labels: Arc<Mutex<BTreeSet<String>>>) -> Self {
+    pub fn new(
+        sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+        theme: Theme,
+        float_format: FloatFormat,
+        config: Config,
+    ) -> Self {
         let open_dir = current_dir().unwrap();
         let save_dir = open_dir.clone();
-        if let Some(some) = param {
-            let param = Param::new(
-                ParamParent::Struct(some.try_into_owned().unwrap()),
-                sorted_labels.clone(),
-            );
-            Self {
-                state: State::Normal {
-                    param,
-                    edited: false,
-                    state: Box::new(NormalState::View),
-                },
-                sorted_labels,
-                open_dir,
-                save_dir,
+        let settings = Settings {
+            theme,
+            float_format,
+            float_step: config.float_step.unwrap_or(DEFAULT_FLOAT_STEP),
+            ..Default::default()
+        };
+        Self {
+            state: State::Empty(EmptyState::View),
+            sorted_labels,
+            settings: Arc::new(Mutex::new(settings)),
+            history: Arc::new(Mutex::new(InputHistory::load())),
+            keys: config.keys,
+            layout: config.layout,
+            labels: config.labels.iter().cloned().collect(),
+            labels_target: config.labels,
+            label_collisions: vec![],
+            schema: None,
+            explorer: config.explorer,
+            game_root: config.game_root,
+            export_format: config.export_format,
+            autosave: Some(Duration::from_secs(config.autosave)).filter(|secs| !secs.is_zero()),
+            last_save: Instant::now(),
+            file: None,
+            files: vec![],
+            unsaved_name: None,
+            modified: None,
+            last_check: Instant::now(),
+            jobs: VecDeque::new(),
+            saving: None,
+            disk: None,
+            saved: None,
+            snapshots: vec![],
+            sidecar: Sidecar::default(),
+            notification: None,
+            error: None,
+            details: false,
+            breadcrumbs: vec![],
+            title: String::new(),
+            clipboard: ClipboardWriter::default(),
+            plugins: vec![Arc::new(Builtin)],
+            open_dir,
+            save_dir,
+        }
+    }
+
+    /// How params are shown, shared with the params and the tree
+    fn settings(&self) -> Settings {
+        self.settings
+            .lock()
+            .map(|settings| *settings)
+            .unwrap_or_default()
+    }
+
+    /// The colors everything is drawn in, kept with the rest of the display settings
+    fn theme(&self) -> Theme {
+        self.settings().theme
+    }
+
+    /// Adds the plugin's commands and exporters to the command palette
+    pub fn add_plugin(&mut self, plugin: Arc<dyn Plugin>) {
+        log::info!("added the plugin {}", plugin.name());
+        self.plugins.push(plugin);
+    }
+
+    /// Asks whether to reopen the file from the last session, and return to the same place in it
+    pub fn offer_session(&mut self, session: Session) {
+        let msg = format!("Restore the last session ({})?", session.file.display());
+        self.state = State::Empty(EmptyState::ConfirmRestore(
+            Confirm::new(&msg, self.theme()),
+            session,
+        ));
+    }
+
+    /// The current session, if a file is open
+    pub fn session(&self) -> Option<Session> {
+        match (&self.state, &self.file) {
+            (State::Normal { param, .. }, Some(file)) => Some(Session {
+                file: file.clone(),
+                levels: param.session(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Shows an error in the corner of the screen until it expires
+    pub fn show_error(&mut self, message: String) {
+        self.notification = Some(Notification::new(
+            message,
+            NotificationKind::Error,
+            self.theme(),
+        ));
+    }
+
+    /// Shows a popup about something which failed, which stays until it's dismissed
+    fn show_error_popup<T: Into<String>>(&mut self, title: T, message: String) {
+        let title = title.into();
+        log::error!("{}: {}", title, message);
+        self.error = Some(ErrorPopup::new(title, message, self.theme()));
+    }
+
+    /// Starts downloading the param labels in the background
+    fn download_labels(&mut self) {
+        log::info!("downloading the param labels");
+        let busy = Busy::new(String::from("Downloading labels"), self.theme());
+        let task = Task::spawn(download_labels);
+        self.jobs.push_back(Job {
+            busy,
+            kind: JobKind::DownloadLabels { task },
+        });
+    }
+
+    /// Replaces the labels file with the downloaded labels, reporting if either failed
+    fn finish_download_labels(&mut self, downloaded: Option<Result<Vec<u8>, io::Error>>) {
+        let path = match self.labels_target.clone() {
+            Some(path) => Ok(path),
+            None => default_labels_path(),
+        };
+        let installed = match (downloaded, path) {
+            (Some(Ok(contents)), Ok(path)) => {
+                // without labels files to read, the downloaded one is read rather than looking in
+                // the current directory first
+                let paths = if self.labels.is_empty() {
+                    vec![path.clone()]
+                } else {
+                    self.labels.clone()
+                };
+                install_labels(&self.sorted_labels, &path, &paths, &contents)
+                    .map(|collisions| (collisions, path))
             }
-        } else {
-            Self {
-                state: State::Empty(EmptyState::View),
-                sorted_labels,
-                open_dir,
-                save_dir,
+            (Some(Err(err)), _) | (_, Err(err)) => Err(err),
+            (None, _) => Err(io::Error::other("the download was interrupted")),
+        };
+        match installed {
+            Ok((collisions, path)) => {
+                log::info!("downloaded the param labels to {}", path.display());
+                let mut message = format!("Downloaded the latest labels to {}", path.display());
+                // labels from the command line are still read instead
+                if !self.labels.is_empty() && !self.labels.contains(&path) {
+                    message.push_str(", but the labels files from --labels are still read");
+                }
+                self.notification = Some(Notification::new(
+                    message,
+                    NotificationKind::Info,
+                    self.theme(),
+                ));
+                self.set_label_collisions(collisions);
             }
+            Err(err) => self.show_error_popup("Failed to download labels", err.to_string()),
         }
     }
 
-    fn open(&mut self, path: PathBuf) -> Result<(), std::io::Error> {
-        if let Some(parent) = path.parent() {
-            self.open_dir = parent.to_path_buf();
+    /// Reads the param labels file again, e.g. after the user added labels to it
+    fn reload_labels(&mut self) {
+        if let Some(collisions) = load_labels(&self.sorted_labels, &self.labels) {
+            self.set_label_collisions(collisions);
+        }
+    }
+
+    /// Reads these labels files from now on, instead of the one from the config
+    pub fn set_label_files(&mut self, paths: Vec<PathBuf>) {
+        self.labels = paths;
+    }
+
+    /// Keeps the hashes which the labels file gives more than one label, pointing them out if
+    /// there are any
+    pub fn set_label_collisions(&mut self, collisions: Vec<Collision>) {
+        if !collisions.is_empty() {
+            self.notification = Some(Notification::new(
+                format!(
+                    "{} hash(es) have more than one label. {}: show warnings",
+                    collisions.len(),
+                    self.keys.warnings
+                ),
+                NotificationKind::Info,
+                self.theme(),
+            ));
+        }
+        self.label_collisions = collisions;
+    }
+
+    /// Checks files against the schema from now on, including the one which is open
+    pub fn set_schema(&mut self, schema: Option<Schema>) {
+        self.schema = schema;
+        self.notify_file_warnings();
+    }
+
+    /// Points out the params in the open file which don't match the schema, or else the hashes
+    /// it uses which have more than one label, if there are any
+    fn notify_file_warnings(&mut self) {
+        if let State::Normal { param, .. } = &self.state {
+            let root = param.snapshot();
+            let violations = self
+                .schema
+                .as_ref()
+                .map_or(0, |schema| schema.validate(&root).len());
+            let usage = hash_usage(&root);
+            let used = self
+                .label_collisions
+                .iter()
+                .filter(|collision| usage.contains_key(&collision.hash))
+                .count();
+            let message = if violations > 0 {
+                format!("{} param(s) don't match the schema", violations)
+            } else if used > 0 {
+                format!("The file uses {} hash(es) with more than one label", used)
+            } else {
+                return;
+            };
+            self.notification = Some(Notification::new(
+                format!("{}. {}: show warnings", message, self.keys.warnings),
+                NotificationKind::Info,
+                self.theme(),
+            ));
+        }
+    }
+
+    /// Everything to warn about: the params in the open file which don't match the schema, then
+    /// the hashes with more than one label, starting with the ones the file uses the most
+    fn warnings(&self) -> Vec<Warning> {
+        let root = match &self.state {
+            State::Normal { param, .. } => Some(param.snapshot()),
+            State::Empty(_) => None,
+        };
+        let usage = root.as_deref().map(hash_usage).unwrap_or_default();
+        let mut warnings = match (&self.schema, &root) {
+            (Some(schema), Some(root)) => schema
+                .validate(root)
+                .into_iter()
+                .map(|violation| Warning {
+                    message: format!("{} {}", violation.path, violation.message),
+                    path: Some(violation.path),
+                })
+                .collect(),
+            _ => vec![],
+        };
+        let label_arc = Hash40::label_map();
+        let labels = match label_arc.lock() {
+            Ok(labels) => labels,
+            Err(err) => err.into_inner(),
+        };
+        let mut collisions = self
+            .label_collisions
+            .iter()
+            .map(|collision| {
+                let uses = usage.get(&collision.hash).copied().unwrap_or_default();
+                let mut warning = format!(
+                    "0x{:010x} has the labels {}, and is shown as {}",
+                    collision.hash.0,
+                    collision.labels.join(", "),
+                    labels.label_of(collision.hash).unwrap_or_default()
+                );
+                if uses > 0 {
+                    warning.push_str(&format!(". The file uses it {} time(s)", uses));
+                }
+                (uses, warning)
+            })
+            .collect::<Vec<_>>();
+        collisions.sort_by_key(|(uses, _)| std::cmp::Reverse(*uses));
+        warnings.extend(collisions.into_iter().map(|(_, message)| Warning {
+            message,
+            path: None,
+        }));
+        warnings
+    }
+
+    /// Checks on any background task. Returns whether the app needs to be redrawn
+    pub fn update(&mut self) -> bool {
+        let jobs_changed = self.poll_jobs();
+        let saving_changed = self.poll_saving();
+        let disk_changed = self.poll_disk();
+        let file_changed = self.poll_file_changes();
+        self.autosave();
+        self.update_title();
+        let state_changed = match &mut self.state {
+            State::Normal { state, .. } => match state.as_mut() {
+                NormalState::Crack(cracker) => cracker.update(),
+                NormalState::Replace(replace) => replace.update(),
+                NormalState::Paths(paths) => paths.update(),
+                _ => false,
+            },
+            State::Empty(..) => false,
+        };
+        jobs_changed || saving_changed || disk_changed || file_changed || state_changed
+    }
+
+    /// How long the app can wait for input before something changes without it, like a
+    /// background task making progress or a notification going away. Nothing is drawn again
+    /// meanwhile
+    pub fn next_update(&self) -> Duration {
+        let panel_busy = match &self.state {
+            State::Normal { state, .. } => match state.as_ref() {
+                NormalState::Crack(cracker) => cracker.is_busy(),
+                NormalState::Replace(replace) => replace.is_busy(),
+                NormalState::Paths(paths) => paths.is_busy(),
+                _ => false,
+            },
+            State::Empty(..) => false,
+        };
+        let working = !self.jobs.is_empty()
+            || self.saving.is_some()
+            || matches!(self.disk, Some(DiskBaseline::Reading(_)))
+            || panel_busy;
+        if working {
+            return SPINNER_FRAME;
+        }
+        let edited = matches!(self.state, State::Normal { edited: true, .. });
+        let due = [
+            self.notification
+                .as_ref()
+                .and_then(Notification::expires_in),
+            self.file
+                .as_ref()
+                .map(|_| CHANGE_CHECK_INTERVAL.saturating_sub(self.last_check.elapsed())),
+            self.autosave
+                .filter(|_| edited)
+                .map(|interval| interval.saturating_sub(self.last_save.elapsed())),
+        ];
+        // something overdue, like an autosave waiting for a prompt to close, is checked for
+        // again each frame rather than constantly
+        due.iter()
+            .flatten()
+            .fold(IDLE_WAIT, |wait, due| wait.min(*due))
+            .max(SPINNER_FRAME)
+    }
+
+    /// Quits on Ctrl+C or an interrupt signal from wherever the user is, stopping any background
+    /// work. With unsaved changes, it asks first, the same as Esc
+    pub fn interrupt(&mut self) -> AppResponse {
+        let theme = self.theme();
+        log::info!("interrupted");
+        self.jobs.clear();
+        self.error = None;
+        let others_edited = self.others_edited();
+        match &mut self.state {
+            State::Normal { edited, state, .. } if *edited || others_edited => {
+                if !matches!(state.as_ref(), NormalState::ConfirmExit(_)) {
+                    **state = NormalState::ConfirmExit(Confirm::new(EXIT_MSG, theme));
+                }
+                AppResponse::None
+            }
+            _ => AppResponse::Exit,
+        }
+    }
+
+    /// Whether the key suspends the editor, which is left to the terminal rather than the app
+    pub fn suspends(&self, key: &KeyEvent) -> bool {
+        self.keys.suspend.matches(key)
+    }
+
+    /// The terminal title: the name of the open file, with `*` when it has unsaved changes
+    pub fn title(&self) -> String {
+        match (&self.state, self.file_name()) {
+            (State::Normal { edited, .. }, Some(name)) => {
+                format!("prickly - {}{}", name, if *edited { "*" } else { "" })
+            }
+            _ => String::from("prickly - prc file editor"),
+        }
+    }
+
+    /// The name of the open file, or what it's called until it's saved
+    fn file_name(&self) -> Option<String> {
+        self.file
+            .as_ref()
+            .map(|file| {
+                file.file_name()
+                    .unwrap_or(file.as_os_str())
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .or_else(|| self.unsaved_name.clone())
+    }
+
+    fn update_title(&mut self) {
+        let title = self.title();
+        if title != self.title {
+            if let Err(err) = tui_components::set_title(&title) {
+                log::warn!("failed to set the terminal title: {}", err);
+            }
+            self.title = title;
         }
-        match prc::open(&path) {
-            Ok(prc) => {
-                self.state = State::Normal {
-                    param: Param::new(ParamParent::Struct(prc), self.sorted_labels.clone()),
-                    edited: false,
-                    state: Box::new(NormalState::View),
+    }
+
+    /// Starts highlighting the params which differ from the saved file, reading it again
+    /// in the background, or stops highlighting them
+    fn toggle_disk_diff(&mut self) {
+        let message = if self.disk.take().is_some() {
+            if let State::Normal {
+                param,
+                tree,
+                compare,
+                ..
+            } = &mut self.state
+            {
+                param.clear_marks();
+                if let Some(compare) = compare {
+                    compare.follow(param);
+                }
+                if let Some(tree) = tree {
+                    tree.set_baseline(None);
+                }
+            }
+            String::from("Stopped highlighting changes from the saved file")
+        } else if let Some(file) = self.file.clone() {
+            log::info!("reading {} to compare with", file.display());
+            let message = format!("Highlighting changes from {}", file.display());
+            self.disk = Some(DiskBaseline::Reading(Task::spawn(move || {
+                format::open(file)
+            })));
+            message
+        } else {
+            String::from("The file hasn't been saved yet")
+        };
+        self.notification = Some(Notification::new(
+            message,
+            NotificationKind::Info,
+            self.theme(),
+        ));
+    }
+
+    /// Picks up the saved file once it has been read. Returns whether anything needs redrawing
+    fn poll_disk(&mut self) -> bool {
+        let task = match self.disk.take() {
+            Some(DiskBaseline::Reading(task)) if task.is_finished() => task,
+            disk => {
+                self.disk = disk;
+                return false;
+            }
+        };
+        match task.join() {
+            Some(Ok(prc)) => self.disk = Some(DiskBaseline::Loaded(Arc::new(prc))),
+            failed => {
+                let reason = match failed {
+                    Some(Err(err)) => err.to_string(),
+                    _ => String::from("the load was interrupted"),
                 };
-                tui_components::set_title(&path.to_string_lossy())?;
-                Ok(())
+                self.show_error_popup("Failed to read the saved file", reason);
             }
-            Err(err) => Err(err),
         }
+        true
     }
 
-    fn save(&mut self, path: PathBuf) {
+    /// Checks whether another program changed the open file since it was opened or saved, and
+    /// if so offers to reload it. Returns whether anything needs redrawing
+    fn poll_file_changes(&mut self) -> bool {
+        if self.last_check.elapsed() < CHANGE_CHECK_INTERVAL
+            || !self.jobs.is_empty()
+            || self.saving.is_some()
+        {
+            return false;
+        }
+        self.last_check = Instant::now();
+        let file = match &self.file {
+            Some(file) => file,
+            None => return false,
+        };
+        let modified = metadata(file).and_then(|meta| meta.modified()).ok();
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+        log::info!("{} was changed by another program", file.display());
+        let name = file
+            .file_name()
+            .unwrap_or(file.as_os_str())
+            .to_string_lossy()
+            .into_owned();
+        // asked once per change, whichever way the user answers
+        self.modified = modified;
+        self.notification = Some(Notification::new(
+            format!(
+                "{} was changed by another program. {}: reload  Esc: keep yours",
+                name, self.keys.revert
+            ),
+            NotificationKind::Prompt,
+            self.theme(),
+        ));
+        true
+    }
+
+    /// Whether the user is being asked about the open file being changed by another program
+    fn is_prompting_reload(&self) -> bool {
+        self.notification
+            .as_ref()
+            .is_some_and(|notification| notification.kind() == NotificationKind::Prompt)
+    }
+
+    /// Saves the open file if the autosave interval has passed since it was last saved,
+    /// as long as nothing else is going on
+    fn autosave(&mut self) {
+        let due = self
+            .autosave
+            .is_some_and(|interval| self.last_save.elapsed() >= interval);
+        let idle = self.jobs.is_empty() && self.saving.is_none() && self.error.is_none();
+        let edited = matches!(
+            &self.state,
+            State::Normal { edited: true, state, .. } if matches!(state.as_ref(), NormalState::View)
+        );
+        if let (true, true, true, Some(file)) = (due, idle, edited, &self.file) {
+            log::info!("autosaving");
+            self.save(file.clone());
+        }
+    }
+
+    /// Gives a file name typed without an extension the export format's extension
+    fn export_path(&self, mut path: PathBuf) -> PathBuf {
+        if let (None, Some(format)) = (path.extension(), self.export_format) {
+            path.set_extension(format.extension());
+        }
+        path
+    }
+
+    /// Starts reading a file in the background. It's shown once it has loaded
+    pub fn open(&mut self, path: PathBuf) {
+        self.start_open(path, vec![], None);
+    }
+
+    /// Opens the first of the files, at the param if one is given, and loads the others into
+    /// slots of their own to switch to
+    pub fn open_all(&mut self, paths: Vec<PathBuf>, at: Option<ParamPath>) {
+        if let Some(first) = paths.first() {
+            self.start_open(first.clone(), vec![], at);
+        }
+        if paths.len() < 2 {
+            return;
+        }
+        // the open file's path is kept in full, so these are too to find it among them
+        self.files = paths
+            .into_iter()
+            .map(|path| FileSlot {
+                path: canonicalize(&path).unwrap_or(path),
+                file: None,
+            })
+            .collect();
+        for path in self.files[1..].iter().map(|slot| slot.path.clone()) {
+            log::info!("opening {}", path.display());
+            let busy = Busy::new(format!("Loading {}", path.display()), self.theme());
+            let task = {
+                let path = path.clone();
+                Task::spawn(move || format::open(path))
+            };
+            self.jobs.push_back(Job {
+                busy,
+                kind: JobKind::OpenSlot { path, task },
+            });
+        }
+    }
+
+    /// Shows another of the files opened together, as it was left. The shown file is put aside
+    /// in its slot if it's one of them too
+    fn switch_to(&mut self, path: PathBuf) {
+        // a save in progress is recorded for the file it was started from
+        let _ = self.wait_for_save();
+        let parked = self
+            .files
+            .iter_mut()
+            .find(|slot| slot.path == path)
+            .and_then(|slot| slot.file.take());
+        match parked {
+            Some(file) => {
+                self.park();
+                self.show_slot(path, *file);
+            }
+            // like the first file, when it failed to load
+            None => self.open(path),
+        }
+    }
+
+    /// Puts the shown file aside in its slot, if it's one of the files opened together
+    fn park(&mut self) {
+        let file = self.file.as_ref();
+        let slot = match self.files.iter_mut().find(|slot| Some(&slot.path) == file) {
+            Some(slot) => slot,
+            None => return,
+        };
         if let State::Normal {
             param,
+            tree,
+            compare,
             edited,
-            state,
+            ..
+        } = std::mem::replace(&mut self.state, State::Empty(EmptyState::View))
+        {
+            log::debug!("put {} aside", slot.path.display());
+            slot.file = Some(Box::new(SlotFile {
+                param,
+                tree,
+                compare,
+                edited,
+                saved: self.saved.take(),
+                disk: self.disk.take(),
+                modified: self.modified,
+                sidecar: std::mem::take(&mut self.sidecar),
+            }));
+        }
+    }
+
+    /// Shows a file which was put aside, or loaded to switch to
+    fn show_slot(&mut self, path: PathBuf, file: SlotFile) {
+        log::info!("switched to {}", path.display());
+        self.file = Some(path);
+        self.unsaved_name = None;
+        self.modified = file.modified;
+        self.saved = file.saved;
+        self.disk = file.disk;
+        self.sidecar = file.sidecar;
+        self.last_save = Instant::now();
+        if self.is_prompting_reload() {
+            self.notification = None;
+        }
+        // the layout may have changed while the file was aside
+        let relayout = file.tree.is_some() != (self.layout == ViewMode::Tree);
+        self.state = State::Normal {
+            param: file.param,
+            tree: file.tree,
+            compare: file.compare,
+            edited: file.edited,
+            state: Box::new(NormalState::View),
+        };
+        if relayout {
+            self.set_layout(self.layout);
+        }
+    }
+
+    /// Whether a file other than the shown one has unsaved changes
+    fn others_edited(&self) -> bool {
+        self.files
+            .iter()
+            .any(|slot| slot.file.as_ref().is_some_and(|file| file.edited))
+    }
+
+    /// Shows params which weren't read from a file, like ones piped in. They're saved to a
+    /// file chosen the first time they're saved
+    pub fn open_unsaved<T: Into<String>>(&mut self, prc: ParamStruct, name: T) {
+        let name = name.into();
+        log::info!("opened {}", name);
+        self.park();
+        self.file = None;
+        self.modified = None;
+        self.disk = None;
+        self.unsaved_name = Some(name);
+        self.show_param(prc, &[]);
+    }
+
+    /// Starts a file from scratch, with an empty root struct
+    pub fn new_file(&mut self) {
+        self.open_unsaved(ParamStruct::default(), "untitled");
+    }
+
+    /// Starts reading a file in the background, then re-applies the given session to it, or goes
+    /// to the param at the path
+    fn start_open(&mut self, path: PathBuf, levels: Vec<LevelSession>, at: Option<ParamPath>) {
+        if let Some(parent) = path.parent().filter(|parent| parent.is_dir()) {
+            self.open_dir = parent.to_path_buf();
+        }
+        log::info!("opening {}", path.display());
+        let busy = Busy::new(format!("Loading {}", path.display()), self.theme());
+        let task = {
+            let path = path.clone();
+            Task::spawn(move || format::open(path))
+        };
+        self.jobs.push_back(Job {
+            busy,
+            kind: JobKind::Open {
+                path,
+                task,
+                levels,
+                at,
+            },
+        });
+    }
+
+    /// Selects the next or previous param edited since the file was opened or saved, in the
+    /// order they're in the file, going around from either end
+    fn select_edited(&mut self, forward: bool) {
+        let root = match &self.state {
+            State::Normal { param, .. } => param.snapshot(),
+            State::Empty(_) => return,
+        };
+        let edited = match &self.saved {
+            // params which were removed have nowhere to be selected
+            Some(saved) => diff(saved, &root)
+                .into_iter()
+                .filter(|difference| !matches!(difference.change, Change::Removed(_)))
+                .map(|difference| difference.path)
+                .collect::<HashSet<_>>(),
+            None => HashSet::new(),
+        };
+        let message = match self.select_among(&root, &edited, forward) {
+            Some((index, count)) => format!("Edited param {} of {}", index + 1, count),
+            None => String::from("Nothing has been edited since the file was opened or saved"),
+        };
+        self.notification = Some(Notification::new(
+            message,
+            NotificationKind::Info,
+            self.theme(),
+        ));
+    }
+
+    /// Selects the next or previous bookmarked param, in the order they're in the file, going
+    /// around from either end
+    fn select_bookmark(&mut self, forward: bool) {
+        let root = match &self.state {
+            State::Normal { param, .. } => param.snapshot(),
+            State::Empty(_) => return,
+        };
+        let bookmarks = self.sidecar.bookmarks();
+        let message = match self.select_among(&root, &bookmarks, forward) {
+            Some((index, count)) => format!("Bookmark {} of {}", index + 1, count),
+            None => format!(
+                "Nothing in the file is bookmarked. {} bookmarks the selected param",
+                self.keys.bookmark
+            ),
+        };
+        self.notification = Some(Notification::new(
+            message,
+            NotificationKind::Info,
+            self.theme(),
+        ));
+    }
+
+    /// Selects the next or previous of the params after the selected one, in the order they're
+    /// in the file, going around from either end. Returns which of them was selected and how
+    /// many of them are in the file, or None if none are
+    fn select_among(
+        &mut self,
+        root: &ParamStruct,
+        paths: &HashSet<ParamPath>,
+        forward: bool,
+    ) -> Option<(usize, usize)> {
+        let selected = match &self.state {
+            State::Normal { param, .. } => param.selected_path(),
+            State::Empty(_) => return None,
+        };
+        // where the selected param and each of the others are in the file
+        let mut position = 0;
+        let mut current = None;
+        let mut found = vec![];
+        visit(root, |path, _| {
+            if *path == selected {
+                current = Some(position);
+            }
+            if paths.contains(path) {
+                found.push((position, path.clone()));
+            }
+            position += 1;
+        });
+        let next = if forward {
+            found
+                .iter()
+                .position(|(position, _)| current.is_none_or(|current| *position > current))
+                .or_else(|| (!found.is_empty()).then_some(0))
+        } else {
+            found
+                .iter()
+                .rposition(|(position, _)| current.is_none_or(|current| *position < current))
+                .or_else(|| found.len().checked_sub(1))
+        }?;
+        self.reveal(&found[next].1);
+        Some((next, found.len()))
+    }
+
+    /// Opens every level down to the param at the path and selects it, in the tree too
+    fn reveal(&mut self, path: &ParamPath) {
+        if let State::Normal {
+            param,
+            tree,
+            compare,
+            ..
         } = &mut self.state
         {
-            if let Some(parent) = path.parent() {
-                self.save_dir = parent.to_path_buf();
+            param.reveal(&path.0);
+            if let Some(tree) = tree {
+                tree.reveal(path);
             }
-            let param = param.recreate_param();
-            if prc::save(path, param.try_into_ref().unwrap()).is_ok() {
-                *edited = false;
+            if let Some(compare) = compare {
+                compare.follow(param);
             }
-            // TODO: error message in case of failure
-            *state = Box::new(NormalState::View);
         }
     }
-}
 
-impl App for Root {
-    fn handle_event(&mut self, event: Event) -> AppResponse {
-        match &mut self.state {
-            State::Empty(EmptyState::View) => {
-                if let Event::Key(key_event) = event {
-                    match key_event.code {
-                        KeyCode::Esc => return AppResponse::Exit,
-                        KeyCode::Char('o')
-                            if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
-                        {
-                            self.state = State::Empty(EmptyState::Open(Box::new(Explorer::new(
-                                self.open_dir.clone(),
-                                ExplorerMode::Open,
-                            ))))
-                        }
-                        KeyCode::Char('s')
-                            if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
-                        {
-                            self.state = State::Empty(EmptyState::Open(Box::new(Explorer::new(
-                                self.open_dir.clone(),
-                                ExplorerMode::Open,
-                            ))))
+    /// Starts reading a second file to show next to the current one
+    pub fn open_compare(&mut self, path: PathBuf) {
+        log::info!("opening {} to compare", path.display());
+        let busy = Busy::new(format!("Loading {}", path.display()), self.theme());
+        let task = {
+            let path = path.clone();
+            Task::spawn(move || format::open(path))
+        };
+        self.jobs.push_back(Job {
+            busy,
+            kind: JobKind::OpenCompare { path, task },
+        });
+    }
+
+    /// Applies every finished job, in the order they were started.
+    /// Returns whether anything needs to be redrawn
+    fn poll_jobs(&mut self) -> bool {
+        // while waiting, the spinner needs redrawing
+        let busy = !self.jobs.is_empty();
+        while self.jobs.front().is_some_and(|job| job.kind.is_finished()) {
+            let job = self.jobs.pop_front().unwrap();
+            self.finish(job.kind);
+        }
+        busy
+    }
+
+    fn finish(&mut self, job: JobKind) {
+        let theme = self.theme();
+        match job {
+            JobKind::Open {
+                path,
+                task,
+                levels,
+                at,
+            } => match task.join() {
+                Some(Ok(prc)) => {
+                    log::info!("opened {}", path.display());
+                    let file = canonicalize(&path).unwrap_or_else(|_| path.clone());
+                    // reloading the shown file drops its edits, but any other open file is kept
+                    if self.file.as_ref() != Some(&file) {
+                        self.park();
+                    }
+                    // a file which was aside is read again, like any other
+                    if let Some(slot) = self.files.iter_mut().find(|slot| slot.path == file) {
+                        slot.file = None;
+                    }
+                    self.file = Some(file);
+                    self.modified = metadata(&path).and_then(|meta| meta.modified()).ok();
+                    self.last_save = Instant::now();
+                    if self.is_prompting_reload() {
+                        self.notification = None;
+                    }
+                    let missing = at.as_ref().filter(|at| at.get(&prc).is_none()).cloned();
+                    self.show_param(prc, &levels);
+                    match (at, missing) {
+                        (Some(at), None) => self.reveal(&at),
+                        (_, Some(missing)) => {
+                            self.show_error(format!("There's no {} in {}", missing, path.display()))
                         }
-                        _ => {}
+                        (None, None) => {}
                     }
                 }
-            }
-            State::Empty(EmptyState::Open(open)) => match open.handle_event(event) {
-                ExplorerResponse::Open(path) => self.open(path).unwrap_or_default(),
-                ExplorerResponse::Save(_) => {}
-                ExplorerResponse::Cancel => self.state = State::Empty(EmptyState::View),
-                ExplorerResponse::Handled => {}
-                ExplorerResponse::None => {}
+                failed => self.show_open_error(&path, failed),
             },
-            State::Normal {
-                param,
-                edited,
-                state,
-            } => match state.as_mut() {
-                NormalState::View => match param.handle_event(event) {
-                    ParamResponse::None => {
-                        if let Event::Key(key) = event {
-                            match key.code {
-                                KeyCode::Esc => {
-                                    if *edited {
-                                        let msg = "You have unsaved changes. Are you sure you want to exit?";
-                                        *state =
-                                            Box::new(NormalState::ConfirmExit(Confirm::new(msg)));
-                                    } else {
-                                        return AppResponse::Exit;
-                                    }
-                                }
-                                KeyCode::Char('o')
-                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
-                                {
-                                    if *edited {
-                                        let msg = "You have unsaved changes. Are you sure you want to open a new file?";
-                                        *state =
-                                            Box::new(NormalState::ConfirmOpen(Confirm::new(msg)));
-                                    } else {
-                                        *state = Box::new(NormalState::Open(Explorer::new(
-                                            self.open_dir.clone(),
-                                            ExplorerMode::Open,
-                                        )));
-                                    }
-                                }
-                                KeyCode::Char('s')
-                                    if key.modifiers.contains(KeyModifiers::CONTROL) =>
-                                {
-                                    *state = Box::new(NormalState::Save(Explorer::new(
-                                        self.save_dir.clone(),
-                                        ExplorerMode::Save,
-                                    )));
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                    ParamResponse::Handled {
-                        edited: component_edited,
-                    } => {
-                        if component_edited {
-                            *edited = true;
-                        }
+            JobKind::OpenCompare { path, task } => {
+                let result = task.join();
+                if let State::Normal {
+                    param,
+                    compare,
+                    state,
+                    ..
+                } = &mut self.state
+                {
+                    **state = NormalState::View;
+                    if let Some(Ok(prc)) = result {
+                        let mut other = Param::new(
+                            prc,
+                            self.sorted_labels.clone(),
+                            self.settings.clone(),
+                            self.history.clone(),
+                        );
+                        other.follow(param);
+                        *compare = Some(Box::new(other));
+                        return;
                     }
-                    ParamResponse::Exit => {}
-                },
-                NormalState::Open(open) => match open.handle_event(event) {
-                    ExplorerResponse::Open(path) => self.open(path).unwrap_or_default(),
-                    ExplorerResponse::Cancel => *state = Box::new(NormalState::View),
-                    ExplorerResponse::Save(_) => {}
-                    ExplorerResponse::Handled => {}
-                    ExplorerResponse::None => {}
-                },
-                NormalState::Save(save) => match save.handle_event(event) {
-                    ExplorerResponse::Save(path) => self.save(path),
-                    ExplorerResponse::Cancel => *state = Box::new(NormalState::View),
-                    ExplorerResponse::Open(_) => {}
-                    ExplorerResponse::Handled => {}
-                    ExplorerResponse::None => {}
-                },
-                NormalState::ConfirmExit(confirm) => match confirm.handle_event(event) {
-                    ConfirmResponse::Confirm(answer) => {
-                        if answer {
-                            return AppResponse::Exit;
-                        } else {
-                            *state = Box::new(NormalState::View);
-                        }
+                } else if let Some(Ok(_)) = result {
+                    // e.g. the file given with --compare loaded, but the one to open didn't
+                    let message = format!(
+                        "{} loaded, but a file has to be open to compare it with",
+                        path.display()
+                    );
+                    self.show_error_popup("Nothing to compare with", message);
+                    return;
+                }
+                self.show_open_error(&path, result);
+            }
+            JobKind::OpenSlot { path, task } => match task.join() {
+                Some(Ok(prc)) => {
+                    log::info!("opened {}", path.display());
+                    let file = self.slot_file(&path, prc);
+                    // e.g. the first file failed to load, so this one is shown instead
+                    if let State::Empty(_) = self.state {
+                        self.show_slot(path, file);
+                    } else if let Some(slot) = self.files.iter_mut().find(|slot| slot.path == path)
+                    {
+                        slot.file = Some(Box::new(file));
                     }
-                    ConfirmResponse::Handled => {}
-                    ConfirmResponse::None => {}
-                },
-                NormalState::ConfirmOpen(confirm) => match confirm.handle_event(event) {
-                    ConfirmResponse::Confirm(answer) => {
-                        if answer {
-                            *state = Box::new(NormalState::Open(Explorer::new(
-                                self.open_dir.clone(),
-                                ExplorerMode::Open,
-                            )));
+                }
+                failed => {
+                    self.files.retain(|slot| slot.path != path);
+                    self.show_open_error(&path, failed);
+                }
+            },
+            JobKind::Import { path, task } => {
+                let result = task.join();
+                if let State::Normal { param, state, .. } = &mut self.state {
+                    **state = NormalState::View;
+                    if let Some(Ok(prc)) = result {
+                        let name = path
+                            .file_name()
+                            .unwrap_or(path.as_os_str())
+                            .to_string_lossy()
+                            .into_owned();
+                        if param.in_list() {
+                            self.graft(None, prc, name);
                         } else {
-                            *state = Box::new(NormalState::View);
+                            let stem = path
+                                .file_stem()
+                                .map(|stem| stem.to_string_lossy().into_owned())
+                                .unwrap_or_default();
+                            let mut input = HashInput::new(
+                                hash40(&stem),
+                                self.sorted_labels.clone(),
+                                &hash_usage(&param.snapshot()),
+                                theme,
+                            );
+                            input.set_text(&stem);
+                            **state = NormalState::ImportKey(
+                                Box::new(KeyPrompt::new(format!("Key for {}", name), input, theme)),
+                                Box::new(prc),
+                                name,
+                            );
                         }
+                        return;
                     }
-                    ConfirmResponse::Handled => {}
-                    ConfirmResponse::None => {}
-                },
+                }
+                self.show_open_error(&path, result);
+            }
+            JobKind::Export { path, task } => match task.join() {
+                Some(Ok(())) => {
+                    log::info!("exported {}", path.display());
+                    self.notification = Some(Notification::new(
+                        format!("Exported {}", path.display()),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+                failed => {
+                    let reason = match failed {
+                        Some(Err(err)) => err.to_string(),
+                        _ => String::from("the export was interrupted"),
+                    };
+                    let message = format!("{}: {}", path.display(), reason);
+                    self.show_error_popup("Failed to export", message);
+                }
+            },
+            JobKind::Search { find, task } => match task.join() {
+                Some(replacements) if replacements.is_empty() => {
+                    self.show_error(format!("Pattern not found: {}", find))
+                }
+                Some(replacements) => self.replace_values(&replacements),
+                None => {}
             },
+            JobKind::DownloadLabels { task } => self.finish_download_labels(task.join()),
         }
-        AppResponse::None
     }
 
-    fn draw(&mut self, rect: tui_components::tui::layout::Rect, buffer: &mut Buffer) {
-        let explorer_rect = rect.centered(rect.scaled(0.75, 0.75));
-
-        match &mut self.state {
-            State::Empty(EmptyState::View) => {
-                Empty.draw(rect, buffer);
+    /// Replaces whatever was open with the params, at the place in them from the session
+    fn show_param(&mut self, prc: ParamStruct, levels: &[LevelSession]) {
+        let sidecar = match &self.file {
+            Some(file) => load_sidecar(file),
+            None => Ok(Sidecar::default()),
+        };
+        let (view, filters) = match &sidecar {
+            Ok(sidecar) => (sidecar.view, sidecar.filters()),
+            Err(_) => (None, HashMap::new()),
+        };
+        // the file is shown the way it was when its sidecar was written
+        if let Some(view) = view {
+            self.layout = view.layout;
+            self.details = view.details;
+            if let Ok(mut settings) = self.settings.lock() {
+                view.apply(&mut settings);
             }
-            State::Empty(EmptyState::Open(open)) => {
-                open.draw(explorer_rect, buffer);
+        }
+        let mut param = Param::new(
+            prc,
+            self.sorted_labels.clone(),
+            self.settings.clone(),
+            self.history.clone(),
+        )
+        .with_filters(filters);
+        param.restore(levels);
+        self.saved = Some(param.snapshot());
+        if self.disk.is_some() {
+            self.disk = Some(DiskBaseline::Loaded(param.snapshot()));
+        }
+        let tree = (self.layout == ViewMode::Tree).then(|| {
+            let mut tree = TreeView::new(
+                param.snapshot(),
+                self.sorted_labels.clone(),
+                self.settings.clone(),
+            );
+            tree.reveal(&param.selected_path());
+            Box::new(tree)
+        });
+        self.state = State::Normal {
+            param,
+            tree,
+            compare: None,
+            edited: false,
+            state: Box::new(NormalState::View),
+        };
+        self.notify_file_warnings();
+        match sidecar {
+            Ok(sidecar) => self.sidecar = sidecar,
+            Err(err) => {
+                self.sidecar = Sidecar::default();
+                self.show_error(err);
             }
-            State::Normal {
-                param,
-                edited: _,
-                state,
-            } => {
-                let param_buffer = param.draw(rect, buffer);
-                buffer.merge(&param_buffer);
+        }
+    }
 
-                match state.as_mut() {
-                    NormalState::View => {}
-                    NormalState::Open(open) => {
-                        let clear = Clear;
-                        clear.render(explorer_rect, buffer);
-                        open.draw(explorer_rect, buffer)
-                    }
-                    NormalState::Save(save) => {
-                        let clear = Clear;
-                        clear.render(explorer_rect, buffer);
-                        save.draw(explorer_rect, buffer)
-                    }
-                    // TODO: updated boundaries
-                    NormalState::ConfirmExit(confirm) => confirm.draw(rect, buffer),
-                    NormalState::ConfirmOpen(confirm) => confirm.draw(rect, buffer),
+    /// A file loaded to switch to, as it would be shown on opening it
+    fn slot_file(&mut self, path: &Path, prc: ParamStruct) -> SlotFile {
+        let sidecar = load_sidecar(path).unwrap_or_else(|err| {
+            self.show_error(err);
+            Sidecar::default()
+        });
+        let param = Param::new(
+            prc,
+            self.sorted_labels.clone(),
+            self.settings.clone(),
+            self.history.clone(),
+        )
+        .with_filters(sidecar.filters());
+        SlotFile {
+            saved: Some(param.snapshot()),
+            param,
+            tree: None,
+            compare: None,
+            edited: false,
+            disk: None,
+            modified: metadata(path).and_then(|meta| meta.modified()).ok(),
+            sidecar,
+        }
+    }
+
+    /// Switches between showing the params as columns and as one tree. The columns already
+    /// follow the tree, so either layout picks up where the other left off
+    fn set_layout(&mut self, layout: ViewMode) {
+        self.layout = layout;
+        if let State::Normal { param, tree, .. } = &mut self.state {
+            *tree = match layout {
+                ViewMode::Tree => {
+                    let mut new_tree = TreeView::new(
+                        param.snapshot(),
+                        self.sorted_labels.clone(),
+                        self.settings.clone(),
+                    );
+                    new_tree.reveal(&param.selected_path());
+                    Some(Box::new(new_tree))
                 }
-            }
+                ViewMode::Columns => None,
+            };
+        }
+    }
+
+    /// Sets the replaced values in the open file as one edit
+    fn replace_values(&mut self, replacements: &[Replacement]) {
+        if let State::Normal {
+            param,
+            compare,
+            edited,
+            ..
+        } = &mut self.state
+        {
+            let description = format!("Replaced {} value(s)", replacements.len());
+            let applied = param.replace_values(description, replacements);
+            log::debug!("replaced {} values", applied);
+            if applied > 0 {
+                *edited = true;
+            }
+            if let Some(compare) = compare {
+                compare.follow(param);
+            }
+            self.notification = Some(Notification::new(
+                format!("Replaced {} value(s)", applied),
+                NotificationKind::Info,
+                self.theme(),
+            ));
+        }
+    }
+
+    /// Runs a command typed after `:`. Quitting or opening another file with unsaved changes
+    /// asks first, like the keys for them do, unless the command ends with `!`
+    fn run_command(&mut self, command: VimCommand) -> AppResponse {
+        let edited = match &self.state {
+            State::Normal { edited, .. } => *edited,
+            State::Empty(_) => return AppResponse::None,
+        };
+        let ask = |state: &mut State, next: NormalState| {
+            if let State::Normal { state, .. } = state {
+                **state = next;
+            }
+        };
+        match command {
+            VimCommand::Write(path) => match path.or_else(|| self.file.clone()) {
+                Some(path) => self.save(self.export_path(path)),
+                None => self.show_error(NOT_SAVED_MSG.into()),
+            },
+            VimCommand::WriteQuit => match self.file.clone() {
+                Some(path) => {
+                    self.save(self.export_path(path));
+                    // a failed save is shown, and the editor stays open to try again
+                    if self.wait_for_save().is_err() {
+                        return AppResponse::None;
+                    }
+                    if !self.others_edited() {
+                        return AppResponse::Exit;
+                    }
+                    let next = NormalState::ConfirmExit(Confirm::new(EXIT_MSG, self.theme()));
+                    ask(&mut self.state, next);
+                }
+                None => self.show_error(NOT_SAVED_MSG.into()),
+            },
+            VimCommand::Quit { force } if (edited || self.others_edited()) && !force => {
+                let next = NormalState::ConfirmExit(Confirm::new(EXIT_MSG, self.theme()));
+                ask(&mut self.state, next);
+            }
+            VimCommand::Quit { .. } => return AppResponse::Exit,
+            VimCommand::Edit {
+                path: Some(path),
+                force,
+            } => {
+                if edited && !force {
+                    let msg = format!(
+                        "You have unsaved changes. Are you sure you want to open {}?",
+                        path.display()
+                    );
+                    let next = NormalState::ConfirmSwitch(Confirm::new(msg, self.theme()), path);
+                    ask(&mut self.state, next);
+                } else {
+                    self.open(path);
+                }
+            }
+            VimCommand::Edit { path: None, force } => {
+                if self.file.is_none() {
+                    self.show_error(NOT_SAVED_MSG.into());
+                } else if edited && !force {
+                    let msg = "Drop your unsaved changes and reload the file?";
+                    let next = NormalState::ConfirmRevert(Confirm::new(msg, self.theme()));
+                    ask(&mut self.state, next);
+                } else {
+                    self.revert();
+                }
+            }
+            VimCommand::Set(SetOption::Layout(layout)) => self.set_layout(layout),
+            VimCommand::Set(SetOption::Details(switch)) => {
+                self.details = switch.apply(self.details)
+            }
+            VimCommand::Set(option) => {
+                if let Ok(mut settings) = self.settings.lock() {
+                    match option {
+                        SetOption::HexColumn(switch) => {
+                            settings.hex_column = switch.apply(settings.hex_column)
+                        }
+                        SetOption::RowNumbers(switch) => {
+                            settings.row_numbers = switch.apply(settings.row_numbers)
+                        }
+                        SetOption::Scientific(switch) => {
+                            settings.float_format.scientific =
+                                switch.apply(settings.float_format.scientific)
+                        }
+                        SetOption::Hashes(hashes) => settings.hash_display = hashes,
+                        SetOption::Decimals(decimals) => settings.float_format.decimals = decimals,
+                        SetOption::Layout(_) | SetOption::Details(_) => {}
+                    }
+                }
+            }
+            VimCommand::Substitute {
+                find,
+                replace,
+                whole_file,
+            } => {
+                let pattern = match Pattern::new(&find, true) {
+                    Ok(pattern) => pattern,
+                    Err(_) => {
+                        self.show_error(format!("Not a valid regex: {}", find));
+                        return AppResponse::None;
+                    }
+                };
+                let (root, base) = match &self.state {
+                    State::Normal { param, .. } if whole_file => {
+                        (param.snapshot(), ParamPath::default())
+                    }
+                    State::Normal { param, .. } => (param.snapshot(), param.level_path()),
+                    State::Empty(_) => return AppResponse::None,
+                };
+                // a large file takes a while to search, so it's searched in the background
+                let task = Cancellable::spawn(move |progress| {
+                    find_replacements_in(&root, &base, &pattern, &replace, progress)
+                });
+                let busy = Busy::new(format!("Searching for {}", find), self.theme())
+                    .with_progress(task.progress().clone());
+                self.jobs.push_back(Job {
+                    busy,
+                    kind: JobKind::Search { find, task },
+                });
+            }
+        }
+        AppResponse::None
+    }
+
+    /// Opens the file again as it was last saved, at the same place in it
+    fn revert(&mut self) {
+        if let (State::Normal { param, .. }, Some(file)) = (&self.state, &self.file) {
+            let levels = param.session();
+            self.start_open(file.clone(), levels, None);
+        }
+    }
+
+    /// Starts reading a file to add to the open one
+    fn start_import(&mut self, path: PathBuf) {
+        log::info!("importing {}", path.display());
+        let busy = Busy::new(format!("Loading {}", path.display()), self.theme());
+        let task = {
+            let path = path.clone();
+            Task::spawn(move || format::open(path))
+        };
+        self.jobs.push_back(Job {
+            busy,
+            kind: JobKind::Import { path, task },
+        });
+    }
+
+    /// Adds an imported file's root struct after the selected param, under the key if it's
+    /// going into a struct
+    fn graft(&mut self, key: Option<Hash40>, prc: ParamStruct, name: String) {
+        if let State::Normal {
+            param,
+            compare,
+            edited,
+            ..
+        } = &mut self.state
+        {
+            let message = if param.graft(key, ParamKind::Struct(prc), &name) {
+                *edited = true;
+                if let Some(compare) = compare {
+                    compare.follow(param);
+                }
+                format!("Imported {}", name)
+            } else {
+                String::from("The struct already has a param with that key")
+            };
+            self.notification = Some(Notification::new(
+                message,
+                NotificationKind::Info,
+                self.theme(),
+            ));
+        }
+    }
+
+    /// Starts writing a param to a file of its own in the background
+    fn export(&mut self, path: PathBuf, subtree: ParamStruct) {
+        if let Some(parent) = path.parent().filter(|parent| parent.is_dir()) {
+            self.save_dir = parent.to_path_buf();
+        }
+        log::info!("exporting {}", path.display());
+        let busy = Busy::new(format!("Exporting {}", path.display()), self.theme());
+        let task = {
+            let path = path.clone();
+            Task::spawn(move || format::save(path, &subtree))
+        };
+        self.jobs.push_back(Job {
+            busy,
+            kind: JobKind::Export { path, task },
+        });
+    }
+
+    /// Starts writing the file in a plugin's format in the background
+    fn export_with(&mut self, path: PathBuf, root: Arc<ParamStruct>, exporter: Arc<dyn Exporter>) {
+        if let Some(parent) = path.parent().filter(|parent| parent.is_dir()) {
+            self.save_dir = parent.to_path_buf();
+        }
+        log::info!("exporting {} as {}", path.display(), exporter.name());
+        let busy = Busy::new(format!("Exporting {}", path.display()), self.theme());
+        let task = {
+            let path = path.clone();
+            Task::spawn(move || {
+                let bytes = exporter.export(&root).map_err(io::Error::other)?;
+                write(path, bytes)
+            })
+        };
+        self.jobs.push_back(Job {
+            busy,
+            kind: JobKind::Export { path, task },
+        });
+    }
+
+    fn show_open_error<T>(&mut self, path: &Path, failed: Option<Result<T, io::Error>>) {
+        let reason = match failed {
+            Some(Err(err)) => err.to_string(),
+            _ => String::from("the load was interrupted"),
+        };
+        let message = format!("{}: {}", path.display(), reason);
+        self.show_error_popup("Failed to open file", message);
+    }
+
+    /// Starts writing the file in the background. The file counts as saved right away,
+    /// so edits made while it's saving still need to be saved again
+    fn save(&mut self, path: PathBuf) {
+        // a failure is reported like one found by polling, and the new save goes ahead anyway
+        let _ = self.wait_for_save();
+        if let State::Normal {
+            param,
+            edited,
+            state,
+            ..
+        } = &mut self.state
+        {
+            if let Some(parent) = path.parent().filter(|parent| parent.is_dir()) {
+                self.save_dir = parent.to_path_buf();
+            }
+            log::info!("saving {}", path.display());
+            let param = param.snapshot();
+            *edited = false;
+            self.last_save = Instant::now();
+            **state = NormalState::View;
+            self.notification = Some(Notification::new(
+                format!("Saving {}...", path.display()),
+                NotificationKind::Progress,
+                self.theme(),
+            ));
+            let task = {
+                let path = path.clone();
+                let param = param.clone();
+                Task::spawn(move || format::save(path, &param))
+            };
+            self.saving = Some(Saving { path, param, task });
+        }
+    }
+
+    /// Blocks until the file being saved is written, e.g. before exiting.
+    /// Returns why the save failed, which is also shown in a popup if the app is still running
+    pub fn wait_for_save(&mut self) -> Result<(), String> {
+        match self.saving.take() {
+            Some(saving) => self.finish_save(saving),
+            None => Ok(()),
+        }
+    }
+
+    fn finish_save(&mut self, saving: Saving) -> Result<(), String> {
+        let path = saving.path;
+        match saving.task.join() {
+            Some(Ok(())) => {
+                log::info!("saved {}", path.display());
+                if self.disk.is_some() {
+                    self.disk = Some(DiskBaseline::Loaded(saving.param.clone()));
+                }
+                self.saved = Some(saving.param);
+                let mut message = format!("Saved {}", path.display());
+                // the sidecar follows the file wherever it's saved
+                if let (State::Normal { param, .. }, false) = (&self.state, self.sidecar.is_empty())
+                {
+                    let settings = self.settings();
+                    let view = ViewSettings::new(self.layout, self.details, &settings);
+                    self.sidecar.record(view, &param.level_filters());
+                    if let Err(err) = save_sidecar(&path, &self.sidecar) {
+                        message = format!("{}, but failed to save its sidecar: {}", message, err);
+                    }
+                }
+                self.modified = metadata(&path).and_then(|meta| meta.modified()).ok();
+                self.file = Some(canonicalize(&path).unwrap_or(path));
+                self.notification = Some(Notification::new(
+                    message,
+                    NotificationKind::Info,
+                    self.theme(),
+                ));
+                Ok(())
+            }
+            failed => {
+                if let State::Normal { edited, .. } = &mut self.state {
+                    *edited = true;
+                }
+                let reason = match failed {
+                    Some(Err(err)) => err.to_string(),
+                    _ => String::from("the save was interrupted"),
+                };
+                self.notification = None;
+                let message = format!("{}: {}", path.display(), reason);
+                log::error!("failed to save {}", message);
+                self.error = Some(ErrorPopup::new(
+                    "Failed to save file",
+                    message.clone(),
+                    self.theme(),
+                ));
+                Err(message)
+            }
+        }
+    }
+
+    /// Picks up a finished save and removes an expired notification.
+    /// Returns whether anything needs to be redrawn
+    fn poll_saving(&mut self) -> bool {
+        let mut changed = false;
+        if self
+            .saving
+            .as_ref()
+            .is_some_and(|saving| saving.task.is_finished())
+        {
+            let saving = self.saving.take().unwrap();
+            // a failure is shown in a popup
+            let _ = self.finish_save(saving);
+            changed = true;
+        }
+        if self
+            .notification
+            .as_ref()
+            .is_some_and(Notification::is_expired)
+        {
+            self.notification = None;
+            changed = true;
+        }
+        changed
+    }
+}
+
+impl Root {
+    /// The status bar for the current state
+    fn status_bar(&self) -> StatusBar {
+        let file = match (
+            self.file_name(),
+            file_position(&self.files, self.file.as_ref()),
+        ) {
+            (Some(name), Some((position, len))) => {
+                Some(format!("{} ({}/{})", name, position + 1, len))
+            }
+            (name, _) => name,
+        };
+        let hints = if !self.jobs.is_empty() {
+            "Esc: cancel".into()
+        } else {
+            match &self.state {
+                State::Empty(EmptyState::View) => format!("{}: open  Esc: quit", self.keys.open),
+                State::Empty(EmptyState::Open(_)) => OPEN_HINTS.into(),
+                State::Empty(EmptyState::OpenArc(_)) => ARC_HINTS.into(),
+                State::Empty(EmptyState::ConfirmDownload(_))
+                | State::Empty(EmptyState::ConfirmRestore(..)) => CONFIRM_HINTS.into(),
+                State::Empty(EmptyState::Warnings(_)) => "Up/Down: scroll  Esc: close".into(),
+                State::Normal {
+                    param, tree, state, ..
+                } => match state.as_ref() {
+                    NormalState::View => match tree {
+                        Some(tree) => tree.key_hints(self.keys.save),
+                        None => param.key_hints(self.keys.save),
+                    },
+                    NormalState::Open(_)
+                    | NormalState::OpenCompare(_)
+                    | NormalState::Import(_) => OPEN_HINTS.into(),
+                    NormalState::OpenArc(_) => ARC_HINTS.into(),
+                    NormalState::ImportKey(..) => {
+                        "Tab: complete  Up/Down: suggestions  Enter: import  Esc: cancel".into()
+                    }
+                    NormalState::Save(_)
+                    | NormalState::Export(..)
+                    | NormalState::PluginExport(..) => SAVE_HINTS.into(),
+                    NormalState::Palette(_) => "Up/Down: choose  Enter: run  Esc: cancel".into(),
+                    NormalState::ConfirmExit(_)
+                    | NormalState::ConfirmOpen(_)
+                    | NormalState::ConfirmSwitch(..)
+                    | NormalState::ConfirmNew(_)
+                    | NormalState::ConfirmRevert(_)
+                    | NormalState::ConfirmDownload(_) => CONFIRM_HINTS.into(),
+                    NormalState::Crack(_) => "Enter: add the label  Esc: cancel".into(),
+                    NormalState::Report(_) => {
+                        "Enter: search for a label  e: export  Esc: back".into()
+                    }
+                    NormalState::Replace(_) => {
+                        "Tab: find/replace with  Ctrl+R: regex  Up/Down: scroll  Enter: replace all  Esc: cancel"
+                            .into()
+                    }
+                    NormalState::Transform(_) | NormalState::Script(_) => {
+                        "Up/Down: scroll  Enter: change all  Esc: cancel".into()
+                    }
+                    NormalState::Stats(_) => "Esc: close".into(),
+                    NormalState::Structure(_) => {
+                        "Up/Down: choose  Left/Right: collapse/expand  Enter: go to the param  Esc: close"
+                            .into()
+                    }
+                    NormalState::Warnings(_) => {
+                        "Up/Down: choose  Enter: go to the param  Esc: close".into()
+                    }
+                    NormalState::History(_) => {
+                        "Up/Down: choose  Enter: go to the param  r: undo/redo to here  Esc: close"
+                            .into()
+                    }
+                    NormalState::Snapshots(snapshots) => snapshots.key_hints().into(),
+                    NormalState::Note(..) => {
+                        "Enter: keep the note, or remove it if it's empty  Esc: cancel".into()
+                    }
+                    NormalState::Paths(paths) => paths.key_hints().into(),
+                    NormalState::Command(_) => {
+                        "Enter: run  Up/Down: earlier commands  Esc: cancel".into()
+                    }
+                },
+            }
+        };
+        match &self.state {
+            State::Normal { param, edited, .. } => StatusBar {
+                file,
+                path: Some(param.selected_path()),
+                filtered: param.filtered_levels(),
+                edited: *edited,
+                hints,
+                theme: self.theme(),
+            },
+            State::Empty(_) => StatusBar {
+                hints,
+                theme: self.theme(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Handles an event while no file is open
+    fn handle_empty_event(&mut self, event: Event) -> AppResponse {
+        let theme = self.theme();
+        let state = match &mut self.state {
+            State::Empty(state) => state,
+            State::Normal { .. } => return AppResponse::None,
+        };
+        match state {
+            EmptyState::View => {
+                if let Event::Key(key_event) = event {
+                    match key_event.code {
+                        KeyCode::Esc => return AppResponse::Exit,
+                        _ if self.keys.open.matches(&key_event) => {
+                            self.state = State::Empty(EmptyState::Open(Box::new(
+                                Explorer::new(self.open_dir.clone(), ExplorerMode::Open, theme)
+                                    .with_config(self.explorer),
+                            )))
+                        }
+                        _ if self.keys.save.matches(&key_event) => {
+                            self.state = State::Empty(EmptyState::Open(Box::new(
+                                Explorer::new(self.open_dir.clone(), ExplorerMode::Open, theme)
+                                    .with_config(self.explorer),
+                            )))
+                        }
+                        _ if self.keys.new_file.matches(&key_event) => self.new_file(),
+                        _ if self.keys.open_arc.matches(&key_event) => match &self.game_root {
+                            Some(root) => {
+                                self.state = State::Empty(EmptyState::OpenArc(Box::new(
+                                    ArcPrompt::new(root.clone(), theme),
+                                )))
+                            }
+                            None => self.show_error(NO_GAME_ROOT_MSG.into()),
+                        },
+                        _ if self.keys.reload_labels.matches(&key_event) => {
+                            self.reload_labels();
+                        }
+                        _ if self.keys.warnings.matches(&key_event) => {
+                            self.state = State::Empty(EmptyState::Warnings(WarningsPanel::new(
+                                self.warnings(),
+                                theme,
+                            )))
+                        }
+                        _ if self.keys.download_labels.matches(&key_event) => {
+                            self.state = State::Empty(EmptyState::ConfirmDownload(Confirm::new(
+                                DOWNLOAD_LABELS_MSG,
+                                theme,
+                            )))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            EmptyState::Warnings(warnings) => match warnings.handle_event(event) {
+                WarningsPanelResponse::Exit => self.state = State::Empty(EmptyState::View),
+                WarningsPanelResponse::Open(_) => {}
+                WarningsPanelResponse::Handled => {}
+                WarningsPanelResponse::None => {}
+            },
+            EmptyState::Open(open) => match open.handle_event(event) {
+                ExplorerResponse::Open(path) => self.open(path),
+                ExplorerResponse::Save(_) => {}
+                ExplorerResponse::Cancel => self.state = State::Empty(EmptyState::View),
+                ExplorerResponse::Handled => {}
+                ExplorerResponse::None => {}
+            },
+            EmptyState::OpenArc(prompt) => match prompt.handle_event(event) {
+                ArcPromptResponse::Open(path) => {
+                    self.state = State::Empty(EmptyState::View);
+                    self.open(path);
+                }
+                ArcPromptResponse::Cancel => self.state = State::Empty(EmptyState::View),
+                ArcPromptResponse::Handled => {}
+                ArcPromptResponse::None => {}
+            },
+            EmptyState::ConfirmRestore(confirm, session) => match confirm.handle_event(event) {
+                ConfirmResponse::Confirm(answer) => {
+                    let session = session.clone();
+                    self.state = State::Empty(EmptyState::View);
+                    if answer {
+                        self.start_open(session.file, session.levels, None);
+                    }
+                }
+                ConfirmResponse::Handled => {}
+                ConfirmResponse::None => {}
+            },
+            EmptyState::ConfirmDownload(confirm) => match confirm.handle_event(event) {
+                ConfirmResponse::Confirm(answer) => {
+                    self.state = State::Empty(EmptyState::View);
+                    if answer {
+                        self.download_labels();
+                    }
+                }
+                ConfirmResponse::Handled => {}
+                ConfirmResponse::None => {}
+            },
+        }
+        AppResponse::None
+    }
+
+    /// Handles an event in the params, or in the tree when it's shown
+    fn handle_view_event(&mut self, event: Event) -> AppResponse {
+        let (param, tree, compare, edited) = match &mut self.state {
+            State::Normal {
+                param,
+                tree,
+                compare,
+                edited,
+                ..
+            } => (param, tree, compare, edited),
+            State::Empty(_) => return AppResponse::None,
+        };
+        match view_event(param, tree.as_deref_mut(), event) {
+            ParamResponse::None => match event {
+                Event::Key(key) => return self.handle_view_key(key),
+                Event::Mouse(mouse) => self.handle_breadcrumb_click(mouse),
+            },
+            ParamResponse::Handled {
+                edited: component_edited,
+            } => {
+                if component_edited {
+                    *edited = true;
+                    log_edit(param);
+                }
+                if let Some(compare) = compare {
+                    compare.follow(param);
+                }
+            }
+            ParamResponse::Exit => {}
+        }
+        AppResponse::None
+    }
+
+    /// Handles a key the params left alone, like the ones opening panels and prompts
+    fn handle_view_key(&mut self, key: KeyEvent) -> AppResponse {
+        let settings = self.settings();
+        let theme = settings.theme;
+        let prompting_reload = self.is_prompting_reload();
+        let others_edited = self.others_edited();
+        let (param, compare, edited, state) = match &mut self.state {
+            State::Normal {
+                param,
+                compare,
+                edited,
+                state,
+                ..
+            } => (param, compare, edited, state),
+            State::Empty(_) => return AppResponse::None,
+        };
+        match key.code {
+            KeyCode::Esc if prompting_reload => {
+                self.notification = None;
+            }
+            KeyCode::Esc => {
+                if *edited || others_edited {
+                    **state = NormalState::ConfirmExit(Confirm::new(EXIT_MSG, theme));
+                } else {
+                    return AppResponse::Exit;
+                }
+            }
+            _ if self.keys.open.matches(&key) => {
+                if *edited {
+                    let msg = "You have unsaved changes. Are you sure you want to open a new file?";
+                    **state = NormalState::ConfirmOpen(Confirm::new(msg, theme));
+                } else {
+                    **state = NormalState::Open(
+                        Explorer::new(self.open_dir.clone(), ExplorerMode::Open, theme)
+                            .with_config(self.explorer),
+                    );
+                }
+            }
+            _ if self.keys.next_edited.matches(&key) => self.select_edited(true),
+            _ if self.keys.previous_edited.matches(&key) => self.select_edited(false),
+            _ if self.keys.bookmark.matches(&key) => {
+                let path = param.selected_path();
+                if !path.0.is_empty() {
+                    let message = if self.sidecar.toggle_bookmark(&path) {
+                        format!("Bookmarked {}", path)
+                    } else {
+                        format!("Removed the bookmark on {}", path)
+                    };
+                    self.notification = Some(keep_sidecar(
+                        &mut self.sidecar,
+                        ViewSettings::new(self.layout, self.details, &settings),
+                        param,
+                        self.file.as_ref(),
+                        message,
+                        theme,
+                    ));
+                }
+            }
+            _ if self.keys.next_bookmark.matches(&key) => self.select_bookmark(true),
+            _ if self.keys.previous_bookmark.matches(&key) => self.select_bookmark(false),
+            _ if self.keys.open_arc.matches(&key) => match &self.game_root {
+                Some(root) => {
+                    **state = NormalState::OpenArc(Box::new(ArcPrompt::new(root.clone(), theme)))
+                }
+                None => {
+                    self.notification = Some(Notification::new(
+                        NO_GAME_ROOT_MSG.into(),
+                        NotificationKind::Error,
+                        theme,
+                    ))
+                }
+            },
+            _ if self.keys.next_file.matches(&key) || self.keys.previous_file.matches(&key) => {
+                let forward = self.keys.next_file.matches(&key);
+                // the shown file is put aside if it's one of them
+                let kept = file_position(&self.files, self.file.as_ref()).is_some();
+                match adjacent_file(&self.files, self.file.as_ref(), forward) {
+                    Some(path) if *edited && !kept => {
+                        let msg = format!(
+                            "You have unsaved changes. Are you sure you want to open {}?",
+                            path.display()
+                        );
+                        **state = NormalState::ConfirmSwitch(Confirm::new(msg, theme), path);
+                    }
+                    Some(path) => self.switch_to(path),
+                    None => {
+                        self.notification = Some(Notification::new(
+                            "Open several files at once to switch between them, like prickly a.prc b.prc".into(),
+                            NotificationKind::Info,
+                            theme,
+                        ));
+                    }
+                }
+            }
+            _ if self.keys.new_file.matches(&key) => {
+                if *edited {
+                    let msg =
+                        "You have unsaved changes. Are you sure you want to start a new file?";
+                    **state = NormalState::ConfirmNew(Confirm::new(msg, theme));
+                } else {
+                    self.new_file();
+                }
+            }
+            _ if self.keys.save.matches(&key) => {
+                **state = NormalState::Save(
+                    Explorer::new(self.save_dir.clone(), ExplorerMode::Save, theme)
+                        .with_config(self.explorer),
+                );
+            }
+            _ if self.keys.warnings.matches(&key) => {
+                let warnings = self.warnings();
+                if let State::Normal { state, .. } = &mut self.state {
+                    **state = NormalState::Warnings(WarningsPanel::new(warnings, theme));
+                }
+            }
+            _ if self.keys.reload_labels.matches(&key) => {
+                self.reload_labels();
+            }
+            _ if self.keys.download_labels.matches(&key) => {
+                **state = NormalState::ConfirmDownload(Confirm::new(DOWNLOAD_LABELS_MSG, theme));
+            }
+            _ if self.keys.crack_hash.matches(&key) => {
+                if let Some(hash) = param.selected_hash() {
+                    **state = NormalState::Crack(HashCracker::new(
+                        hash,
+                        self.sorted_labels.clone(),
+                        theme,
+                    ));
+                }
+            }
+            _ if self.keys.unknown_hashes.matches(&key) => {
+                let unknown = unknown_hashes(&param.snapshot());
+                **state = NormalState::Report(HashReport::new(unknown, theme));
+            }
+            _ if self.keys.stats.matches(&key) => {
+                let stats = file_stats(&param.snapshot());
+                **state = NormalState::Stats(StatsPanel::new(stats, theme));
+            }
+            _ if self.keys.paths.matches(&key) => {
+                **state = NormalState::Paths(Box::new(PathList::new(
+                    param.snapshot(),
+                    self.history.clone(),
+                    settings,
+                )));
+            }
+            _ if self.keys.replace.matches(&key) => {
+                **state = NormalState::Replace(Box::new(FindReplace::new(
+                    param.snapshot(),
+                    param.level_path(),
+                    theme,
+                )));
+            }
+            _ if self.keys.transform.matches(&key) => {
+                **state = NormalState::Transform(Box::new(TransformInput::new(
+                    param.snapshot(),
+                    param.shown_paths(),
+                    theme,
+                )));
+            }
+            _ if self.keys.script.matches(&key) => {
+                **state = NormalState::Script(Box::new(ScriptInput::new(param.snapshot(), theme)));
+            }
+            _ if self.keys.copy_path.matches(&key) => {
+                let path = param.selected_path().to_string();
+                self.notification = Some(match self.clipboard.copy(&path) {
+                    Ok(()) => {
+                        Notification::new(format!("Copied {}", path), NotificationKind::Info, theme)
+                    }
+                    Err(err) => Notification::new(
+                        format!("Failed to copy the path: {}", err),
+                        NotificationKind::Error,
+                        theme,
+                    ),
+                });
+            }
+            _ if self.keys.copy_link.matches(&key) => {
+                let link = self.file.as_ref().map(|file| {
+                    format!(
+                        "prickly {} --at {}",
+                        shell_quote(&file.to_string_lossy()),
+                        shell_quote(&param.selected_path().to_string())
+                    )
+                });
+                let copied = link.map(|link| {
+                    let copied = self.clipboard.copy(&link);
+                    (link, copied)
+                });
+                self.notification = Some(match copied {
+                    Some((link, Ok(()))) => {
+                        Notification::new(format!("Copied {}", link), NotificationKind::Info, theme)
+                    }
+                    Some((_, Err(err))) => Notification::new(
+                        format!("Failed to copy the link: {}", err),
+                        NotificationKind::Error,
+                        theme,
+                    ),
+                    None => Notification::new(
+                        "Save the file first to link to it".into(),
+                        NotificationKind::Info,
+                        theme,
+                    ),
+                });
+            }
+            _ if self.keys.command_line.matches(&key) => {
+                **state =
+                    NormalState::Command(Box::new(CommandLine::new(self.history.clone(), theme)));
+            }
+            _ if self.keys.palette.matches(&key) => {
+                **state = NormalState::Palette(Box::new(Palette::new(&self.plugins, theme)));
+            }
+            _ if self.keys.undo.matches(&key) => {
+                let description = param.undo();
+                self.notification = Some(match description {
+                    Some(description) => {
+                        *edited = differs_from_saved(self.saved.as_ref(), param);
+                        if let Some(compare) = compare {
+                            compare.follow(param);
+                        }
+                        Notification::new(
+                            format!("Undid: {}", description),
+                            NotificationKind::Info,
+                            theme,
+                        )
+                    }
+                    None => {
+                        Notification::new("Nothing to undo".into(), NotificationKind::Info, theme)
+                    }
+                });
+            }
+            _ if self.keys.redo.matches(&key) => {
+                let description = param.redo();
+                self.notification = Some(match description {
+                    Some(description) => {
+                        *edited = differs_from_saved(self.saved.as_ref(), param);
+                        if let Some(compare) = compare {
+                            compare.follow(param);
+                        }
+                        Notification::new(
+                            format!("Redid: {}", description),
+                            NotificationKind::Info,
+                            theme,
+                        )
+                    }
+                    None => {
+                        Notification::new("Nothing to redo".into(), NotificationKind::Info, theme)
+                    }
+                });
+            }
+            _ if self.keys.history.matches(&key) => {
+                let (entries, made) = param.edit_history();
+                **state = NormalState::History(Box::new(HistoryPanel::new(
+                    entries, made, settings, theme,
+                )));
+            }
+            _ if self.keys.snapshots.matches(&key) => {
+                let entries =
+                    SnapshotEntry::list(&self.snapshots, self.file.as_ref(), &param.snapshot());
+                **state = NormalState::Snapshots(Box::new(SnapshotPanel::new(entries, theme)));
+            }
+            _ if self.keys.note.matches(&key) => {
+                let path = param.selected_path();
+                if !path.0.is_empty() {
+                    let prompt = NotePrompt::new(
+                        format!("Note for {}", path),
+                        self.sidecar.note(&path),
+                        theme,
+                    );
+                    **state = NormalState::Note(Box::new(prompt), path);
+                }
+            }
+            _ if self.keys.compare.matches(&key) => {
+                if compare.take().is_some() {
+                    param.clear_marks();
+                } else {
+                    **state = NormalState::OpenCompare(
+                        Explorer::new(self.open_dir.clone(), ExplorerMode::Open, theme)
+                            .with_config(self.explorer),
+                    );
+                }
+            }
+            _ if self.keys.structure_diff.matches(&key) => match compare {
+                Some(compare) => {
+                    let nodes = diff_structure(&param.snapshot(), &compare.snapshot());
+                    **state = NormalState::Structure(Box::new(StructurePanel::new(nodes, theme)));
+                }
+                None => {
+                    self.notification = Some(Notification::new(
+                        format!("Open a file to compare with first ({})", self.keys.compare),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+            },
+            _ if self.keys.layout.matches(&key) => {
+                self.set_layout(self.layout.toggle());
+            }
+            _ if self.keys.disk_diff.matches(&key) => {
+                self.toggle_disk_diff();
+            }
+            _ if self.keys.revert.matches(&key) => {
+                if self.file.is_none() {
+                    self.show_error("The file hasn't been saved yet".into());
+                } else if *edited && !prompting_reload {
+                    let msg = "Drop your unsaved changes and reload the file?";
+                    **state = NormalState::ConfirmRevert(Confirm::new(msg, theme));
+                } else {
+                    self.revert();
+                }
+            }
+            _ if self.keys.import_subtree.matches(&key) => {
+                **state = NormalState::Import(
+                    Explorer::new(self.open_dir.clone(), ExplorerMode::Open, theme)
+                        .with_config(self.explorer)
+                        .with_title("Import File"),
+                );
+            }
+            _ if self.keys.export_subtree.matches(&key) => {
+                let subtree = subtree::extract(&param.snapshot(), &param.selected_path());
+                if let Some(subtree) = subtree {
+                    **state = NormalState::Export(
+                        Explorer::new(self.save_dir.clone(), ExplorerMode::Save, theme)
+                            .with_config(self.explorer)
+                            .with_title("Export Param"),
+                        Box::new(subtree),
+                    );
+                }
+            }
+            _ if self.keys.clear_filter.matches(&key) => {
+                let cleared = param.clear_filter();
+                if !cleared {
+                    self.notification = Some(Notification::new(
+                        "There's no filter to clear".into(),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+            }
+            _ if self.keys.details.matches(&key) => {
+                self.details = !self.details;
+            }
+            _ if self.keys.hash_display.matches(&key) => {
+                if let Ok(mut settings) = self.settings.lock() {
+                    settings.hash_display = settings.hash_display.next();
+                }
+            }
+            _ if self.keys.hex_column.matches(&key) => {
+                if let Ok(mut settings) = self.settings.lock() {
+                    settings.hex_column = !settings.hex_column;
+                }
+            }
+            _ if self.keys.row_numbers.matches(&key) => {
+                if let Ok(mut settings) = self.settings.lock() {
+                    settings.row_numbers = !settings.row_numbers;
+                }
+            }
+            _ if self.keys.float_decimals.matches(&key) => {
+                if let Ok(mut settings) = self.settings.lock() {
+                    settings.float_format = settings.float_format.next_decimals();
+                }
+            }
+            _ if self.keys.float_notation.matches(&key) => {
+                if let Ok(mut settings) = self.settings.lock() {
+                    settings.float_format.scientific = !settings.float_format.scientific;
+                }
+            }
+            _ => {}
+        }
+        AppResponse::None
+    }
+
+    /// Goes back to the level of the part of the path clicked in the status bar
+    fn handle_breadcrumb_click(&mut self, mouse: MouseEvent) {
+        let (param, tree, compare) = match &mut self.state {
+            State::Normal {
+                param,
+                tree,
+                compare,
+                ..
+            } => (param, tree, compare),
+            State::Empty(_) => return,
+        };
+        let clicked = self.breadcrumbs.iter().position(|crumb| {
+            mouse.column >= crumb.x && mouse.column < crumb.x + crumb.width && mouse.row == crumb.y
+        });
+        if let (MouseEventKind::Down(MouseButton::Left), Some(depth)) = (mouse.kind, clicked) {
+            param.close_below(depth);
+            if let Some(tree) = tree {
+                tree.reveal(&param.selected_path());
+            }
+            if let Some(compare) = compare {
+                compare.follow(param);
+            }
+        }
+    }
+
+    /// Handles an event in the panel or prompt open over the params
+    fn handle_panel_event(&mut self, event: Event) -> AppResponse {
+        let settings = self.settings();
+        let theme = settings.theme;
+        let (param, tree, compare, edited, state) = match &mut self.state {
+            State::Normal {
+                param,
+                tree,
+                compare,
+                edited,
+                state,
+            } => (param, tree, compare, edited, state),
+            State::Empty(_) => return AppResponse::None,
+        };
+        match state.as_mut() {
+            // the params handle events themselves while nothing is open over them
+            NormalState::View => {}
+            NormalState::Open(open) => match open.handle_event(event) {
+                ExplorerResponse::Open(path) => self.open(path),
+                ExplorerResponse::Cancel => **state = NormalState::View,
+                ExplorerResponse::Save(_) => {}
+                ExplorerResponse::Handled => {}
+                ExplorerResponse::None => {}
+            },
+            NormalState::OpenArc(prompt) => match prompt.handle_event(event) {
+                ArcPromptResponse::Open(path) if *edited => {
+                    let msg = format!(
+                        "You have unsaved changes. Are you sure you want to open {}?",
+                        path.display()
+                    );
+                    **state = NormalState::ConfirmSwitch(Confirm::new(msg, theme), path);
+                }
+                ArcPromptResponse::Open(path) => {
+                    **state = NormalState::View;
+                    self.open(path);
+                }
+                ArcPromptResponse::Cancel => **state = NormalState::View,
+                ArcPromptResponse::Handled => {}
+                ArcPromptResponse::None => {}
+            },
+            NormalState::OpenCompare(open) => match open.handle_event(event) {
+                ExplorerResponse::Open(path) => self.open_compare(path),
+                ExplorerResponse::Cancel => **state = NormalState::View,
+                ExplorerResponse::Save(_) => {}
+                ExplorerResponse::Handled => {}
+                ExplorerResponse::None => {}
+            },
+            NormalState::Save(save) => match save.handle_event(event) {
+                ExplorerResponse::Save(path) => self.save(self.export_path(path)),
+                ExplorerResponse::Cancel => **state = NormalState::View,
+                ExplorerResponse::Open(_) => {}
+                ExplorerResponse::Handled => {}
+                ExplorerResponse::None => {}
+            },
+            NormalState::Import(open) => match open.handle_event(event) {
+                ExplorerResponse::Open(path) => self.start_import(path),
+                ExplorerResponse::Cancel => **state = NormalState::View,
+                ExplorerResponse::Save(_) => {}
+                ExplorerResponse::Handled => {}
+                ExplorerResponse::None => {}
+            },
+            NormalState::ImportKey(prompt, prc, name) => match prompt.handle_event(event) {
+                KeyPromptResponse::Submit(key) => {
+                    let prc = std::mem::take(prc.as_mut());
+                    let name = std::mem::take(name);
+                    **state = NormalState::View;
+                    self.graft(Some(key), prc, name);
+                }
+                KeyPromptResponse::Cancel => **state = NormalState::View,
+                KeyPromptResponse::Handled => {}
+                KeyPromptResponse::None => {}
+            },
+            NormalState::Export(save, subtree) => match save.handle_event(event) {
+                ExplorerResponse::Save(path) => {
+                    let subtree = std::mem::take(subtree.as_mut());
+                    **state = NormalState::View;
+                    self.export(self.export_path(path), subtree);
+                }
+                ExplorerResponse::Cancel => **state = NormalState::View,
+                ExplorerResponse::Open(_) => {}
+                ExplorerResponse::Handled => {}
+                ExplorerResponse::None => {}
+            },
+            NormalState::ConfirmExit(confirm) => match confirm.handle_event(event) {
+                ConfirmResponse::Confirm(answer) => {
+                    if answer {
+                        return AppResponse::Exit;
+                    } else {
+                        **state = NormalState::View;
+                    }
+                }
+                ConfirmResponse::Handled => {}
+                ConfirmResponse::None => {}
+            },
+            NormalState::ConfirmOpen(confirm) => match confirm.handle_event(event) {
+                ConfirmResponse::Confirm(answer) => {
+                    if answer {
+                        **state = NormalState::Open(
+                            Explorer::new(self.open_dir.clone(), ExplorerMode::Open, theme)
+                                .with_config(self.explorer),
+                        );
+                    } else {
+                        **state = NormalState::View;
+                    }
+                }
+                ConfirmResponse::Handled => {}
+                ConfirmResponse::None => {}
+            },
+            NormalState::ConfirmSwitch(confirm, path) => match confirm.handle_event(event) {
+                ConfirmResponse::Confirm(answer) => {
+                    let path = path.clone();
+                    **state = NormalState::View;
+                    if answer {
+                        self.switch_to(path);
+                    }
+                }
+                ConfirmResponse::Handled => {}
+                ConfirmResponse::None => {}
+            },
+            NormalState::ConfirmNew(confirm) => match confirm.handle_event(event) {
+                ConfirmResponse::Confirm(answer) => {
+                    **state = NormalState::View;
+                    if answer {
+                        self.new_file();
+                    }
+                }
+                ConfirmResponse::Handled => {}
+                ConfirmResponse::None => {}
+            },
+            NormalState::ConfirmRevert(confirm) => match confirm.handle_event(event) {
+                ConfirmResponse::Confirm(answer) => {
+                    **state = NormalState::View;
+                    if answer {
+                        self.revert();
+                    }
+                }
+                ConfirmResponse::Handled => {}
+                ConfirmResponse::None => {}
+            },
+            NormalState::Crack(cracker) => match cracker.handle_event(event) {
+                HashCrackerResponse::Exit => **state = NormalState::View,
+                HashCrackerResponse::Handled => {}
+                HashCrackerResponse::None => {}
+            },
+            NormalState::Report(report) => match report.handle_event(event) {
+                HashReportResponse::Crack(hash) => {
+                    **state = NormalState::Crack(HashCracker::new(
+                        hash,
+                        self.sorted_labels.clone(),
+                        theme,
+                    ))
+                }
+                HashReportResponse::Exit => **state = NormalState::View,
+                HashReportResponse::Handled => {}
+                HashReportResponse::None => {}
+            },
+            NormalState::Replace(replace) => match replace.handle_event(event) {
+                FindReplaceResponse::Apply(replacements) => {
+                    **state = NormalState::View;
+                    self.replace_values(&replacements);
+                }
+                FindReplaceResponse::Cancel => **state = NormalState::View,
+                FindReplaceResponse::Handled => {}
+                FindReplaceResponse::None => {}
+            },
+            NormalState::Transform(transform) => match transform.handle_event(event) {
+                TransformInputResponse::Apply(replacements) => {
+                    **state = NormalState::View;
+                    let description = format!("Changed {} value(s)", replacements.len());
+                    let applied = param.replace_values(description, &replacements);
+                    log::debug!("transformed {} values", applied);
+                    if applied > 0 {
+                        *edited = true;
+                    }
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                    self.notification = Some(Notification::new(
+                        format!("Changed {} value(s)", applied),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+                TransformInputResponse::Cancel => **state = NormalState::View,
+                TransformInputResponse::Handled => {}
+                TransformInputResponse::None => {}
+            },
+            NormalState::Script(script) => match script.handle_event(event) {
+                ScriptInputResponse::Apply(replacements) => {
+                    **state = NormalState::View;
+                    let description = format!("Ran a script on {} value(s)", replacements.len());
+                    let applied = param.replace_values(description, &replacements);
+                    log::debug!("scripted {} values", applied);
+                    if applied > 0 {
+                        *edited = true;
+                    }
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                    self.notification = Some(Notification::new(
+                        format!("Changed {} value(s)", applied),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+                ScriptInputResponse::Cancel => **state = NormalState::View,
+                ScriptInputResponse::Handled => {}
+                ScriptInputResponse::None => {}
+            },
+            NormalState::Command(command) => match command.handle_event(event) {
+                CommandLineResponse::Run(command) => {
+                    **state = NormalState::View;
+                    return self.run_command(command);
+                }
+                CommandLineResponse::Cancel => **state = NormalState::View,
+                CommandLineResponse::Handled => {}
+                CommandLineResponse::None => {}
+            },
+            NormalState::Palette(palette) => match palette.handle_event(event) {
+                PaletteResponse::Run(PaletteEntry::Command(command)) => {
+                    **state = NormalState::View;
+                    log::info!("running the command {}", command.name());
+                    match command.run(&param.snapshot()) {
+                        Ok(replacements) => {
+                            let description = format!("Ran {}", command.name());
+                            let applied = param.replace_values(description, &replacements);
+                            if applied > 0 {
+                                *edited = true;
+                            }
+                            if let Some(compare) = compare {
+                                compare.follow(param);
+                            }
+                            self.notification = Some(Notification::new(
+                                format!("{} changed {} value(s)", command.name(), applied),
+                                NotificationKind::Info,
+                                theme,
+                            ));
+                        }
+                        Err(err) => {
+                            let title = format!("Failed to run {}", command.name());
+                            self.show_error_popup(title, err);
+                        }
+                    }
+                }
+                PaletteResponse::Run(PaletteEntry::Export(exporter)) => {
+                    **state = NormalState::PluginExport(
+                        Explorer::new(self.save_dir.clone(), ExplorerMode::Save, theme)
+                            .with_config(self.explorer)
+                            .with_title("Export File"),
+                        exporter,
+                    );
+                }
+                PaletteResponse::Cancel => **state = NormalState::View,
+                PaletteResponse::Handled => {}
+                PaletteResponse::None => {}
+            },
+            NormalState::PluginExport(save, exporter) => match save.handle_event(event) {
+                ExplorerResponse::Save(mut path) => {
+                    if path.extension().is_none() {
+                        path.set_extension(exporter.extension());
+                    }
+                    let exporter = exporter.clone();
+                    let root = param.snapshot();
+                    **state = NormalState::View;
+                    self.export_with(path, root, exporter);
+                }
+                ExplorerResponse::Cancel => **state = NormalState::View,
+                ExplorerResponse::Open(_) => {}
+                ExplorerResponse::Handled => {}
+                ExplorerResponse::None => {}
+            },
+            NormalState::Warnings(warnings) => match warnings.handle_event(event) {
+                WarningsPanelResponse::Open(path) => {
+                    **state = NormalState::View;
+                    param.reveal(&path.0);
+                    if let Some(tree) = tree {
+                        tree.reveal(&path);
+                    }
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                }
+                WarningsPanelResponse::Exit => **state = NormalState::View,
+                WarningsPanelResponse::Handled => {}
+                WarningsPanelResponse::None => {}
+            },
+            NormalState::History(history) => match history.handle_event(event) {
+                HistoryPanelResponse::Open(path) => {
+                    **state = NormalState::View;
+                    param.reveal(&path.0);
+                    if let Some(tree) = tree {
+                        tree.reveal(&path);
+                    }
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                }
+                HistoryPanelResponse::Undo(count) => {
+                    let undone = (0..count).take_while(|_| param.undo().is_some()).count();
+                    if undone > 0 {
+                        *edited = differs_from_saved(self.saved.as_ref(), param);
+                    }
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                    let (entries, made) = param.edit_history();
+                    history.update(entries, made);
+                    self.notification = Some(Notification::new(
+                        format!("Undid {} edit(s)", undone),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+                HistoryPanelResponse::Redo(count) => {
+                    let redone = (0..count).take_while(|_| param.redo().is_some()).count();
+                    if redone > 0 {
+                        *edited = differs_from_saved(self.saved.as_ref(), param);
+                    }
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                    let (entries, made) = param.edit_history();
+                    history.update(entries, made);
+                    self.notification = Some(Notification::new(
+                        format!("Redid {} edit(s)", redone),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+                HistoryPanelResponse::Exit => **state = NormalState::View,
+                HistoryPanelResponse::Handled => {}
+                HistoryPanelResponse::None => {}
+            },
+            NormalState::Snapshots(snapshots) => match snapshots.handle_event(event) {
+                SnapshotPanelResponse::Take(name) => {
+                    let snapshot = Snapshot {
+                        name: name.clone(),
+                        file: self.file.clone(),
+                        root: param.snapshot(),
+                    };
+                    // taking one with the same name again moves it to now
+                    let existing = self.snapshots.iter().position(|other| {
+                        other.name == snapshot.name && other.file == snapshot.file
+                    });
+                    let message = match existing {
+                        Some(index) => {
+                            self.snapshots[index] = snapshot;
+                            format!("Updated the snapshot {}", name)
+                        }
+                        None => {
+                            self.snapshots.push(snapshot);
+                            format!("Took the snapshot {}", name)
+                        }
+                    };
+                    **state = NormalState::View;
+                    self.notification =
+                        Some(Notification::new(message, NotificationKind::Info, theme));
+                }
+                SnapshotPanelResponse::Restore(index) => {
+                    let snapshot = &self.snapshots[index];
+                    param.replace_root(
+                        format!("Restored the snapshot {}", snapshot.name),
+                        snapshot.root.clone(),
+                    );
+                    *edited = true;
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                    **state = NormalState::View;
+                    self.notification = Some(Notification::new(
+                        format!("Restored the snapshot {}", snapshot.name),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+                SnapshotPanelResponse::Compare(index) => {
+                    let snapshot = &self.snapshots[index];
+                    let mut other = Param::new(
+                        snapshot.root.clone(),
+                        self.sorted_labels.clone(),
+                        self.settings.clone(),
+                        self.history.clone(),
+                    );
+                    other.follow(param);
+                    *compare = Some(Box::new(other));
+                    **state = NormalState::View;
+                    self.notification = Some(Notification::new(
+                        format!("Comparing with the snapshot {}", snapshot.name),
+                        NotificationKind::Info,
+                        theme,
+                    ));
+                }
+                SnapshotPanelResponse::Delete(index) => {
+                    self.snapshots.remove(index);
+                    let entries =
+                        SnapshotEntry::list(&self.snapshots, self.file.as_ref(), &param.snapshot());
+                    snapshots.update(entries);
+                }
+                SnapshotPanelResponse::Exit => **state = NormalState::View,
+                SnapshotPanelResponse::Handled => {}
+                SnapshotPanelResponse::None => {}
+            },
+            NormalState::Note(prompt, path) => match prompt.handle_event(event) {
+                NotePromptResponse::Submit(note) => {
+                    let path = path.clone();
+                    **state = NormalState::View;
+                    self.sidecar.set_note(&path, &note);
+                    let message = if note.trim().is_empty() {
+                        format!("Removed the note for {}", path)
+                    } else {
+                        format!("Kept the note for {}", path)
+                    };
+                    self.notification = Some(keep_sidecar(
+                        &mut self.sidecar,
+                        ViewSettings::new(self.layout, self.details, &settings),
+                        param,
+                        self.file.as_ref(),
+                        message,
+                        theme,
+                    ));
+                }
+                NotePromptResponse::Cancel => **state = NormalState::View,
+                NotePromptResponse::Handled => {}
+                NotePromptResponse::None => {}
+            },
+            NormalState::Structure(structure) => match structure.handle_event(event) {
+                StructurePanelResponse::Open(path) => {
+                    **state = NormalState::View;
+                    param.reveal(&path.0);
+                    if let Some(tree) = tree {
+                        tree.reveal(&path);
+                    }
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                }
+                StructurePanelResponse::Exit => **state = NormalState::View,
+                StructurePanelResponse::Handled => {}
+                StructurePanelResponse::None => {}
+            },
+            NormalState::Stats(stats) => match stats.handle_event(event) {
+                StatsPanelResponse::Exit => **state = NormalState::View,
+                StatsPanelResponse::None => {}
+            },
+            NormalState::Paths(paths) => match paths.handle_event(event) {
+                PathListResponse::Open(path) => {
+                    **state = NormalState::View;
+                    param.reveal(&path.0);
+                    if let Some(tree) = tree {
+                        tree.reveal(&path);
+                    }
+                    if let Some(compare) = compare {
+                        compare.follow(param);
+                    }
+                }
+                PathListResponse::Exit => **state = NormalState::View,
+                PathListResponse::Handled => {}
+                PathListResponse::None => {}
+            },
+            NormalState::ConfirmDownload(confirm) => match confirm.handle_event(event) {
+                ConfirmResponse::Confirm(answer) => {
+                    **state = NormalState::View;
+                    if answer {
+                        self.download_labels();
+                    }
+                }
+                ConfirmResponse::Handled => {}
+                ConfirmResponse::None => {}
+            },
+        }
+        AppResponse::None
+    }
+}
+
+/// Records how the file is shown in its sidecar, then writes the sidecar next to the file if it's
+/// been saved. The notification has the message, or why the sidecar couldn't be written
+fn keep_sidecar(
+    sidecar: &mut Sidecar,
+    view: ViewSettings,
+    param: &Param,
+    file: Option<&PathBuf>,
+    message: String,
+    theme: Theme,
+) -> Notification {
+    sidecar.record(view, &param.level_filters());
+    match file.map(|file| save_sidecar(file, sidecar).map(|()| file)) {
+        Some(Ok(file)) => Notification::new(
+            format!("{}, in {}", message, sidecar_path(file).display()),
+            NotificationKind::Info,
+            theme,
+        ),
+        Some(Err(err)) => Notification::new(
+            format!("Failed to save the sidecar: {}", err),
+            NotificationKind::Error,
+            theme,
+        ),
+        None => Notification::new(
+            format!("{}, to be kept next to the file once it's saved", message),
+            NotificationKind::Info,
+            theme,
+        ),
+    }
+}
+
+/// Whether the file has changes which aren't in the version it was opened or last saved as.
+/// Params which weren't read from a file always do
+fn differs_from_saved(saved: Option<&Arc<ParamStruct>>, param: &Param) -> bool {
+    saved.is_none_or(|saved| **saved != *param.snapshot())
+}
+
+/// Where the open file is among the files opened together, and how many there are, if there's
+/// more than one
+fn file_position(files: &[FileSlot], file: Option<&PathBuf>) -> Option<(usize, usize)> {
+    if files.len() < 2 {
+        return None;
+    }
+    let position = files.iter().position(|slot| Some(&slot.path) == file)?;
+    Some((position, files.len()))
+}
+
+/// The file after or before the open one among the files opened together. From a file which
+/// isn't one of them, the first one is next
+fn adjacent_file(files: &[FileSlot], file: Option<&PathBuf>, forward: bool) -> Option<PathBuf> {
+    let len = files.len();
+    if len < 2 {
+        return None;
+    }
+    let next = match (file_position(files, file), forward) {
+        (Some((position, _)), true) => add_mod(position, 1, len),
+        (Some((position, _)), false) => sub_mod(position, 1, len),
+        (None, true) => 0,
+        (None, false) => len - 1,
+    };
+    files.get(next).map(|slot| slot.path.clone())
+}
+
+/// Passes an event to the tree if there is one, otherwise to the columns. The columns follow
+/// the tree's selection, so the commands acting on the selected param work the same in both
+fn view_event(param: &mut Param, tree: Option<&mut TreeView>, event: Event) -> ParamResponse {
+    let tree = match tree {
+        Some(tree) => tree,
+        None => return param.handle_event(event),
+    };
+    match tree.handle_event(event) {
+        TreeResponse::None => ParamResponse::None,
+        TreeResponse::Handled => {
+            param.reveal(&tree.selected_path().0);
+            ParamResponse::Handled { edited: false }
+        }
+        TreeResponse::Edit(replacement) => {
+            let description = format!("Edited {}", replacement.path);
+            let edited = param.replace_values(description, &[replacement]) > 0;
+            tree.set_root(param.snapshot());
+            ParamResponse::Handled { edited }
+        }
+    }
+}
+
+/// Records the edit just made to the selected param
+fn log_edit(param: &Param) {
+    if !log::log_enabled!(log::Level::Debug) {
+        return;
+    }
+    let path = param.selected_path();
+    match param.selected_details().and_then(|details| details.value) {
+        Some(value) => log::debug!("edited {} = {}", path, value),
+        None => log::debug!("edited {}", path),
+    }
+}
+
+impl App for Root {
+    fn handle_event(&mut self, event: Event) -> AppResponse {
+        if let Event::Key(key) = event {
+            // raw mode turns Ctrl+C into a key instead of a signal
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                return self.interrupt();
+            }
+        }
+        if !self.jobs.is_empty() {
+            // the results of cancelled jobs are dropped when they finish
+            if let Event::Key(key) = event {
+                if key.code == KeyCode::Esc {
+                    self.jobs.clear();
+                }
+            }
+            return AppResponse::None;
+        }
+        if let Some(error) = &mut self.error {
+            if error.handle_event(event) == ErrorPopupResponse::Dismiss {
+                self.error = None;
+            }
+            return AppResponse::None;
+        }
+        match &self.state {
+            State::Empty(_) => self.handle_empty_event(event),
+            State::Normal { state, .. } if matches!(**state, NormalState::View) => {
+                self.handle_view_event(event)
+            }
+            State::Normal { .. } => self.handle_panel_event(event),
+        }
+    }
+
+    fn draw(&mut self, full_rect: tui_components::tui::layout::Rect, buffer: &mut Buffer) {
+        let settings = self.settings();
+        let theme = settings.theme;
+        if full_rect.width < MIN_WIDTH || full_rect.height < MIN_HEIGHT {
+            // the mouse can't click anything which isn't drawn
+            self.breadcrumbs.clear();
+            let message = format!(
+                "The terminal is too small. Make it at least {}x{}",
+                MIN_WIDTH, MIN_HEIGHT
+            );
+            let paragraph = Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            let height = full_rect.height.min(3);
+            let area = Rect {
+                y: full_rect.y + (full_rect.height - height) / 2,
+                height,
+                ..full_rect
+            };
+            Widget::render(paragraph, area, buffer);
+            return;
+        }
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(full_rect);
+        let rect = areas[0];
+        self.breadcrumbs = self.status_bar().draw(areas[1], buffer);
+        let explorer_rect = rect.centered(rect.scaled(0.75, 0.75));
+
+        match &mut self.state {
+            State::Empty(EmptyState::View) => {
+                Empty {
+                    open: self.keys.open,
+                    theme,
+                }
+                .draw(rect, buffer);
+            }
+            State::Empty(EmptyState::Open(open)) => {
+                open.draw(explorer_rect, buffer);
+            }
+            State::Empty(EmptyState::OpenArc(prompt)) => {
+                Empty {
+                    open: self.keys.open,
+                    theme,
+                }
+                .draw(rect, buffer);
+                prompt.draw(rect, buffer);
+            }
+            State::Empty(EmptyState::Warnings(warnings)) => {
+                Empty {
+                    open: self.keys.open,
+                    theme,
+                }
+                .draw(rect, buffer);
+                warnings.draw(explorer_rect, buffer);
+            }
+            State::Empty(EmptyState::ConfirmDownload(confirm)) => {
+                Empty {
+                    open: self.keys.open,
+                    theme,
+                }
+                .draw(rect, buffer);
+                confirm.draw(rect, buffer);
+            }
+            State::Empty(EmptyState::ConfirmRestore(confirm, _)) => {
+                Empty {
+                    open: self.keys.open,
+                    theme,
+                }
+                .draw(rect, buffer);
+                confirm.draw(rect, buffer);
+            }
+            State::Normal {
+                param,
+                tree,
+                compare,
+                edited: _,
+                state,
+            } => {
+                if let Some(DiskBaseline::Loaded(disk)) = &self.disk {
+                    param.mark_against(disk);
+                }
+                if let Some(tree) = tree {
+                    tree.set_baseline(match &self.disk {
+                        Some(DiskBaseline::Loaded(disk)) => Some(disk.clone()),
+                        _ => None,
+                    });
+                }
+                let rect = if self.details {
+                    let panes = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([
+                            Constraint::Min(0),
+                            Constraint::Length(DETAILS_WIDTH.min(rect.width / 2)),
+                        ])
+                        .split(rect);
+                    let hashes = settings.hash_display;
+                    let path = param.selected_path();
+                    Details {
+                        note: self.sidecar.note(&path).map(str::to_owned),
+                        path,
+                        details: param.selected_details(),
+                        hashes,
+                        theme,
+                    }
+                    .draw(panes[1], buffer);
+                    panes[0]
+                } else {
+                    rect
+                };
+                match compare {
+                    Some(compare) => {
+                        let panes = Layout::default()
+                            .direction(Direction::Horizontal)
+                            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                            .split(rect);
+                        match tree {
+                            Some(tree) => {
+                                tree.set_root(param.snapshot());
+                                tree.draw(panes[0], buffer);
+                            }
+                            None => {
+                                let param_buffer = param.draw(panes[0], buffer);
+                                buffer.merge(&param_buffer);
+                            }
+                        }
+                        let mut compare_buffer = compare.draw(panes[1], buffer);
+                        compare_buffer.area.x += panes[1].x;
+                        buffer.merge(&compare_buffer);
+                    }
+                    None => match tree {
+                        Some(tree) => {
+                            tree.set_root(param.snapshot());
+                            tree.draw(rect, buffer);
+                        }
+                        None => {
+                            let param_buffer = param.draw(rect, buffer);
+                            buffer.merge(&param_buffer);
+                        }
+                    },
+                }
+
+                match state.as_mut() {
+                    NormalState::View => {}
+                    NormalState::Open(open) => {
+                        let clear = Clear;
+                        clear.render(explorer_rect, buffer);
+                        open.draw(explorer_rect, buffer)
+                    }
+                    NormalState::OpenCompare(open) | NormalState::Import(open) => {
+                        let clear = Clear;
+                        clear.render(explorer_rect, buffer);
+                        open.draw(explorer_rect, buffer)
+                    }
+                    NormalState::Save(save)
+                    | NormalState::Export(save, _)
+                    | NormalState::PluginExport(save, _) => {
+                        let clear = Clear;
+                        clear.render(explorer_rect, buffer);
+                        save.draw(explorer_rect, buffer)
+                    }
+                    // TODO: updated boundaries
+                    NormalState::ConfirmExit(confirm) => confirm.draw(rect, buffer),
+                    NormalState::ConfirmOpen(confirm) => confirm.draw(rect, buffer),
+                    NormalState::ConfirmSwitch(confirm, _) => confirm.draw(rect, buffer),
+                    NormalState::ConfirmNew(confirm) => confirm.draw(rect, buffer),
+                    NormalState::ConfirmRevert(confirm) => confirm.draw(rect, buffer),
+                    NormalState::ConfirmDownload(confirm) => confirm.draw(rect, buffer),
+                    NormalState::Crack(cracker) => cracker.draw(rect, buffer),
+                    NormalState::Report(report) => report.draw(explorer_rect, buffer),
+                    NormalState::Replace(replace) => replace.draw(explorer_rect, buffer),
+                    NormalState::Transform(transform) => transform.draw(explorer_rect, buffer),
+                    NormalState::Script(script) => script.draw(explorer_rect, buffer),
+                    NormalState::Palette(palette) => palette.draw(explorer_rect, buffer),
+                    NormalState::Stats(stats) => stats.draw(explorer_rect, buffer),
+                    NormalState::Structure(structure) => structure.draw(explorer_rect, buffer),
+                    NormalState::Warnings(warnings) => warnings.draw(explorer_rect, buffer),
+                    NormalState::History(history) => history.draw(explorer_rect, buffer),
+                    NormalState::Snapshots(snapshots) => snapshots.draw(explorer_rect, buffer),
+                    NormalState::ImportKey(prompt, ..) => prompt.draw(rect, buffer),
+                    NormalState::Note(prompt, _) => prompt.draw(rect, buffer),
+                    NormalState::OpenArc(prompt) => prompt.draw(rect, buffer),
+                    NormalState::Paths(paths) => paths.draw(rect, buffer),
+                    // over the status bar, where vim has its command line
+                    NormalState::Command(command) => command.draw(areas[1], buffer),
+                }
+            }
+        }
+
+        if let Some(notification) = &mut self.notification {
+            notification.draw(full_rect, buffer);
+        }
+        if let Some(error) = &mut self.error {
+            error.draw(rect, buffer);
+        }
+        if let Some(job) = self.jobs.front_mut() {
+            job.busy.draw(rect, buffer);
         }
     }
 }
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use prc::ParamStruct;
+use tui_components::components::Input;
+use tui_components::crossterm::event::{KeyCode, KeyModifiers};
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Direction, Layout, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::replace::Replacement;
+use crate::script::run_rhai;
+use crate::theme::Theme;
+use crate::utils::paste::{clipboard_text, is_paste, paste_events};
+
+use super::preview::ReplacementPreview;
+
+/// A prompt for a rhai script which edits the file, like
+/// `for p in paths("fighter_param_table[*].walk_speed") { set(p, get(p) * 1.05) }`,
+/// listing every change before it's applied
+#[derive(Debug)]
+pub struct ScriptInput {
+    input: Input,
+    /// The file when the prompt was opened, which the changes are previewed against
+    root: Arc<ParamStruct>,
+    preview: ReplacementPreview,
+    theme: Theme,
+}
+
+#[derive(Debug)]
+pub enum ScriptInputResponse {
+    None,
+    Handled,
+    /// The user confirmed the previewed changes
+    Apply(Vec<Replacement>),
+    Cancel,
+}
+
+impl ScriptInput {
+    pub fn new(root: Arc<ParamStruct>, theme: Theme) -> Self {
+        let mut input = Input::default().editing_style(Style::default().bg(theme.highlight));
+        input.focused = true;
+        Self {
+            input,
+            root,
+            preview: ReplacementPreview::default(),
+            theme,
+        }
+    }
+
+    fn update_replacements(&mut self) {
+        self.preview.set(run_rhai(&self.root, &self.input.value));
+    }
+}
+
+impl Component for ScriptInput {
+    type Response = ScriptInputResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return ScriptInputResponse::None,
+        };
+        if is_paste(&key) {
+            for event in paste_events(&clipboard_text().unwrap_or_default()) {
+                self.handle_event(event);
+            }
+            return ScriptInputResponse::Handled;
+        }
+        match key.code {
+            KeyCode::Char(_) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return ScriptInputResponse::None
+            }
+            KeyCode::Up => self.preview.move_selection(true),
+            KeyCode::Down => self.preview.move_selection(false),
+            KeyCode::Enter if self.preview.valid_count() > 0 => {
+                return ScriptInputResponse::Apply(self.preview.take());
+            }
+            KeyCode::Enter => {}
+            KeyCode::Esc => return ScriptInputResponse::Cancel,
+            KeyCode::Char(_) | KeyCode::Backspace => {
+                self.input.handle_event(event);
+                self.update_replacements();
+            }
+            _ => return ScriptInputResponse::None,
+        }
+        ScriptInputResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                "Run a script",
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(rect);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+
+        let mut script = Spans::from(Span::styled(
+            "script: ",
+            Style::default().fg(self.theme.hint),
+        ));
+        script.0.extend(self.input.get_spans().0);
+        let help = Span::styled(
+            "like for p in paths(\"table[*].speed\") { set(p, get(p) * 1.05) }",
+            Style::default().fg(self.theme.hint),
+        );
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(block, rect, buffer);
+        Widget::render(Paragraph::new(script), areas[0], buffer);
+        Widget::render(Paragraph::new(Spans::from(help)), areas[1], buffer);
+        self.preview.draw(areas[2], buffer, self.theme);
+    }
+}
@@ -0,0 +1,248 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use prc::ParamStruct;
+use tui_components::components::{Input, InputResponse};
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::diff::diff;
+use crate::theme::Theme;
+use crate::utils::modulo::{add_mod, sub_mod};
+
+/// A file as it was at some point in the session, kept under a name to go back to or compare
+/// with. Taking one only keeps another reference to the params, which are copied once the file
+/// is next edited
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub name: String,
+    /// The file it was taken of, so it's only offered for that file
+    pub file: Option<PathBuf>,
+    pub root: Arc<ParamStruct>,
+}
+
+/// A snapshot as the panel lists it
+#[derive(Debug, Clone)]
+pub struct SnapshotEntry {
+    /// Where the snapshot is among every one taken in the session
+    pub index: usize,
+    pub name: String,
+    /// How many params differ between the snapshot and the file now
+    pub differences: usize,
+}
+
+impl SnapshotEntry {
+    /// Lists the snapshots taken of the file, comparing each with how it is now
+    pub fn list(snapshots: &[Snapshot], file: Option<&PathBuf>, root: &ParamStruct) -> Vec<Self> {
+        snapshots
+            .iter()
+            .enumerate()
+            .filter(|(_, snapshot)| snapshot.file.as_ref() == file)
+            .map(|(index, snapshot)| SnapshotEntry {
+                index,
+                name: snapshot.name.clone(),
+                differences: diff(&snapshot.root, root).len(),
+            })
+            .collect()
+    }
+}
+
+/// A panel listing the snapshots of the open file, to take more, go back to one, or compare
+/// the file with one
+#[derive(Debug)]
+pub struct SnapshotPanel {
+    entries: Vec<SnapshotEntry>,
+    selected: usize,
+    offset: usize,
+    /// The name being typed for a new snapshot
+    naming: Option<Input>,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub enum SnapshotPanelResponse {
+    None,
+    Handled,
+    /// The user named a new snapshot of the file as it is now
+    Take(String),
+    /// The user chose to put the file back as it was in the snapshot at this index
+    Restore(usize),
+    /// The user chose to show the snapshot at this index next to the file
+    Compare(usize),
+    Delete(usize),
+    Exit,
+}
+
+impl SnapshotPanel {
+    /// Starts naming a snapshot straight away if there are none yet
+    pub fn new(entries: Vec<SnapshotEntry>, theme: Theme) -> Self {
+        let mut panel = Self {
+            entries,
+            selected: 0,
+            offset: 0,
+            naming: None,
+            theme,
+        };
+        if panel.entries.is_empty() {
+            panel.start_naming();
+        }
+        panel
+    }
+
+    /// Lists the snapshots again after one was deleted, keeping the same row selected
+    pub fn update(&mut self, entries: Vec<SnapshotEntry>) {
+        self.entries = entries;
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn key_hints(&self) -> &'static str {
+        if self.naming.is_some() {
+            "Enter: take the snapshot  Esc: cancel"
+        } else {
+            "Up/Down: choose  n: new  Enter: restore  c: compare  d: delete  Esc: close"
+        }
+    }
+
+    fn start_naming(&mut self) {
+        let mut input = Input::default().editing_style(Style::default().bg(self.theme.highlight));
+        input.value = format!("Snapshot {}", self.entries.len() + 1);
+        input.focused = true;
+        self.naming = Some(input);
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.entries.get(self.selected).map(|entry| entry.index)
+    }
+
+    fn handle_naming_event(&mut self, event: Event) -> SnapshotPanelResponse {
+        let input = match &mut self.naming {
+            Some(input) => input,
+            None => return SnapshotPanelResponse::None,
+        };
+        match input.handle_event(event) {
+            InputResponse::Submit => {
+                let name = input.value.trim().to_owned();
+                self.naming = None;
+                if name.is_empty() {
+                    SnapshotPanelResponse::Handled
+                } else {
+                    SnapshotPanelResponse::Take(name)
+                }
+            }
+            InputResponse::Cancel => {
+                self.naming = None;
+                SnapshotPanelResponse::Handled
+            }
+            InputResponse::Edited { .. } => SnapshotPanelResponse::Handled,
+            InputResponse::None => SnapshotPanelResponse::None,
+        }
+    }
+}
+
+impl Component for SnapshotPanel {
+    type Response = SnapshotPanelResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if self.naming.is_some() {
+            return self.handle_naming_event(event);
+        }
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return SnapshotPanelResponse::None,
+        };
+        let len = self.entries.len();
+        match key.code {
+            KeyCode::Up if len > 0 => self.selected = sub_mod(self.selected, 1, len),
+            KeyCode::Down if len > 0 => self.selected = add_mod(self.selected, 1, len),
+            KeyCode::Char('n') => self.start_naming(),
+            KeyCode::Enter => {
+                return self
+                    .selected_index()
+                    .map_or(SnapshotPanelResponse::None, SnapshotPanelResponse::Restore)
+            }
+            KeyCode::Char('c') => {
+                return self
+                    .selected_index()
+                    .map_or(SnapshotPanelResponse::None, SnapshotPanelResponse::Compare)
+            }
+            KeyCode::Char('d') => {
+                return self
+                    .selected_index()
+                    .map_or(SnapshotPanelResponse::None, SnapshotPanelResponse::Delete)
+            }
+            KeyCode::Esc => return SnapshotPanelResponse::Exit,
+            _ => return SnapshotPanelResponse::None,
+        }
+        SnapshotPanelResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Snapshots ({})", self.entries.len()),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let mut inner = block.inner(rect);
+        Widget::render(Clear, rect, buffer);
+        Widget::render(block, rect, buffer);
+
+        if let Some(input) = &self.naming {
+            let mut spans = vec![Span::styled("Name: ", Style::default().fg(self.theme.hint))];
+            spans.extend(input.get_spans().0);
+            Widget::render(
+                Paragraph::new(Spans::from(spans)),
+                Rect { height: 1, ..inner },
+                buffer,
+            );
+            // a line between the name and the snapshots
+            inner.y += 2;
+            inner.height = inner.height.saturating_sub(2);
+        }
+
+        let height = inner.height as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let lines = if self.entries.is_empty() {
+            vec![Spans::from(Span::styled(
+                "No snapshots of this file yet",
+                Style::default().fg(self.theme.hint),
+            ))]
+        } else {
+            self.entries
+                .iter()
+                .enumerate()
+                .skip(self.offset)
+                .take(height)
+                .map(|(row, entry)| {
+                    let style = if row == self.selected && self.naming.is_none() {
+                        Style::default().bg(self.theme.highlight)
+                    } else {
+                        Style::default()
+                    };
+                    let differences = match entry.differences {
+                        0 => "the same as now".to_owned(),
+                        count => format!("{} param(s) differ from now", count),
+                    };
+                    Spans::from(vec![
+                        Span::styled(entry.name.as_str(), style),
+                        Span::styled("  ", style),
+                        Span::styled(differences, style.fg(self.theme.hint)),
+                    ])
+                })
+                .collect()
+        };
+        Widget::render(Paragraph::new(lines), inner, buffer);
+    }
+}
@@ -0,0 +1,97 @@
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event};
+
+use crate::stats::FileStats;
+use crate::theme::Theme;
+use crate::utils::format_size::format_size;
+
+/// A panel with figures about the whole file
+#[derive(Debug)]
+pub struct StatsPanel {
+    stats: FileStats,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum StatsPanelResponse {
+    None,
+    Exit,
+}
+
+impl StatsPanel {
+    pub fn new(stats: FileStats, theme: Theme) -> Self {
+        Self { stats, theme }
+    }
+
+    fn line(&self, name: &str, value: String) -> Spans<'static> {
+        Spans::from(vec![
+            Span::styled(
+                format!("{:<24}", name),
+                Style::default().fg(self.theme.hint),
+            ),
+            Span::raw(value),
+        ])
+    }
+}
+
+impl Component for StatsPanel {
+    type Response = StatsPanelResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        match event {
+            Event::Key(key) if matches!(key.code, KeyCode::Esc | KeyCode::Enter) => {
+                StatsPanelResponse::Exit
+            }
+            _ => StatsPanelResponse::None,
+        }
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                "File stats",
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+
+        let stats = &self.stats;
+        let mut lines = vec![
+            self.line("params", stats.params.to_string()),
+            self.line("max depth", stats.max_depth.to_string()),
+            self.line("hashes", stats.hashes.to_string()),
+            self.line("unlabeled hashes", stats.unknown_hashes.to_string()),
+            self.line(
+                "string table",
+                format!(
+                    "{} ({} strings)",
+                    format_size(stats.string_table_size as u64).trim(),
+                    stats.strings
+                ),
+            ),
+            self.line(
+                "estimated size",
+                format!(
+                    "{} as a param file",
+                    format_size(stats.estimated_size as u64).trim()
+                ),
+            ),
+            Spans::default(),
+        ];
+        lines.extend(
+            stats
+                .types
+                .iter()
+                .map(|(ty, count)| self.line(ty, count.to_string())),
+        );
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(Paragraph::new(lines).block(block), rect, buffer);
+    }
+}
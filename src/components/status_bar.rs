@@ -0,0 +1,84 @@
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::{Modifier, Style};
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Paragraph, Widget};
+use tui_components::{Component, Event};
+use unicode_width::UnicodeWidthStr;
+
+use crate::path::ParamPath;
+
+use crate::theme::Theme;
+
+/// The line at the bottom of the screen, showing the open file, where the cursor is,
+/// whether there are unsaved changes, and the keys which do something right now.
+/// Each segment of the path can be clicked to go back to that level
+#[derive(Debug, Default)]
+pub struct StatusBar {
+    pub file: Option<String>,
+    pub path: Option<ParamPath>,
+    /// Whether the level each segment of the path is in has a filter, which colors the segment
+    pub filtered: Vec<bool>,
+    pub edited: bool,
+    pub hints: String,
+    pub theme: Theme,
+}
+
+impl Component for StatusBar {
+    type Response = ();
+    /// Where each segment of the path was drawn
+    type DrawResponse = Vec<Rect>;
+
+    fn handle_event(&mut self, _event: Event) -> Self::Response {}
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) -> Self::DrawResponse {
+        let file = self.file.as_deref().unwrap_or("no file");
+        let mut spans = vec![Span::styled(
+            format!(" {} ", file),
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        if self.edited {
+            spans.push(Span::styled(
+                "[modified] ",
+                Style::default().fg(self.theme.accent),
+            ));
+        }
+        let mut segments = vec![];
+        if let Some(path) = &self.path {
+            let mut x = rect.x + spans.iter().map(|span| span.width() as u16).sum::<u16>();
+            let strings = path.segment_strings();
+            let last = strings.len().saturating_sub(1);
+            for (i, segment) in strings.into_iter().enumerate() {
+                let width = segment.width() as u16;
+                // segments past the right edge aren't drawn, so they're left with no width
+                let start = x.min(rect.right());
+                segments.push(Rect {
+                    x: start,
+                    y: rect.y,
+                    width: width.min(rect.right() - start),
+                    height: 1,
+                });
+                x = x.saturating_add(width);
+                // the levels which can be gone back to are underlined
+                let mut style = if i < last {
+                    Style::default().add_modifier(Modifier::UNDERLINED)
+                } else {
+                    Style::default()
+                };
+                if self.filtered.get(i).copied().unwrap_or_default() {
+                    style = style.fg(self.theme.accent);
+                }
+                spans.push(Span::styled(segment, style));
+            }
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(
+            format!("| {}", self.hints),
+            Style::default().fg(self.theme.hint),
+        ));
+        let paragraph =
+            Paragraph::new(Spans::from(spans)).style(Style::default().bg(self.theme.status_bar));
+        Widget::render(paragraph, rect, buffer);
+        segments
+    }
+}
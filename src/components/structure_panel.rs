@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event};
+
+use crate::diff::{ShapeChange, ShapeNode};
+use crate::path::ParamPath;
+use crate::theme::Theme;
+use crate::utils::modulo::{add_mod, sub_mod};
+
+use super::tree_view::{COLLAPSED, EXPANDED, INDENT, LEAF};
+
+/// A visible row of the tree
+#[derive(Debug)]
+struct ShapeRow {
+    /// The indices of the node and each of its parents, from the top of the tree
+    at: Vec<usize>,
+}
+
+/// A collapsible tree of how the structure of the open file differs from the compared one:
+/// keys only one of them has, lists of different lengths, and params of different types
+#[derive(Debug)]
+pub struct StructurePanel {
+    nodes: Vec<ShapeNode>,
+    /// The paths of the nodes which are expanded
+    expanded: HashSet<ParamPath>,
+    rows: Vec<ShapeRow>,
+    selected: usize,
+    offset: usize,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub enum StructurePanelResponse {
+    None,
+    Handled,
+    /// The user chose to go to the selected param
+    Open(ParamPath),
+    Exit,
+}
+
+impl StructurePanel {
+    pub fn new(nodes: Vec<ShapeNode>, theme: Theme) -> Self {
+        let mut panel = Self {
+            nodes,
+            expanded: HashSet::new(),
+            rows: vec![],
+            selected: 0,
+            offset: 0,
+            theme,
+        };
+        panel.update_rows();
+        panel
+    }
+
+    fn node(&self, at: &[usize]) -> &ShapeNode {
+        let mut node = &self.nodes[at[0]];
+        for index in &at[1..] {
+            node = &node.children[*index];
+        }
+        node
+    }
+
+    fn update_rows(&mut self) {
+        fn push_rows(
+            nodes: &[ShapeNode],
+            at: &mut Vec<usize>,
+            expanded: &HashSet<ParamPath>,
+            rows: &mut Vec<ShapeRow>,
+        ) {
+            for (index, node) in nodes.iter().enumerate() {
+                at.push(index);
+                rows.push(ShapeRow { at: at.clone() });
+                if expanded.contains(&node.path) {
+                    push_rows(&node.children, at, expanded, rows);
+                }
+                at.pop();
+            }
+        }
+
+        let mut rows = vec![];
+        push_rows(&self.nodes, &mut vec![], &self.expanded, &mut rows);
+        self.rows = rows;
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    /// Expands or collapses the selected node, if it has differences below it
+    fn set_expanded(&mut self, expand: bool) {
+        let node = match self.rows.get(self.selected) {
+            Some(row) => self.node(&row.at),
+            None => return,
+        };
+        if node.children.is_empty() {
+            return;
+        }
+        let path = node.path.clone();
+        if expand {
+            self.expanded.insert(path);
+        } else {
+            self.expanded.remove(&path);
+        }
+        self.update_rows();
+    }
+
+    /// Collapses the selected node, or selects its parent if it's already collapsed
+    fn collapse(&mut self) {
+        let row = match self.rows.get(self.selected) {
+            Some(row) => row,
+            None => return,
+        };
+        if self.expanded.contains(&self.node(&row.at).path) {
+            self.set_expanded(false);
+        } else if row.at.len() > 1 {
+            let parent = &row.at[..row.at.len() - 1];
+            if let Some(index) = self.rows.iter().position(|row| row.at == parent) {
+                self.selected = index;
+            }
+        }
+    }
+
+    fn row_spans(&self, row: &ShapeRow, style: Style) -> Spans<'static> {
+        let node = self.node(&row.at);
+        let marker = match (node.children.is_empty(), self.expanded.contains(&node.path)) {
+            (true, _) => LEAF,
+            (false, true) => EXPANDED,
+            (false, false) => COLLAPSED,
+        };
+        let name = node.path.segment_strings().pop().unwrap_or_default();
+        let mut spans = vec![Span::styled(
+            format!(
+                "{}{}{}",
+                " ".repeat((row.at.len() - 1) * INDENT),
+                marker,
+                name.trim_start_matches('.')
+            ),
+            style,
+        )];
+        let (sign, text, color) = match &node.change {
+            Some(ShapeChange::Added(ty)) => (
+                "+",
+                format!("only in the compared file ({})", ty),
+                self.theme.success,
+            ),
+            Some(ShapeChange::Removed(ty)) => {
+                ("-", format!("only in this file ({})", ty), self.theme.error)
+            }
+            Some(ShapeChange::Retyped(old, new)) => {
+                ("~", format!("{} -> {}", old, new), self.theme.warning)
+            }
+            Some(ShapeChange::Resized(old, new)) => (
+                "#",
+                format!("{} -> {} entries", old, new),
+                self.theme.accent,
+            ),
+            None => ("", String::new(), self.theme.hint),
+        };
+        if !sign.is_empty() {
+            spans.push(Span::styled(
+                format!("  {} {}", sign, text),
+                style.fg(color),
+            ));
+        }
+        if !node.children.is_empty() && !self.expanded.contains(&node.path) {
+            spans.push(Span::styled(
+                format!(
+                    "  ({} below)",
+                    node.children.iter().map(ShapeNode::count).sum::<usize>()
+                ),
+                style.fg(self.theme.hint),
+            ));
+        }
+        Spans::from(spans)
+    }
+}
+
+impl Component for StructurePanel {
+    type Response = StructurePanelResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return StructurePanelResponse::None,
+        };
+        let len = self.rows.len();
+        match key.code {
+            KeyCode::Up if len > 0 => self.selected = sub_mod(self.selected, 1, len),
+            KeyCode::Down if len > 0 => self.selected = add_mod(self.selected, 1, len),
+            KeyCode::Right => self.set_expanded(true),
+            KeyCode::Left => self.collapse(),
+            KeyCode::Enter => {
+                return match self.rows.get(self.selected) {
+                    Some(row) => StructurePanelResponse::Open(self.node(&row.at).path.clone()),
+                    None => StructurePanelResponse::None,
+                }
+            }
+            KeyCode::Esc => return StructurePanelResponse::Exit,
+            _ => return StructurePanelResponse::None,
+        }
+        StructurePanelResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let count = self.nodes.iter().map(ShapeNode::count).sum::<usize>();
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Structural differences ({})", count),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let height = block.inner(rect).height as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let lines = if self.rows.is_empty() {
+            vec![Spans::from(Span::styled(
+                "Both files have the same structure",
+                Style::default().fg(self.theme.hint),
+            ))]
+        } else {
+            self.rows
+                .iter()
+                .enumerate()
+                .skip(self.offset)
+                .take(height)
+                .map(|(index, row)| {
+                    let style = if index == self.selected {
+                        Style::default().bg(self.theme.highlight)
+                    } else {
+                        Style::default()
+                    };
+                    self.row_spans(row, style)
+                })
+                .collect()
+        };
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(Paragraph::new(lines).block(block), rect, buffer);
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use prc::ParamStruct;
+use tui_components::components::Input;
+use tui_components::crossterm::event::{KeyCode, KeyModifiers};
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Direction, Layout, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use tui_components::{Component, Event, Spannable};
+
+use crate::path::ParamPath;
+use crate::replace::Replacement;
+use crate::theme::Theme;
+use crate::transform::{transform_values, Transform};
+use crate::utils::paste::{clipboard_text, is_paste, paste_events};
+
+use super::preview::ReplacementPreview;
+
+/// A panel for applying one operation to many numbers, like the rows matching a filter,
+/// listing every change before it's applied
+#[derive(Debug)]
+pub struct TransformInput {
+    input: Input,
+    /// The file when the panel was opened, which the changes are previewed against
+    root: Arc<ParamStruct>,
+    /// The params the operation applies to. Any which aren't numbers are left alone
+    paths: Vec<ParamPath>,
+    preview: ReplacementPreview,
+    theme: Theme,
+}
+
+#[derive(Debug)]
+pub enum TransformInputResponse {
+    None,
+    Handled,
+    /// The user confirmed the previewed changes
+    Apply(Vec<Replacement>),
+    Cancel,
+}
+
+impl TransformInput {
+    pub fn new(root: Arc<ParamStruct>, paths: Vec<ParamPath>, theme: Theme) -> Self {
+        let mut input = Input::default().editing_style(Style::default().bg(theme.highlight));
+        input.focused = true;
+        Self {
+            input,
+            root,
+            paths,
+            preview: ReplacementPreview::default(),
+            theme,
+        }
+    }
+
+    fn update_replacements(&mut self) {
+        self.preview.set(
+            Transform::parse(&self.input.value)
+                .map(|transform| transform_values(&self.root, &self.paths, &transform)),
+        );
+    }
+}
+
+impl Component for TransformInput {
+    type Response = TransformInputResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return TransformInputResponse::None,
+        };
+        if is_paste(&key) {
+            for event in paste_events(&clipboard_text().unwrap_or_default()) {
+                self.handle_event(event);
+            }
+            return TransformInputResponse::Handled;
+        }
+        match key.code {
+            KeyCode::Char(_) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return TransformInputResponse::None
+            }
+            KeyCode::Up => self.preview.move_selection(true),
+            KeyCode::Down => self.preview.move_selection(false),
+            KeyCode::Enter if self.preview.valid_count() > 0 => {
+                return TransformInputResponse::Apply(self.preview.take());
+            }
+            KeyCode::Enter => {}
+            KeyCode::Esc => return TransformInputResponse::Cancel,
+            KeyCode::Char(_) | KeyCode::Backspace => {
+                self.input.handle_event(event);
+                self.update_replacements();
+            }
+            _ => return TransformInputResponse::None,
+        }
+        TransformInputResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Change the {} shown params", self.paths.len()),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(rect);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+
+        let mut operation = Spans::from(Span::styled(
+            "operation: ",
+            Style::default().fg(self.theme.hint),
+        ));
+        operation.0.extend(self.input.get_spans().0);
+        let help = Span::styled(
+            "like *1.05 or +2 to change each value, 10 to set them all, or clamp 0 100",
+            Style::default().fg(self.theme.hint),
+        );
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(block, rect, buffer);
+        Widget::render(Paragraph::new(operation), areas[0], buffer);
+        Widget::render(Paragraph::new(Spans::from(help)), areas[1], buffer);
+        self.preview.draw(areas[2], buffer, self.theme);
+    }
+}
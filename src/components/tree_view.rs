@@ -0,0 +1,684 @@
+use std::collections::{BTreeSet, HashSet};
+use std::sync::{Arc, Mutex};
+
+use prc::{ParamKind, ParamStruct};
+use serde::{Deserialize, Serialize};
+use tui_components::components::{Input, InputResponse};
+use tui_components::crossterm::event::{KeyCode, MouseButton, MouseEventKind};
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::{Constraint, Rect};
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Cell, Row, StatefulWidget, Table, TableState};
+use tui_components::{Component, Event, Spannable};
+use unicode_width::UnicodeWidthStr;
+
+use crate::enums::{enum_options, int_value, with_int};
+use crate::keys::Key;
+use crate::path::{ParamPath, PathSegment};
+use crate::replace::Replacement;
+use crate::report::hash_usage;
+use crate::settings::Settings;
+use crate::utils::modulo::{add_mod, sub_mod};
+use crate::utils::mouse::{ClickTracker, SCROLL_ROWS};
+use crate::utils::paste::{clipboard_text, is_paste, paste_events};
+use crate::utils::type_ahead::TypeAhead;
+use crate::utils::vim::{is_plain, vim_code};
+use crate::value::{format_value, param_type, parse_value};
+
+use super::enum_picker::{EnumPicker, EnumPickerResponse};
+use super::hash_input::{HashInput, HashInputResponse};
+use super::param::{keyed_value, number_width, popup_area, RowKind};
+
+/// How the params are laid out
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewMode {
+    /// Each opened list or struct gets a column to the right of its parent
+    #[default]
+    Columns,
+    /// One tree, where lists and structs are expanded in place below their row
+    Tree,
+}
+
+impl ViewMode {
+    pub fn toggle(self) -> Self {
+        match self {
+            ViewMode::Columns => ViewMode::Tree,
+            ViewMode::Tree => ViewMode::Columns,
+        }
+    }
+}
+
+pub(crate) const EXPANDED: &str = "▼ ";
+pub(crate) const COLLAPSED: &str = "▶ ";
+pub(crate) const LEAF: &str = "  ";
+/// How far each level of the tree is indented
+pub(crate) const INDENT: usize = 2;
+/// The width of the widest type name
+const TYPE_WIDTH: u16 = 6;
+
+/// The whole file as one tree, where lists and structs are expanded and collapsed in place
+#[derive(Debug)]
+pub struct TreeView {
+    /// The file the rows were listed from, replaced whenever it's edited
+    root: Arc<ParamStruct>,
+    /// Another version of the file, like the saved one, whose differences are highlighted
+    baseline: Option<Arc<ParamStruct>>,
+    /// The paths of the lists and structs which are expanded
+    expanded: HashSet<ParamPath>,
+    rows: Vec<TreeRow>,
+    selected: usize,
+    offset: usize,
+    /// The value being typed for the selected row, if it's being edited
+    editing: Option<TreeInput>,
+    sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+    settings: Arc<Mutex<Settings>>,
+    /// Where the rows were last drawn, for finding the row under the mouse
+    area: Option<Rect>,
+    clicks: ClickTracker,
+    /// The start of a name being typed to jump to
+    type_ahead: TypeAhead,
+}
+
+/// An input for the selected row's value. Hashes get their own, which suggests labels, and
+/// integers whose key's values have names are picked from them
+#[derive(Debug)]
+enum TreeInput {
+    Value(Input),
+    Hash(HashInput),
+    Enum(EnumPicker),
+}
+
+#[derive(Debug)]
+struct TreeRow {
+    path: ParamPath,
+    depth: usize,
+    parent: bool,
+    /// Where the param is in its list or struct
+    position: usize,
+}
+
+#[derive(Debug)]
+pub enum TreeResponse {
+    None,
+    /// The selection may have moved
+    Handled,
+    /// The user changed the selected value
+    Edit(Replacement),
+}
+
+impl TreeView {
+    pub fn new(
+        root: Arc<ParamStruct>,
+        sorted_labels: Arc<Mutex<BTreeSet<String>>>,
+        settings: Arc<Mutex<Settings>>,
+    ) -> Self {
+        let mut tree = Self {
+            root,
+            baseline: None,
+            expanded: HashSet::new(),
+            rows: vec![],
+            selected: 0,
+            offset: 0,
+            editing: None,
+            sorted_labels,
+            settings,
+            area: None,
+            clicks: ClickTracker::default(),
+            type_ahead: TypeAhead::default(),
+        };
+        tree.update_rows();
+        tree
+    }
+
+    /// Shows the file as it is now. The rows are only listed again if it changed
+    pub fn set_root(&mut self, root: Arc<ParamStruct>) {
+        if !Arc::ptr_eq(&self.root, &root) {
+            self.root = root;
+            self.update_rows();
+        }
+    }
+
+    /// Highlights the params which differ from another version of the file, or stops if None
+    pub fn set_baseline(&mut self, baseline: Option<Arc<ParamStruct>>) {
+        self.baseline = baseline;
+    }
+
+    /// The path of the selected param, or an empty path if the file is empty
+    pub fn selected_path(&self) -> ParamPath {
+        self.rows
+            .get(self.selected)
+            .map(|row| row.path.clone())
+            .unwrap_or_default()
+    }
+
+    /// Expands every list and struct down to the param at the path, and selects it
+    pub fn reveal(&mut self, path: &ParamPath) {
+        for end in 1..path.0.len() {
+            self.expanded.insert(ParamPath(path.0[..end].to_vec()));
+        }
+        self.update_rows();
+        if let Some(row) = self.rows.iter().position(|row| row.path == *path) {
+            self.selected = row;
+        }
+    }
+
+    pub fn key_hints(&self, save: Key) -> String {
+        match self.editing {
+            Some(TreeInput::Hash(_)) => {
+                return "Tab: complete  Up/Down: suggestions  Enter: confirm  Esc: cancel".into()
+            }
+            Some(TreeInput::Enum(_)) => {
+                return "Up/Down: choose  Enter: confirm  Esc: cancel".into()
+            }
+            Some(TreeInput::Value(_)) => return "Enter: confirm  Esc: cancel".into(),
+            None => {}
+        }
+        format!(
+            "Enter: expand/edit  Left/Right: collapse/expand  'name: jump  {}: save  Esc: quit",
+            save
+        )
+    }
+
+    fn settings(&self) -> Settings {
+        self.settings
+            .lock()
+            .map(|settings| *settings)
+            .unwrap_or_default()
+    }
+
+    /// Lists the rows again, keeping the same param selected if it's still there
+    fn update_rows(&mut self) {
+        fn push_rows(
+            param: &ParamKind,
+            position: usize,
+            path: &mut ParamPath,
+            expanded: &HashSet<ParamPath>,
+            rows: &mut Vec<TreeRow>,
+        ) {
+            let parent = matches!(param, ParamKind::List(_) | ParamKind::Struct(_));
+            rows.push(TreeRow {
+                path: path.clone(),
+                depth: path.0.len() - 1,
+                parent,
+                position,
+            });
+            if !parent || !expanded.contains(path) {
+                return;
+            }
+            match param {
+                ParamKind::List(list) => {
+                    for (index, child) in list.0.iter().enumerate() {
+                        path.0.push(PathSegment::Index(index));
+                        push_rows(child, index, path, expanded, rows);
+                        path.0.pop();
+                    }
+                }
+                ParamKind::Struct(str) => push_struct(str, path, expanded, rows),
+                _ => {}
+            }
+        }
+
+        fn push_struct(
+            str: &ParamStruct,
+            path: &mut ParamPath,
+            expanded: &HashSet<ParamPath>,
+            rows: &mut Vec<TreeRow>,
+        ) {
+            for (position, (key, child)) in str.0.iter().enumerate() {
+                path.0.push(PathSegment::Key(*key));
+                push_rows(child, position, path, expanded, rows);
+                path.0.pop();
+            }
+        }
+
+        let current = self.rows.get(self.selected).map(|row| row.path.clone());
+        let mut rows = vec![];
+        push_struct(
+            &self.root,
+            &mut ParamPath::default(),
+            &self.expanded,
+            &mut rows,
+        );
+        self.rows = rows;
+        self.selected = current
+            .and_then(|current| self.rows.iter().position(|row| row.path == current))
+            .unwrap_or(self.selected)
+            .min(self.rows.len().saturating_sub(1));
+    }
+
+    fn selected_param(&self) -> Option<&ParamKind> {
+        self.rows.get(self.selected)?.path.get(&self.root)
+    }
+
+    /// Expands or collapses the selected list or struct
+    fn set_expanded(&mut self, expand: bool) {
+        let row = match self.rows.get(self.selected) {
+            Some(row) if row.parent => row,
+            _ => return,
+        };
+        if expand {
+            self.expanded.insert(row.path.clone());
+        } else {
+            self.expanded.remove(&row.path);
+        }
+        self.update_rows();
+    }
+
+    fn is_expanded(&self, row: &TreeRow) -> bool {
+        row.parent && self.expanded.contains(&row.path)
+    }
+
+    /// How many rows fit in the tree at once
+    fn page(&self) -> usize {
+        self.area.map_or(1, |area| (area.height as usize).max(1))
+    }
+
+    /// Selects the row of the list or struct the selected param is in
+    fn select_parent(&mut self) {
+        let depth = match self.rows.get(self.selected) {
+            Some(row) if row.depth > 0 => row.depth,
+            _ => return,
+        };
+        if let Some(parent) = self.rows[..self.selected]
+            .iter()
+            .rposition(|row| row.depth < depth)
+        {
+            self.selected = parent;
+        }
+    }
+
+    /// Acts on the selected row like pressing Enter: lists and structs are expanded or collapsed,
+    /// bools are flipped, and anything else starts being edited
+    fn activate(&mut self) -> TreeResponse {
+        let row = match self.rows.get(self.selected) {
+            Some(row) => row,
+            None => return TreeResponse::None,
+        };
+        if row.parent {
+            let expand = !self.is_expanded(row);
+            self.set_expanded(expand);
+            return TreeResponse::Handled;
+        }
+        let value = self.selected_param().and_then(int_value);
+        if let (Some(value), Some(options)) =
+            (value, value.and(row.path.key()).and_then(enum_options))
+        {
+            let picker = EnumPicker::new(value, options, self.settings().theme);
+            self.editing = Some(TreeInput::Enum(picker));
+            return TreeResponse::Handled;
+        }
+        match self.selected_param() {
+            Some(ParamKind::Bool(_)) => self.toggle_bool(),
+            Some(ParamKind::Hash(hash)) => {
+                let input = HashInput::new(
+                    *hash,
+                    self.sorted_labels.clone(),
+                    &hash_usage(&self.root),
+                    self.settings().theme,
+                );
+                self.editing = Some(TreeInput::Hash(input));
+                TreeResponse::Handled
+            }
+            Some(param) => {
+                let theme = self.settings().theme;
+                let mut input = Input::default()
+                    .editing_style(Style::default().bg(theme.highlight))
+                    .error_style(Style::default().fg(theme.error));
+                input.value = format_value(param);
+                input.focused = true;
+                self.editing = Some(TreeInput::Value(input));
+                TreeResponse::Handled
+            }
+            None => TreeResponse::None,
+        }
+    }
+
+    /// Flips the selected bool, without doing anything to other params
+    fn toggle_bool(&self) -> TreeResponse {
+        match (self.rows.get(self.selected), self.selected_param()) {
+            (Some(row), Some(ParamKind::Bool(value))) => TreeResponse::Edit(Replacement {
+                path: row.path.clone(),
+                old: ParamKind::Bool(*value),
+                new: Ok(ParamKind::Bool(!value)),
+            }),
+            _ => TreeResponse::None,
+        }
+    }
+
+    fn handle_editing(&mut self, event: Event) -> TreeResponse {
+        let new = match &mut self.editing {
+            Some(TreeInput::Value(input)) => match input.handle_event(event) {
+                InputResponse::Submit => {
+                    let old = match self.rows.get(self.selected) {
+                        Some(row) => row.path.get(&self.root),
+                        None => None,
+                    };
+                    let old = match old {
+                        Some(old) => old,
+                        None => {
+                            self.editing = None;
+                            return TreeResponse::Handled;
+                        }
+                    };
+                    match parse_value(old, &input.value) {
+                        Ok(new) => new,
+                        Err(err) => {
+                            input.error = Some(err);
+                            return TreeResponse::Handled;
+                        }
+                    }
+                }
+                InputResponse::Cancel => {
+                    self.editing = None;
+                    return TreeResponse::Handled;
+                }
+                InputResponse::Edited { .. } => {
+                    input.error = None;
+                    return TreeResponse::Handled;
+                }
+                InputResponse::None => return TreeResponse::Handled,
+            },
+            Some(TreeInput::Hash(input)) => match input.handle_event(event) {
+                HashInputResponse::Submit => ParamKind::Hash(input.value()),
+                HashInputResponse::Cancel => {
+                    self.editing = None;
+                    return TreeResponse::Handled;
+                }
+                HashInputResponse::Handled | HashInputResponse::None => {
+                    return TreeResponse::Handled
+                }
+            },
+            Some(TreeInput::Enum(picker)) => match picker.handle_event(event) {
+                EnumPickerResponse::Submit => {
+                    let value = picker.value();
+                    match self.selected_param().and_then(|old| with_int(old, value)) {
+                        Some(new) => new,
+                        None => return TreeResponse::Handled,
+                    }
+                }
+                EnumPickerResponse::Cancel => {
+                    self.editing = None;
+                    return TreeResponse::Handled;
+                }
+                EnumPickerResponse::Handled | EnumPickerResponse::None => {
+                    return TreeResponse::Handled
+                }
+            },
+            None => return TreeResponse::None,
+        };
+        self.editing = None;
+        let (row, old) = match (self.rows.get(self.selected), self.selected_param()) {
+            (Some(row), Some(old)) if *old != new => (row, old.clone()),
+            _ => return TreeResponse::Handled,
+        };
+        TreeResponse::Edit(Replacement {
+            path: row.path.clone(),
+            old,
+            new: Ok(new),
+        })
+    }
+
+    fn row_name(&self, row: &TreeRow) -> String {
+        let marker = match (row.parent, self.is_expanded(row)) {
+            (true, true) => EXPANDED,
+            (true, false) => COLLAPSED,
+            (false, _) => LEAF,
+        };
+        format!(
+            "{}{}{}",
+            " ".repeat(row.depth * INDENT),
+            marker,
+            self.name(row)
+        )
+    }
+
+    /// The key or index of the param within its parent
+    fn name(&self, row: &TreeRow) -> String {
+        match row.path.0.last() {
+            Some(PathSegment::Key(hash)) => self.settings().hash_display.format(*hash),
+            Some(PathSegment::Index(index)) => index.to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// Adds the letter to the name being typed, and selects the first param in the same list or
+    /// struct as the selected one whose name starts with it
+    fn type_ahead(&mut self, c: char) {
+        let prefix = self.type_ahead.push(c).to_owned();
+        let depth = match self.rows.get(self.selected) {
+            Some(row) => row.depth,
+            None => return,
+        };
+        let start = self.rows[..self.selected]
+            .iter()
+            .rposition(|row| row.depth < depth)
+            .map_or(0, |parent| parent + 1);
+        let found = self.rows[start..]
+            .iter()
+            .take_while(|row| row.depth >= depth)
+            .position(|row| {
+                row.depth == depth && self.name(row).to_lowercase().starts_with(&prefix)
+            });
+        if let Some(row) = found {
+            self.selected = start + row;
+        }
+    }
+}
+
+impl Component for TreeView {
+    type Response = TreeResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        if self.editing.is_some() {
+            if let Event::Key(key) = event {
+                if is_paste(&key) {
+                    for event in paste_events(&clipboard_text().unwrap_or_default()) {
+                        self.handle_editing(event);
+                    }
+                    return TreeResponse::Handled;
+                }
+            }
+            return self.handle_editing(event);
+        }
+        let len = self.rows.len();
+        match event {
+            Event::Key(key) => {
+                let plain = is_plain(&key);
+                // while a name is being typed, every letter is part of it rather than a command
+                if let KeyCode::Char(c) = key.code {
+                    if plain && TypeAhead::accepts(c) && self.type_ahead.is_typing() {
+                        self.type_ahead(c);
+                        return TreeResponse::Handled;
+                    }
+                }
+                match vim_code(&key) {
+                    KeyCode::Up if len > 0 => self.selected = sub_mod(self.selected, 1, len),
+                    KeyCode::Down if len > 0 => self.selected = add_mod(self.selected, 1, len),
+                    KeyCode::Char('g') if plain => self.selected = 0,
+                    KeyCode::Char('G') if plain => self.selected = len.saturating_sub(1),
+                    KeyCode::Home => self.selected = 0,
+                    KeyCode::End => self.selected = len.saturating_sub(1),
+                    KeyCode::PageUp => self.selected = self.selected.saturating_sub(self.page()),
+                    KeyCode::PageDown => {
+                        self.selected = (self.selected + self.page()).min(len.saturating_sub(1))
+                    }
+                    KeyCode::Right => match self.rows.get(self.selected) {
+                        Some(row) if self.is_expanded(row) => {
+                            self.selected = (self.selected + 1).min(len - 1)
+                        }
+                        _ => self.set_expanded(true),
+                    },
+                    KeyCode::Left => match self.rows.get(self.selected) {
+                        Some(row) if self.is_expanded(row) => self.set_expanded(false),
+                        _ => self.select_parent(),
+                    },
+                    KeyCode::Backspace => self.select_parent(),
+                    KeyCode::Enter => return self.activate(),
+                    KeyCode::Char(' ') => return self.toggle_bool(),
+                    KeyCode::Char(TypeAhead::START) => self.type_ahead.start(),
+                    _ => return TreeResponse::None,
+                }
+            }
+            Event::Mouse(mouse) => {
+                let area = match self.area {
+                    Some(area)
+                        if mouse.column >= area.x
+                            && mouse.column < area.x + area.width
+                            && mouse.row >= area.y
+                            && mouse.row < area.y + area.height =>
+                    {
+                        area
+                    }
+                    _ => return TreeResponse::None,
+                };
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        let row = self.offset + (mouse.row - area.y) as usize;
+                        if row >= len {
+                            return TreeResponse::Handled;
+                        }
+                        let double = self.clicks.click(mouse.column, mouse.row);
+                        self.selected = row;
+                        if double {
+                            return self.activate();
+                        }
+                    }
+                    MouseEventKind::ScrollUp => {
+                        self.selected = self.selected.saturating_sub(SCROLL_ROWS)
+                    }
+                    MouseEventKind::ScrollDown => {
+                        self.selected = (self.selected + SCROLL_ROWS).min(len.saturating_sub(1))
+                    }
+                    _ => return TreeResponse::None,
+                }
+            }
+        }
+        TreeResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let settings = self.settings();
+        let theme = settings.theme;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent));
+        let inner = block.inner(rect);
+        self.area = Some(inner);
+
+        let height = inner.height as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+        self.offset = self.offset.min(self.rows.len().saturating_sub(height));
+
+        let visible = &self.rows[self.offset..(self.offset + height).min(self.rows.len())];
+        // row numbers are as wide as the last one of any list or struct shown, so the column
+        // doesn't change width when scrolling
+        let number_space = if settings.row_numbers {
+            let last = self.rows.iter().map(|row| row.position).max();
+            number_width(last.map_or(0, |last| last + 1)) + 1
+        } else {
+            0
+        };
+        let names = visible
+            .iter()
+            .map(|row| self.row_name(row))
+            .collect::<Vec<_>>();
+        let name_width = names
+            .iter()
+            .map(|name| name.width())
+            .max()
+            .unwrap_or_default()
+            .min(inner.width as usize / 2) as u16;
+        let table_rows = visible
+            .iter()
+            .zip(names)
+            .enumerate()
+            .map(|(i, (row, name))| {
+                let row_path = &row.path;
+                let param = row_path.get(&self.root);
+                let differs =
+                    matches!(&self.baseline, Some(baseline) if row_path.get(baseline) != param);
+                // rows which differ from the saved file are colored as a whole
+                let ty = match param {
+                    Some(param) if !differs => Cell::from(param_type(param))
+                        .style(Style::default().fg(RowKind::of(param).color(&theme))),
+                    _ => Cell::from(param.map(param_type).unwrap_or_default()),
+                };
+                let value = match (&self.editing, self.offset + i == self.selected) {
+                    (Some(TreeInput::Value(input)), true) => input.get_spans(),
+                    (Some(TreeInput::Hash(input)), true) => input.get_spans(),
+                    (Some(TreeInput::Enum(picker)), true) => picker.get_spans(),
+                    _ => Spans::from(Span::raw(
+                        param
+                            .map(|param| {
+                                let (hashes, floats) =
+                                    (settings.hash_display, settings.float_format);
+                                keyed_value(row_path.key(), param, hashes, floats)
+                            })
+                            .unwrap_or_default(),
+                    )),
+                };
+                let mut cells = vec![Cell::from(name), ty, Cell::from(value)];
+                if number_space > 0 {
+                    let number = format!("{:>1$}", row.position, number_space as usize - 1);
+                    let style = Style::default().fg(theme.hint);
+                    cells.insert(0, Cell::from(number).style(style));
+                }
+                let row = Row::new(cells);
+                if differs {
+                    row.style(Style::default().fg(theme.accent))
+                } else {
+                    row
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut widths = vec![
+            Constraint::Length(name_width),
+            Constraint::Length(TYPE_WIDTH),
+            Constraint::Length(
+                inner
+                    .width
+                    .saturating_sub(number_space + name_width + TYPE_WIDTH + 2),
+            ),
+        ];
+        if number_space > 0 {
+            widths.insert(0, Constraint::Length(number_space - 1));
+        }
+        let table = Table::new(table_rows)
+            .block(block)
+            .widths(&widths)
+            .column_spacing(1)
+            .highlight_style(Style::default().bg(theme.highlight));
+        let mut state = TableState::default();
+        if !self.rows.is_empty() {
+            state.select(Some(self.selected - self.offset));
+        }
+        StatefulWidget::render(table, rect, buffer, &mut state);
+
+        if let Some(row) = state.selected() {
+            let value_x = inner.x + number_space + name_width + TYPE_WIDTH + 2;
+            let row_y = inner.y + row as u16;
+            match &self.editing {
+                Some(TreeInput::Hash(hash)) => {
+                    let size = (hash.suggestions_width(), hash.suggestions_height());
+                    if let Some(area) = popup_area(size, inner, inner, value_x, row_y) {
+                        hash.draw_suggestions(area, buffer);
+                    }
+                }
+                Some(TreeInput::Enum(picker)) => {
+                    let size = (picker.options_width(), picker.options_height());
+                    if let Some(area) = popup_area(size, inner, inner, value_x, row_y) {
+                        picker.draw_options(area, buffer);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
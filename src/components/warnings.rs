@@ -0,0 +1,138 @@
+use tui_components::crossterm::event::KeyCode;
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Style;
+use tui_components::tui::text::{Span, Spans};
+use tui_components::tui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
+use tui_components::{Component, Event};
+
+use crate::path::ParamPath;
+use crate::theme::Theme;
+use crate::utils::modulo::{add_mod, sub_mod};
+
+/// Something which doesn't stop the editor from working, but may be a mistake
+#[derive(Debug, Clone)]
+pub struct Warning {
+    pub message: String,
+    /// The param the warning is about, if it's about one
+    pub path: Option<ParamPath>,
+}
+
+/// A panel listing problems like hashes with more than one label, or params which don't match
+/// the schema
+#[derive(Debug)]
+pub struct WarningsPanel {
+    warnings: Vec<Warning>,
+    selected: usize,
+    /// How many lines are scrolled past
+    scroll: u16,
+    theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub enum WarningsPanelResponse {
+    None,
+    Handled,
+    /// The user chose to go to the param the selected warning is about
+    Open(ParamPath),
+    Exit,
+}
+
+impl WarningsPanel {
+    pub fn new(warnings: Vec<Warning>, theme: Theme) -> Self {
+        Self {
+            warnings,
+            selected: 0,
+            scroll: 0,
+            theme,
+        }
+    }
+}
+
+impl Component for WarningsPanel {
+    type Response = WarningsPanelResponse;
+    type DrawResponse = ();
+
+    fn handle_event(&mut self, event: Event) -> Self::Response {
+        let key = match event {
+            Event::Key(key) => key,
+            _ => return WarningsPanelResponse::None,
+        };
+        let len = self.warnings.len();
+        match key.code {
+            KeyCode::Up if len > 0 => self.selected = sub_mod(self.selected, 1, len),
+            KeyCode::Down if len > 0 => self.selected = add_mod(self.selected, 1, len),
+            KeyCode::Enter => {
+                return match self.warnings.get(self.selected) {
+                    Some(Warning {
+                        path: Some(path), ..
+                    }) => WarningsPanelResponse::Open(path.clone()),
+                    _ => WarningsPanelResponse::None,
+                }
+            }
+            KeyCode::Esc => return WarningsPanelResponse::Exit,
+            _ => return WarningsPanelResponse::None,
+        }
+        WarningsPanelResponse::Handled
+    }
+
+    fn draw(&mut self, rect: Rect, buffer: &mut Buffer) {
+        let block = Block::default()
+            .title(Span::styled(
+                format!("Warnings ({})", self.warnings.len()),
+                Style::default().fg(self.theme.title),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.accent));
+        let inner = block.inner(rect);
+        // each warning takes as many rows as it wraps onto
+        let width = inner.width.max(1) as usize;
+        let heights = self
+            .warnings
+            .iter()
+            .map(|warning| (2 + warning.message.chars().count()).div_ceil(width) as u16)
+            .collect::<Vec<_>>();
+        let top = heights[..self.selected.min(heights.len())]
+            .iter()
+            .sum::<u16>();
+        let bottom = top + heights.get(self.selected).copied().unwrap_or(1);
+        if top < self.scroll {
+            self.scroll = top;
+        } else if bottom > self.scroll + inner.height {
+            self.scroll = bottom.saturating_sub(inner.height);
+        }
+
+        let lines = if self.warnings.is_empty() {
+            vec![Spans::from(Span::styled(
+                "No warnings",
+                Style::default().fg(self.theme.hint),
+            ))]
+        } else {
+            self.warnings
+                .iter()
+                .enumerate()
+                .map(|(row, warning)| {
+                    let style = if row == self.selected {
+                        Style::default().bg(self.theme.highlight)
+                    } else {
+                        Style::default()
+                    };
+                    Spans::from(vec![
+                        Span::styled("! ", style.fg(self.theme.warning)),
+                        Span::styled(warning.message.as_str(), style),
+                    ])
+                })
+                .collect()
+        };
+
+        Widget::render(Clear, rect, buffer);
+        Widget::render(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            rect,
+            buffer,
+        );
+    }
+}
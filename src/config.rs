@@ -0,0 +1,81 @@
+use std::env::{current_exe, var_os};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::components::explorer::ExplorerSort;
+use crate::components::tree_view::ViewMode;
+use crate::format::Format;
+use crate::keys::KeyBindings;
+use crate::theme::Theme;
+
+pub const CONFIG_FILE: &str = "prickly.toml";
+
+/// The options read from the config file. Anything missing keeps its default
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The param labels file to read, instead of looking for `ParamLabels.csv`
+    pub labels: Option<PathBuf>,
+    /// The schema to check files against, instead of looking for `Schema.toml`
+    pub schema: Option<PathBuf>,
+    /// The names of integer values to show, instead of looking for `Enums.toml`
+    pub enums: Option<PathBuf>,
+    /// The colors, instead of reading `Theme.toml`
+    pub theme: Option<Theme>,
+    pub keys: KeyBindings,
+    /// Whether the params are shown as columns or as one tree
+    pub layout: ViewMode,
+    /// How many seconds to wait between saving edits to the open file, or 0 to never autosave
+    pub autosave: u64,
+    /// The format of files saved without an extension, and of `dump` without `--format`
+    pub export_format: Option<Format>,
+    /// How much Left and Right change a float being edited, instead of 0.1
+    pub float_step: Option<f32>,
+    /// The folder the game's files were extracted to, for opening files by their path in the
+    /// game's archive, like `fighter/mario/param/vl.prc`
+    pub game_root: Option<PathBuf>,
+    pub explorer: ExplorerConfig,
+}
+
+/// How the file explorer starts out each time it's opened
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExplorerConfig {
+    /// List every file, instead of only param files
+    pub show_all: bool,
+    pub sort: ExplorerSort,
+    pub descending: bool,
+}
+
+/// The config directory under `$XDG_CONFIG_HOME`, or `~/.config` where that isn't set
+fn xdg_config_dir() -> Option<PathBuf> {
+    var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .map(|dir| dir.join("prickly"))
+}
+
+/// Finds the config file, checking the application directory first, then the XDG config directory
+fn config_path() -> Option<PathBuf> {
+    let exe = current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(CONFIG_FILE)));
+    let xdg = xdg_config_dir().map(|dir| dir.join(CONFIG_FILE));
+    exe.into_iter().chain(xdg).find(|path| path.is_file())
+}
+
+/// Reads the config file, or the one given with `--config`. Without one, the defaults are used
+pub fn load_config(path: Option<&Path>) -> Result<Config, String> {
+    let path = match path.map(Path::to_path_buf).or_else(config_path) {
+        Some(path) => path,
+        None => return Ok(Config::default()),
+    };
+    log::info!("reading the config from {}", path.display());
+    read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| toml::from_str(&text).map_err(|err| err.to_string()))
+        .map_err(|err| format!("Failed to load {}: {}", path.display(), err))
+}
@@ -0,0 +1,212 @@
+use std::collections::BTreeSet;
+use std::env::current_exe;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{spawn, JoinHandle};
+
+use prc::hash40::label_map::LabelMap;
+use prc::hash40::{hash40, Hash40};
+
+pub const WORDLIST_FILE: &str = "Wordlist.txt";
+
+const PREFIXES: &[&str] = &["is_", "enable_", "disable_", "use_", "max_", "min_", "num_"];
+const SUFFIXES: &[&str] = &[
+    "_num", "_max", "_min", "_frame", "_speed", "_rate", "_id", "_kind", "_type", "_x", "_y", "_z",
+    "_table", "_param", "_list", "_flag", "_count", "_scale", "_offset", "_range",
+];
+/// Numbered suffixes are tried from 0 up to this value, e.g. `_0`, `_1`, ..., `_20`
+const MAX_SUFFIX_NUMBER: usize = 20;
+/// How many candidates are checked between progress updates
+const BATCH_SIZE: usize = 4096;
+
+/// A background search for a label matching a hash.
+///
+/// Candidates are built from the words in the known labels and a user wordlist,
+/// combined with common prefixes, suffixes, and other words.
+#[derive(Debug)]
+pub struct Cracker {
+    target: Hash40,
+    checked: Arc<AtomicUsize>,
+    total: usize,
+    cancelled: Arc<AtomicBool>,
+    found: Arc<Mutex<Vec<String>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Cracker {
+    pub fn new(target: Hash40, sorted_labels: &Mutex<BTreeSet<String>>) -> Self {
+        let (words, parts) = vocabulary(sorted_labels);
+        let affixes = affixes();
+        let total = words.len() * (1 + affixes.len()) + parts.len() * parts.len();
+
+        let checked = Arc::new(AtomicUsize::new(0));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let found = Arc::new(Mutex::new(Vec::new()));
+        let handle = {
+            let checked = checked.clone();
+            let cancelled = cancelled.clone();
+            let found = found.clone();
+            spawn(move || {
+                let mut search = Search {
+                    target,
+                    length: target.str_len() as usize,
+                    checked: &checked,
+                    cancelled: &cancelled,
+                    found: &found,
+                    batch: 0,
+                };
+                search.run(&words, &parts, &affixes);
+            })
+        };
+
+        Self {
+            target,
+            checked,
+            total,
+            cancelled,
+            found,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn target(&self) -> Hash40 {
+        self.target
+    }
+
+    /// The fraction of candidates checked so far, between 0 and 1
+    pub fn progress(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.checked.load(Ordering::Relaxed) as f64 / self.total as f64).min(1.0)
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    pub fn found(&self) -> Vec<String> {
+        self.found
+            .lock()
+            .map(|found| found.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.join().unwrap_or_default();
+        }
+    }
+}
+
+impl Drop for Cracker {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+struct Search<'a> {
+    target: Hash40,
+    length: usize,
+    checked: &'a AtomicUsize,
+    cancelled: &'a AtomicBool,
+    found: &'a Mutex<Vec<String>>,
+    batch: usize,
+}
+
+impl<'a> Search<'a> {
+    fn run(&mut self, words: &[String], parts: &[String], affixes: &[(String, String)]) {
+        for word in words {
+            if !self.check(word.len(), || word.clone()) {
+                return;
+            }
+            for (prefix, suffix) in affixes {
+                let length = prefix.len() + word.len() + suffix.len();
+                if !self.check(length, || format!("{}{}{}", prefix, word, suffix)) {
+                    return;
+                }
+            }
+        }
+        for first in parts {
+            for second in parts {
+                if !self.check(first.len() + second.len() + 1, || {
+                    format!("{}_{}", first, second)
+                }) {
+                    return;
+                }
+            }
+        }
+        self.flush();
+    }
+
+    /// Checks one candidate, only building the string if its length could match.
+    /// Returns false once the search is cancelled
+    fn check<F: FnOnce() -> String>(&mut self, length: usize, candidate: F) -> bool {
+        if length == self.length {
+            let candidate = candidate();
+            if hash40(&candidate) == self.target {
+                if let Ok(mut found) = self.found.lock() {
+                    if !found.contains(&candidate) {
+                        found.push(candidate);
+                    }
+                }
+            }
+        }
+        self.batch += 1;
+        if self.batch == BATCH_SIZE {
+            self.flush();
+            !self.cancelled.load(Ordering::Relaxed)
+        } else {
+            true
+        }
+    }
+
+    fn flush(&mut self) {
+        self.checked.fetch_add(self.batch, Ordering::Relaxed);
+        self.batch = 0;
+    }
+}
+
+/// Collects the whole words (known labels and wordlist entries),
+/// and the parts of words (split by underscores) to try combining
+fn vocabulary(sorted_labels: &Mutex<BTreeSet<String>>) -> (Vec<String>, Vec<String>) {
+    let mut words = sorted_labels
+        .lock()
+        .map(|labels| labels.clone())
+        .unwrap_or_default();
+    let wordlist = LabelMap::read_labels(WORDLIST_FILE).ok().or_else(|| {
+        current_exe()
+            .ok()
+            .and_then(|path| LabelMap::read_labels(path.parent().unwrap().join(WORDLIST_FILE)).ok())
+    });
+    words.extend(
+        wordlist
+            .unwrap_or_default()
+            .into_iter()
+            .map(|word| word.trim().to_owned())
+            .filter(|word| !word.is_empty()),
+    );
+    let parts = words
+        .iter()
+        .flat_map(|word| word.split('_'))
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_owned())
+        .collect::<BTreeSet<_>>();
+    (words.into_iter().collect(), parts.into_iter().collect())
+}
+
+/// The (prefix, suffix) pairs to try around each whole word
+fn affixes() -> Vec<(String, String)> {
+    let prefixes = PREFIXES
+        .iter()
+        .map(|prefix| (prefix.to_string(), String::new()));
+    let suffixes = SUFFIXES
+        .iter()
+        .map(|suffix| (String::new(), suffix.to_string()));
+    let numbers = (0..=MAX_SUFFIX_NUMBER).map(|number| (String::new(), format!("_{}", number)));
+    prefixes.chain(suffixes).chain(numbers).collect()
+}
@@ -0,0 +1,187 @@
+use prc::{ParamKind, ParamList, ParamStruct};
+
+use crate::path::{ParamPath, PathSegment};
+use crate::value::param_type;
+
+/// How a param differs between two files
+#[derive(Debug, Clone)]
+pub enum Change {
+    Added(ParamKind),
+    Removed(ParamKind),
+    /// The old and new params, which may also differ in type
+    Changed(ParamKind, ParamKind),
+}
+
+#[derive(Debug, Clone)]
+pub struct Difference {
+    pub path: ParamPath,
+    pub change: Change,
+}
+
+/// Finds every param which was added, removed, or changed going from `a` to `b`.
+/// Struct children are matched by key and list children by index
+pub fn diff(a: &ParamStruct, b: &ParamStruct) -> Vec<Difference> {
+    let mut differences = vec![];
+    diff_struct(a, b, &mut ParamPath::default(), &mut differences);
+    differences
+}
+
+fn diff_struct(
+    a: &ParamStruct,
+    b: &ParamStruct,
+    path: &mut ParamPath,
+    differences: &mut Vec<Difference>,
+) {
+    for (hash, child_a) in a.0.iter() {
+        path.0.push(PathSegment::Key(*hash));
+        match b.0.iter().find(|(key, _)| key == hash) {
+            Some((_, child_b)) => diff_param(child_a, child_b, path, differences),
+            None => push(differences, path, Change::Removed(child_a.clone())),
+        }
+        path.0.pop();
+    }
+    for (hash, child_b) in b.0.iter() {
+        if !a.0.iter().any(|(key, _)| key == hash) {
+            path.0.push(PathSegment::Key(*hash));
+            push(differences, path, Change::Added(child_b.clone()));
+            path.0.pop();
+        }
+    }
+}
+
+fn diff_list(
+    a: &ParamList,
+    b: &ParamList,
+    path: &mut ParamPath,
+    differences: &mut Vec<Difference>,
+) {
+    for index in 0..a.0.len().max(b.0.len()) {
+        path.0.push(PathSegment::Index(index));
+        match (a.0.get(index), b.0.get(index)) {
+            (Some(child_a), Some(child_b)) => diff_param(child_a, child_b, path, differences),
+            (Some(child_a), None) => push(differences, path, Change::Removed(child_a.clone())),
+            (None, Some(child_b)) => push(differences, path, Change::Added(child_b.clone())),
+            (None, None) => unreachable!(),
+        }
+        path.0.pop();
+    }
+}
+
+fn diff_param(
+    a: &ParamKind,
+    b: &ParamKind,
+    path: &mut ParamPath,
+    differences: &mut Vec<Difference>,
+) {
+    match (a, b) {
+        (ParamKind::Struct(a), ParamKind::Struct(b)) => diff_struct(a, b, path, differences),
+        (ParamKind::List(a), ParamKind::List(b)) => diff_list(a, b, path, differences),
+        _ if a != b => push(differences, path, Change::Changed(a.clone(), b.clone())),
+        _ => {}
+    }
+}
+
+fn push(differences: &mut Vec<Difference>, path: &ParamPath, change: Change) {
+    differences.push(Difference {
+        path: path.clone(),
+        change,
+    });
+}
+
+/// How the shape of a param differs between two files, leaving aside its value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShapeChange {
+    /// A key only the second file has, with the type of its param
+    Added(&'static str),
+    /// A key only the first file has, with the type of its param
+    Removed(&'static str),
+    /// The old and new types
+    Retyped(&'static str, &'static str),
+    /// The old and new lengths of a list
+    Resized(usize, usize),
+}
+
+/// A param in the tree of structural differences. Only params which differ themselves, or have
+/// children which do, are in the tree
+#[derive(Debug, Clone)]
+pub struct ShapeNode {
+    pub path: ParamPath,
+    pub change: Option<ShapeChange>,
+    pub children: Vec<ShapeNode>,
+}
+
+impl ShapeNode {
+    /// How many differences there are at this param and below it
+    pub fn count(&self) -> usize {
+        self.change.iter().count() + self.children.iter().map(ShapeNode::count).sum::<usize>()
+    }
+}
+
+/// Compares the structure of `a` and `b`: the keys in each struct, the lengths of lists, and the
+/// types of params, but not their values. List entries both files have are compared by index
+pub fn diff_structure(a: &ParamStruct, b: &ParamStruct) -> Vec<ShapeNode> {
+    shape_struct(a, b, &mut ParamPath::default())
+}
+
+fn shape_struct(a: &ParamStruct, b: &ParamStruct, path: &mut ParamPath) -> Vec<ShapeNode> {
+    let mut nodes = vec![];
+    for (hash, child_a) in a.0.iter() {
+        path.0.push(PathSegment::Key(*hash));
+        match b.0.iter().find(|(key, _)| key == hash) {
+            Some((_, child_b)) => nodes.extend(shape_param(child_a, child_b, path)),
+            None => nodes.push(leaf(path, ShapeChange::Removed(param_type(child_a)))),
+        }
+        path.0.pop();
+    }
+    for (hash, child_b) in b.0.iter() {
+        if !a.0.iter().any(|(key, _)| key == hash) {
+            path.0.push(PathSegment::Key(*hash));
+            nodes.push(leaf(path, ShapeChange::Added(param_type(child_b))));
+            path.0.pop();
+        }
+    }
+    nodes
+}
+
+fn shape_param(a: &ParamKind, b: &ParamKind, path: &mut ParamPath) -> Option<ShapeNode> {
+    let (change, children) = match (a, b) {
+        (ParamKind::Struct(a), ParamKind::Struct(b)) => (None, shape_struct(a, b, path)),
+        (ParamKind::List(a), ParamKind::List(b)) => {
+            let change =
+                Some(ShapeChange::Resized(a.0.len(), b.0.len())).filter(|_| a.0.len() != b.0.len());
+            let children =
+                a.0.iter()
+                    .zip(b.0.iter())
+                    .enumerate()
+                    .filter_map(|(index, (child_a, child_b))| {
+                        path.0.push(PathSegment::Index(index));
+                        let node = shape_param(child_a, child_b, path);
+                        path.0.pop();
+                        node
+                    })
+                    .collect();
+            (change, children)
+        }
+        _ if param_type(a) != param_type(b) => (
+            Some(ShapeChange::Retyped(param_type(a), param_type(b))),
+            vec![],
+        ),
+        _ => (None, vec![]),
+    };
+    if change.is_none() && children.is_empty() {
+        return None;
+    }
+    Some(ShapeNode {
+        path: path.clone(),
+        change,
+        children,
+    })
+}
+
+fn leaf(path: &ParamPath, change: ShapeChange) -> ShapeNode {
+    ShapeNode {
+        path: path.clone(),
+        change: Some(change),
+        children: vec![],
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::env::current_exe;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use prc::hash40::Hash40;
+use prc::ParamKind;
+
+use crate::value::parse_hash;
+
+pub const ENUMS_FILE: &str = "Enums.toml";
+
+/// The names in use, shared by everything which shows values, like the param labels are
+static ENUM_NAMES: Mutex<Option<EnumNames>> = Mutex::new(None);
+
+/// Names for the values of integer params, like a `kind` of 3 being a "fighter"
+#[derive(Debug, Default, Clone)]
+pub struct EnumNames {
+    names: HashMap<Hash40, BTreeMap<i64, String>>,
+}
+
+impl EnumNames {
+    /// Reads names written in TOML, with a table for each key, like `[kind]` followed by
+    /// `3 = "fighter"`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: BTreeMap<String, BTreeMap<String, String>> =
+            toml::from_str(text).map_err(|err| err.to_string())?;
+        let names = file
+            .into_iter()
+            .map(|(key, values)| {
+                let values = values
+                    .into_iter()
+                    .map(|(value, name)| match value.parse() {
+                        Ok(value) => Ok((value, name)),
+                        Err(_) => Err(format!("{}.{} isn't a number", key, value)),
+                    })
+                    .collect::<Result<_, String>>()?;
+                Ok((parse_hash(&key)?, values))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { names })
+    }
+
+    /// Shows these names wherever values are shown from now on
+    pub fn install(self) {
+        match ENUM_NAMES.lock() {
+            Ok(mut names) => *names = Some(self),
+            Err(err) => *err.into_inner() = Some(self),
+        }
+    }
+}
+
+/// Runs the function on the names in use, if there are any
+fn with_names<T, F: FnOnce(&EnumNames) -> Option<T>>(f: F) -> Option<T> {
+    let lock = ENUM_NAMES.lock();
+    let names = match &lock {
+        Ok(names) => names.as_ref(),
+        Err(err) => err.get_ref().as_ref(),
+    };
+    names.and_then(f)
+}
+
+/// The name of the param's value, if it's an integer under a key whose values have names
+pub fn enum_name(key: Option<Hash40>, param: &ParamKind) -> Option<String> {
+    let value = int_value(param)?;
+    with_names(|names| names.names.get(&key?)?.get(&value).cloned())
+}
+
+/// Every named value of the params under the key, in order of their values
+pub fn enum_options(key: Hash40) -> Option<Vec<(i64, String)>> {
+    with_names(|names| {
+        let values = names.names.get(&key)?;
+        Some(
+            values
+                .iter()
+                .map(|(value, name)| (*value, name.clone()))
+                .collect(),
+        )
+    })
+}
+
+/// The value of an integer param
+pub fn int_value(param: &ParamKind) -> Option<i64> {
+    match param {
+        ParamKind::I8(v) => Some(*v as i64),
+        ParamKind::U8(v) => Some(*v as i64),
+        ParamKind::I16(v) => Some(*v as i64),
+        ParamKind::U16(v) => Some(*v as i64),
+        ParamKind::I32(v) => Some(*v as i64),
+        ParamKind::U32(v) => Some(*v as i64),
+        _ => None,
+    }
+}
+
+/// An integer param of the same type with another value, or None if it doesn't fit in the type
+pub fn with_int(param: &ParamKind, value: i64) -> Option<ParamKind> {
+    Some(match param {
+        ParamKind::I8(_) => ParamKind::I8(i8::try_from(value).ok()?),
+        ParamKind::U8(_) => ParamKind::U8(u8::try_from(value).ok()?),
+        ParamKind::I16(_) => ParamKind::I16(i16::try_from(value).ok()?),
+        ParamKind::U16(_) => ParamKind::U16(u16::try_from(value).ok()?),
+        ParamKind::I32(_) => ParamKind::I32(i32::try_from(value).ok()?),
+        ParamKind::U32(_) => ParamKind::U32(u32::try_from(value).ok()?),
+        _ => return None,
+    })
+}
+
+/// Finds the enum names file, checking the current directory first, then the application
+/// directory
+fn enums_path() -> Option<PathBuf> {
+    let local = PathBuf::from(ENUMS_FILE);
+    if local.is_file() {
+        return Some(local);
+    }
+    let exe = current_exe().ok()?;
+    Some(exe.parent()?.join(ENUMS_FILE)).filter(|path| path.is_file())
+}
+
+/// Reads the enum names file, or the one given in the config. Without one, values are shown as
+/// numbers only
+pub fn load_enums(path: Option<&Path>) -> Result<Option<EnumNames>, String> {
+    let path = match path.map(Path::to_path_buf).or_else(enums_path) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    log::info!("reading the enum names from {}", path.display());
+    read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| EnumNames::parse(&text))
+        .map(Some)
+        .map_err(|err| format!("Failed to load {}: {}", path.display(), err))
+}
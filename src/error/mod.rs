@@ -1,9 +1,16 @@
+use std::fmt::Display;
+use std::io;
+
 use crossterm::ErrorKind;
 use tui_components::crossterm;
 
 #[derive(Debug)]
 pub enum AppError {
     CrossTermError(ErrorKind),
+    /// Reading or writing a file failed
+    Io(io::Error),
+    /// A command was given arguments it can't use
+    InvalidArgument(String),
 }
 
 impl From<ErrorKind> for AppError {
@@ -11,3 +18,13 @@ impl From<ErrorKind> for AppError {
         AppError::CrossTermError(f)
     }
 }
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::CrossTermError(err) => write!(f, "terminal error: {}", err),
+            AppError::Io(err) => write!(f, "{}", err),
+            AppError::InvalidArgument(msg) => write!(f, "{}", msg),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+/// Evaluates arithmetic typed as a new value for a number, using `+`, `-`, `*`, `/`,
+/// parentheses, and hex integers (`0x...`). Text starting with `+`, `*`, or `/` is applied to
+/// the current value, so `*1.1` scales it and `+5` adds to it. Anything else is a value of its
+/// own, including text starting with `-`: `-5` is negative five, not five less than the current
+/// value
+pub fn evaluate(text: &str, current: f64) -> Result<f64, String> {
+    let text = text.trim();
+    let relative = text.starts_with(['+', '*', '/']);
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+    };
+    let value = if relative {
+        parser.apply(current)?
+    } else {
+        parser.expr()?
+    };
+    match parser.tokens.get(parser.pos) {
+        None if value.is_finite() => Ok(value),
+        None => Err(String::from("(not a finite number)")),
+        Some(token) => Err(format!("(unexpected {})", token)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Op(char),
+    Open,
+    Close,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '+' | '-' | '*' | '/' => tokens.push(Token::Op(c)),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '0' if matches!(chars.peek(), Some((_, 'x' | 'X'))) => {
+                chars.next();
+                let mut hex = String::new();
+                while let Some((_, c)) = chars.peek().copied() {
+                    if !c.is_ascii_hexdigit() {
+                        break;
+                    }
+                    hex.push(c);
+                    chars.next();
+                }
+                let number = i64::from_str_radix(&hex, 16)
+                    .map_err(|_| format!("(invalid number 0x{})", hex))?;
+                tokens.push(Token::Number(number as f64));
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut end = start + 1;
+                while let Some((i, c)) = chars.peek().copied() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                let number = &text[start..end];
+                let number = number
+                    .parse()
+                    .map_err(|_| format!("(invalid number {})", number))?;
+                tokens.push(Token::Number(number));
+            }
+            c => return Err(format!("(unexpected '{}')", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads the tokens by precedence: sums of products of (possibly negated) numbers or groups
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_op(&self, ops: &[char]) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(op) => Some(*op),
+            _ => None,
+        }
+    }
+
+    /// Continues an expression whose first term has already been evaluated
+    fn apply(&mut self, first: f64) -> Result<f64, String> {
+        let mut term = first;
+        while let Some(op) = self.peek_op(&['*', '/']) {
+            self.pos += 1;
+            term = product(term, op, self.factor()?);
+        }
+        self.sum(term)
+    }
+
+    fn expr(&mut self) -> Result<f64, String> {
+        let first = self.term()?;
+        self.sum(first)
+    }
+
+    fn sum(&mut self, first: f64) -> Result<f64, String> {
+        let mut value = first;
+        while let Some(op) = self.peek_op(&['+', '-']) {
+            self.pos += 1;
+            let term = self.term()?;
+            value = if op == '+' {
+                value + term
+            } else {
+                value - term
+            };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.factor()?;
+        while let Some(op) = self.peek_op(&['*', '/']) {
+            self.pos += 1;
+            value = product(value, op, self.factor()?);
+        }
+        Ok(value)
+    }
+
+    fn factor(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(number),
+            Some(Token::Op('-')) => Ok(-self.factor()?),
+            Some(Token::Op('+')) => self.factor(),
+            Some(Token::Open) => {
+                let value = self.expr()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err(String::from("(missing ')')")),
+                }
+            }
+            Some(token) => Err(format!("(unexpected {})", token)),
+            None => Err(String::from("(incomplete)")),
+        }
+    }
+}
+
+fn product(lhs: f64, op: char, rhs: f64) -> f64 {
+    if op == '*' {
+        lhs * rhs
+    } else {
+        lhs / rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(evaluate("5", 1.0), Ok(5.0));
+        assert_eq!(evaluate(" 1.25 ", 1.0), Ok(1.25));
+        assert_eq!(evaluate("0x1f", 1.0), Ok(31.0));
+        assert_eq!(evaluate("0X10", 1.0), Ok(16.0));
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(evaluate("1 + 2 * 3", 0.0), Ok(7.0));
+        assert_eq!(evaluate("10 - 4 - 3", 0.0), Ok(3.0));
+        assert_eq!(evaluate("8 / 4 / 2", 0.0), Ok(1.0));
+        assert_eq!(evaluate("(1 + 2) * 3", 0.0), Ok(9.0));
+        assert_eq!(evaluate("2 * (3 + (4 - 1))", 0.0), Ok(12.0));
+    }
+
+    #[test]
+    fn negation() {
+        assert_eq!(evaluate("-5", 10.0), Ok(-5.0));
+        assert_eq!(evaluate("2 * -3", 0.0), Ok(-6.0));
+        assert_eq!(evaluate("-(1 + 2)", 0.0), Ok(-3.0));
+        assert_eq!(evaluate("--2", 0.0), Ok(2.0));
+    }
+
+    #[test]
+    fn relative() {
+        assert_eq!(evaluate("+5", 10.0), Ok(15.0));
+        assert_eq!(evaluate("*1.5", 10.0), Ok(15.0));
+        assert_eq!(evaluate("/4", 10.0), Ok(2.5));
+        // the rest of the expression keeps its precedence
+        assert_eq!(evaluate("*2 + 1", 10.0), Ok(21.0));
+        assert_eq!(evaluate("+2 * 3", 10.0), Ok(16.0));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(evaluate("", 0.0), Err(String::from("(incomplete)")));
+        assert_eq!(evaluate("1 +", 0.0), Err(String::from("(incomplete)")));
+        assert_eq!(evaluate("(1 + 2", 0.0), Err(String::from("(missing ')')")));
+        assert_eq!(evaluate("1 2", 0.0), Err(String::from("(unexpected 2)")));
+        assert_eq!(evaluate("1 )", 0.0), Err(String::from("(unexpected ')')")));
+        assert_eq!(evaluate("abc", 0.0), Err(String::from("(unexpected 'a')")));
+        assert_eq!(
+            evaluate("1.2.3", 0.0),
+            Err(String::from("(invalid number 1.2.3)"))
+        );
+        assert_eq!(
+            evaluate("0x", 0.0),
+            Err(String::from("(invalid number 0x)"))
+        );
+        assert_eq!(
+            evaluate("1 / 0", 0.0),
+            Err(String::from("(not a finite number)"))
+        );
+    }
+}
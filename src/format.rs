@@ -0,0 +1,173 @@
+use std::fs::{read, write};
+use std::io::{Cursor, Error, ErrorKind, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use prc::xml::{read_xml, write_xml};
+use prc::ParamStruct;
+use serde::Deserialize;
+
+/// The extensions of the files which can be opened, shown in the explorer by default
+pub const PARAM_EXTENSIONS: &[&str] = &["prc", "stprm", "stdat", "xml", "json"];
+
+/// Whether the file has one of the extensions which can be opened
+pub fn is_param_file<P: AsRef<Path>>(path: P) -> bool {
+    path.as_ref().extension().is_some_and(|ext| {
+        let ext = ext.to_string_lossy().to_lowercase();
+        PARAM_EXTENSIONS.contains(&ext.as_str())
+    })
+}
+
+/// The file formats a param file can be read from and written to
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Prc,
+    Xml,
+    Json,
+}
+
+impl Format {
+    /// Picks the format from a file's extension. Anything unrecognized is treated as a param file,
+    /// since those come with many extensions (prc, stprm, stdat, ...)
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let extension = path
+            .as_ref()
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("xml") => Format::Xml,
+            Some("json") => Format::Json,
+            _ => Format::Prc,
+        }
+    }
+
+    /// Guesses the format from how the data starts, for data which doesn't come from a file
+    pub fn detect(bytes: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(&bytes[..bytes.len().min(64)]);
+        let text = text.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{feff}');
+        if bytes.starts_with(PRC_MAGIC) {
+            Format::Prc
+        } else if text.starts_with('<') {
+            Format::Xml
+        } else if text.starts_with(['{', '[']) {
+            Format::Json
+        } else {
+            Format::Prc
+        }
+    }
+
+    /// The extension given to files saved in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Prc => "prc",
+            Format::Xml => "xml",
+            Format::Json => "json",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "prc" => Ok(Format::Prc),
+            "xml" => Ok(Format::Xml),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+/// Reads a param file in the format matching its extension
+pub fn open<P: AsRef<Path>>(path: P) -> Result<ParamStruct, Error> {
+    let format = Format::from_path(&path);
+    from_bytes(&read(path)?, format)
+}
+
+/// Writes a param file in the format matching its extension
+pub fn save<P: AsRef<Path>>(path: P, param: &ParamStruct) -> Result<(), Error> {
+    let format = Format::from_path(&path);
+    write(path, to_bytes(param, format)?)
+}
+
+/// The bytes every param file starts with
+const PRC_MAGIC: &[u8] = b"paracobn";
+const FORMATS_HINT: &str =
+    "prickly opens param files (prc, stprm, stdat), and the xml and json files it exports";
+
+pub fn from_bytes(bytes: &[u8], format: Format) -> Result<ParamStruct, Error> {
+    let result = match format {
+        Format::Prc => read_prc(bytes),
+        Format::Xml => read_xml(&mut Cursor::new(bytes)).map_err(|err| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{:?} at byte {}", err.error, err.start),
+            )
+        }),
+        Format::Json => serde_json::from_slice(bytes).map_err(Error::from),
+    };
+    // a param file given another extension fails to parse as text, so say what it really is
+    result.map_err(|err| match format {
+        Format::Xml | Format::Json if bytes.starts_with(PRC_MAGIC) => Error::new(
+            ErrorKind::InvalidData,
+            "this is a param file, so give it the .prc extension to open it",
+        ),
+        _ => err,
+    })
+}
+
+/// Reads a param file, explaining where it went wrong if the file isn't one or is damaged
+fn read_prc(bytes: &[u8]) -> Result<ParamStruct, Error> {
+    if !bytes.starts_with(PRC_MAGIC) {
+        let reason = not_param_file(bytes);
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{}. {}", reason, FORMATS_HINT),
+        ));
+    }
+    let mut cursor = Cursor::new(bytes);
+    prc::read_stream(&mut cursor).map_err(|err| {
+        let reason = if err.kind() == ErrorKind::UnexpectedEof {
+            format!("it ends early, at byte {}", bytes.len())
+        } else {
+            format!("{} at byte {}", err, cursor.position())
+        };
+        Error::new(err.kind(), format!("the param file is damaged: {}", reason))
+    })
+}
+
+/// Describes a file which doesn't start like a param file, guessing what it is instead
+fn not_param_file(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(&bytes[..bytes.len().min(64)]);
+    let text = text.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{feff}');
+    if bytes.is_empty() {
+        String::from("the file is empty")
+    } else if text.starts_with('<') {
+        String::from("this looks like xml, so give it the .xml extension to open it")
+    } else if text.starts_with(['{', '[']) {
+        String::from("this looks like json, so give it the .json extension to open it")
+    } else {
+        let start = bytes.iter().take(PRC_MAGIC.len());
+        format!(
+            "this isn't a param file, which starts with \"paracobn\" (this one starts with {})",
+            start
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(" ")
+        )
+    }
+}
+
+pub fn to_bytes(param: &ParamStruct, format: Format) -> Result<Vec<u8>, Error> {
+    let mut writer = Cursor::new(Vec::new());
+    match format {
+        Format::Prc => prc::write_stream(&mut writer, param)?,
+        Format::Xml => write_xml(param, &mut writer)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, format!("{:?}", err)))?,
+        Format::Json => serde_json::to_writer_pretty(&mut writer, param)?,
+    }
+    writer.flush()?;
+    Ok(writer.into_inner())
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::env::current_exe;
+use std::fs::{read, write};
+use std::io::Error;
+use std::path::PathBuf;
+
+use prc::hash40::Hash40;
+
+pub const FILTERS_FILE: &str = "Filters.json";
+
+/// The most entries remembered for each field
+const MAX_ENTRIES: usize = 50;
+
+/// Which input an entry was typed into. Values are remembered per param name and type,
+/// so recalling one only offers text which that field can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HistoryField {
+    Filter,
+    /// A value edited in a struct has the struct key's name, and one in a list has none
+    Value {
+        key: Option<Hash40>,
+        ty: &'static str,
+    },
+    /// A command typed after `:`
+    Command,
+}
+
+/// The text previously submitted to each field, oldest first. Filters are kept between runs,
+/// and values and commands for as long as the editor is open
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    entries: HashMap<HistoryField, Vec<String>>,
+}
+
+impl InputHistory {
+    /// Starts with the filters entered in earlier runs
+    pub fn load() -> Self {
+        let filters: Vec<String> = filters_path()
+            .and_then(read)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        let mut entries = HashMap::new();
+        if !filters.is_empty() {
+            entries.insert(HistoryField::Filter, filters);
+        }
+        Self { entries }
+    }
+
+    /// Remembers the text as the field's latest entry, moving it there if it was already entered
+    pub fn push(&mut self, field: HistoryField, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        let entries = self.entries.entry(field).or_default();
+        entries.retain(|entry| *entry != text);
+        entries.push(text);
+        if entries.len() > MAX_ENTRIES {
+            entries.remove(0);
+        }
+        if field == HistoryField::Filter {
+            if let Err(err) = save_filters(entries) {
+                log::warn!("failed to save the filter history: {}", err);
+            }
+        }
+    }
+
+    /// The entry the given number of steps back from the latest, which is 0
+    fn get(&self, field: HistoryField, back: usize) -> Option<&str> {
+        let entries = self.entries.get(&field)?;
+        let index = entries.len().checked_sub(back + 1)?;
+        Some(entries[index].as_str())
+    }
+}
+
+/// The filter history is kept in the application directory, like the bookmarks
+fn filters_path() -> Result<PathBuf, Error> {
+    let exe = current_exe()?;
+    Ok(exe.parent().unwrap().join(FILTERS_FILE))
+}
+
+fn save_filters(filters: &[String]) -> Result<(), Error> {
+    write(filters_path()?, serde_json::to_vec_pretty(filters)?)
+}
+
+/// Where an input is while stepping through its field's history.
+/// The text from before the first step is kept, so stepping past the latest entry brings it back
+#[derive(Debug, Default)]
+pub struct Recall {
+    back: Option<usize>,
+    draft: String,
+}
+
+impl Recall {
+    /// Steps to the next older entry, returning its text, or None if there isn't one
+    pub fn older(
+        &mut self,
+        history: &InputHistory,
+        field: HistoryField,
+        current: &str,
+    ) -> Option<String> {
+        let back = self.back.map_or(0, |back| back + 1);
+        let text = history.get(field, back)?.to_owned();
+        if self.back.is_none() {
+            self.draft = current.to_owned();
+        }
+        self.back = Some(back);
+        Some(text)
+    }
+
+    /// Steps to the next newer entry, or back to the text being typed before recalling
+    pub fn newer(&mut self, history: &InputHistory, field: HistoryField) -> Option<String> {
+        match self.back? {
+            0 => {
+                self.back = None;
+                Some(std::mem::take(&mut self.draft))
+            }
+            back => {
+                self.back = Some(back - 1);
+                history.get(field, back - 1).map(str::to_owned)
+            }
+        }
+    }
+}
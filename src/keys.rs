@@ -0,0 +1,267 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer};
+use tui_components::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A key and the modifiers held with it, written like `ctrl+o`, `alt+shift+x`, or `f5`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// A key pressed on its own, or with Shift if the character needs it
+    pub const fn plain(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    pub const fn ctrl(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+
+    pub const fn alt(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::ALT,
+        }
+    }
+
+    /// Whether the key was pressed, with at least the same modifiers held
+    pub fn matches(self, key: &KeyEvent) -> bool {
+        key.code == self.code && key.modifiers.contains(self.modifiers)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+        // "ctrl++" ends with an empty part after the plus key itself
+        if parts.ends_with(&["", ""]) {
+            parts.truncate(parts.len() - 2);
+            parts.push("+");
+        }
+        let (key, held) = match parts.split_last() {
+            Some(split) => split,
+            None => return Err(format!("unknown key: {}", s)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in held {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier in {}: {}", s, modifier)),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            // terminals send letters typed with shift in uppercase
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) if modifiers.is_empty() => KeyCode::Char(c),
+            (Some(c), None) => KeyCode::Char(c.to_ascii_lowercase()),
+            _ => match key.to_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key: {}", s)),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+/// Written the way the key hints show keys, like `Ctrl+O`
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.is_empty() => write!(f, "{}", c),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Esc => write!(f, "Esc"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The keys for the editor's commands. Keys used within the explorer, inputs, and popups are fixed
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    /// Opens the file explorer for opening files
+    pub open: Key,
+    /// Starts a new file with nothing in it
+    pub new_file: Key,
+    /// Opens a file by its path in the game's archive, from the folder the game was extracted to
+    pub open_arc: Key,
+    /// Opens the file explorer for saving files
+    pub save: Key,
+    pub reload_labels: Key,
+    pub download_labels: Key,
+    /// Opens a second file to compare with, or closes it
+    pub compare: Key,
+    /// Lists how the structure of the compared file differs from the open one
+    pub structure_diff: Key,
+    /// Selects the next param edited since the file was opened or saved
+    pub next_edited: Key,
+    pub previous_edited: Key,
+    /// Switches to the next of the files opened together, like from the command line
+    pub next_file: Key,
+    pub previous_file: Key,
+    /// Removes the filter of the last column without editing it
+    pub clear_filter: Key,
+    /// Shows or hides the details pane
+    pub details: Key,
+    /// Searches for a label matching the selected hash
+    pub crack_hash: Key,
+    /// Lists the hashes without labels
+    pub unknown_hashes: Key,
+    /// Shows figures about the whole file
+    pub stats: Key,
+    /// Lists every value in the file with its full path
+    pub paths: Key,
+    /// Switches between showing the params as columns and as one tree
+    pub layout: Key,
+    /// Highlights the params which differ from the saved file, or stops highlighting them
+    pub disk_diff: Key,
+    /// Saves the selected param to a file of its own
+    pub export_subtree: Key,
+    /// Adds a file's params after the selected param
+    pub import_subtree: Key,
+    /// Opens the file again as it was last saved, dropping any edits
+    pub revert: Key,
+    /// Replaces values below the params in the last column
+    pub replace: Key,
+    /// Changes every number shown in the last column at once
+    pub transform: Key,
+    /// Edits params by their paths, written as a script
+    pub script: Key,
+    /// Lists the commands added by plugins
+    pub palette: Key,
+    /// Starts typing a vim command, like `:w` or `:%s/find/replace/`
+    pub command_line: Key,
+    /// Copies the path of the selected param, like `fighter_param_table[3].walk_speed`
+    pub copy_path: Key,
+    /// Copies the command which opens the file at the selected param, like
+    /// `prickly fighter_param.prc --at 'fighter_param_table[3]'`
+    pub copy_link: Key,
+    /// Lists problems like hashes with more than one label
+    pub warnings: Key,
+    pub undo: Key,
+    pub redo: Key,
+    /// Lists the edits which can be undone or redone
+    pub history: Key,
+    /// Lists the named snapshots of the file taken in this session
+    pub snapshots: Key,
+    /// Attaches a note to the selected param, kept in a file next to the open one
+    pub note: Key,
+    /// Bookmarks the selected param, or removes its bookmark
+    pub bookmark: Key,
+    /// Selects the next bookmarked param
+    pub next_bookmark: Key,
+    pub previous_bookmark: Key,
+    pub hash_display: Key,
+    /// Shows or hides a column with the hex of struct keys and hash values
+    pub hex_column: Key,
+    /// Shows or hides a column with each param's position in its list or struct
+    pub row_numbers: Key,
+    pub float_decimals: Key,
+    pub float_notation: Key,
+    /// Stops the editor and goes back to the shell, which resumes it with `fg`. Unix only
+    pub suspend: Key,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            open: Key::ctrl('o'),
+            new_file: Key::ctrl('n'),
+            open_arc: Key::alt('o'),
+            save: Key::ctrl('s'),
+            reload_labels: Key::ctrl('l'),
+            download_labels: Key::ctrl('d'),
+            compare: Key::ctrl('k'),
+            structure_diff: Key::alt('k'),
+            next_edited: Key::alt('.'),
+            previous_edited: Key::alt(','),
+            next_file: Key::alt('n'),
+            previous_file: Key::alt('b'),
+            clear_filter: Key::alt('f'),
+            details: Key::ctrl('p'),
+            crack_hash: Key::ctrl('b'),
+            unknown_hashes: Key::ctrl('u'),
+            stats: Key::ctrl('a'),
+            paths: Key::ctrl('g'),
+            layout: Key::ctrl('w'),
+            disk_diff: Key::alt('d'),
+            export_subtree: Key::alt('e'),
+            import_subtree: Key::alt('i'),
+            revert: Key::alt('r'),
+            replace: Key::ctrl('r'),
+            transform: Key::ctrl('t'),
+            script: Key::alt('s'),
+            palette: Key::alt('p'),
+            command_line: Key::plain(':'),
+            copy_path: Key::alt('c'),
+            copy_link: Key::alt('l'),
+            warnings: Key::alt('w'),
+            undo: Key::ctrl('z'),
+            redo: Key::ctrl('y'),
+            history: Key::alt('h'),
+            snapshots: Key::alt('t'),
+            note: Key::alt('a'),
+            bookmark: Key::alt('m'),
+            next_bookmark: Key::alt('j'),
+            previous_bookmark: Key::alt('u'),
+            hash_display: Key::ctrl('x'),
+            hex_column: Key::alt('x'),
+            row_numbers: Key::alt('v'),
+            float_decimals: Key::ctrl('f'),
+            float_notation: Key::ctrl('e'),
+            suspend: Key::alt('z'),
+        }
+    }
+}
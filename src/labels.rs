@@ -0,0 +1,177 @@
+use std::collections::{BTreeSet, HashMap};
+use std::env::current_exe;
+use std::fs::File;
+use std::io::{copy, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use prc::hash40::label_map::LabelMap;
+use prc::hash40::Hash40;
+
+pub const LABELS_FILE: &str = "ParamLabels.csv";
+pub const LABELS_URL: &str =
+    "https://raw.githubusercontent.com/ultimate-research/param-labels/master/ParamLabels.csv";
+/// How long a download of the labels can take before it's given up on
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The label of each hash shown so far, so each one is looked up in the label map and
+/// allocated only once, however many rows show it
+static LABEL_TEXTS: RwLock<Option<HashMap<Hash40, Arc<str>>>> = RwLock::new(None);
+
+/// How many times labels have been loaded or added, to tell whether text built from them is
+/// out of date
+static LABEL_CHANGES: AtomicUsize = AtomicUsize::new(0);
+
+/// The label of a hash, or its hex if it has none, shared with every other place showing it
+pub fn label_text(hash: Hash40) -> Arc<str> {
+    let known = match LABEL_TEXTS.read() {
+        Ok(texts) => texts.as_ref().and_then(|texts| texts.get(&hash).cloned()),
+        Err(err) => err
+            .into_inner()
+            .as_ref()
+            .and_then(|texts| texts.get(&hash).cloned()),
+    };
+    if let Some(text) = known {
+        return text;
+    }
+    let text = Arc::<str>::from(hash.to_label());
+    if let Ok(mut texts) = LABEL_TEXTS.write() {
+        texts
+            .get_or_insert_with(HashMap::new)
+            .insert(hash, text.clone());
+    }
+    text
+}
+
+/// Forgets the labels shown so far, once the label map has changed
+pub fn labels_changed() {
+    if let Ok(mut texts) = LABEL_TEXTS.write() {
+        *texts = None;
+    }
+    LABEL_CHANGES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// How many times labels have been loaded or added
+pub fn label_changes() -> usize {
+    LABEL_CHANGES.load(Ordering::Relaxed)
+}
+
+/// A hash given more than one label in the labels file. Only one of them can be shown
+#[derive(Debug, Clone)]
+pub struct Collision {
+    pub hash: Hash40,
+    /// Every label given to the hash, in the order they're listed
+    pub labels: Vec<String>,
+}
+
+/// Finds the hashes given different labels, in the order they're first listed
+pub fn find_collisions(labels: &[(Hash40, String)]) -> Vec<Collision> {
+    let mut by_hash = HashMap::<Hash40, Vec<&str>>::new();
+    let mut order = vec![];
+    for (hash, label) in labels {
+        let entry = by_hash.entry(*hash).or_insert_with(|| {
+            order.push(*hash);
+            vec![]
+        });
+        if !entry.contains(&label.as_str()) {
+            entry.push(label);
+        }
+    }
+    order
+        .into_iter()
+        .filter_map(|hash| {
+            let labels = &by_hash[&hash];
+            (labels.len() > 1).then(|| Collision {
+                hash,
+                labels: labels.iter().map(|label| label.to_string()).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Reads the param labels file into the global label map, replacing any previous labels.
+/// Paths from the command line or the config are read alone, with the labels of every file
+/// merged in order. Otherwise the current directory is checked first, then the application
+/// directory.
+///
+/// Returns the hashes given more than one label, or `None` if the labels couldn't be loaded.
+/// On failure, the existing labels are kept.
+pub fn load_labels(
+    sorted_labels: &Mutex<BTreeSet<String>>,
+    paths: &[PathBuf],
+) -> Option<Vec<Collision>> {
+    let label_arc = Hash40::label_map();
+    let label_map = label_arc.lock().ok();
+    let sorted_lock = sorted_labels.lock().ok();
+    let labels = if paths.is_empty() {
+        LabelMap::read_custom_labels(LABELS_FILE).ok().or_else(|| {
+            current_exe().ok().and_then(|path| {
+                LabelMap::read_custom_labels(path.parent().unwrap().join(LABELS_FILE)).ok()
+            })
+        })
+    } else {
+        paths
+            .iter()
+            .map(|path| {
+                LabelMap::read_custom_labels(path).map_err(|err| {
+                    log::warn!("failed to read {}: {:?}", path.display(), err);
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .map(|files| files.into_iter().flatten().collect::<Vec<_>>())
+    };
+    let loaded = labels.zip(label_map).zip(sorted_lock);
+    if let Some(((labels, mut label_map), mut sorted)) = loaded {
+        *sorted = labels.iter().map(|(_, str)| str.to_owned()).collect();
+        log::info!("loaded {} param labels", sorted.len());
+        let collisions = find_collisions(&labels);
+        if !collisions.is_empty() {
+            log::warn!("{} hashes have more than one label", collisions.len());
+        }
+        label_map.clear();
+        label_map.strict = true;
+        label_map.add_custom_labels(labels.into_iter());
+        drop(label_map);
+        labels_changed();
+        Some(collisions)
+    } else {
+        log::warn!("failed to load the param labels");
+        None
+    }
+}
+
+/// Downloads the latest community labels, without touching any labels file. This blocks until
+/// the download is done, so it's meant for a background task
+pub fn download_labels() -> Result<Vec<u8>, Error> {
+    let agent = ureq::AgentBuilder::new().timeout(DOWNLOAD_TIMEOUT).build();
+    let response = agent.get(LABELS_URL).call().map_err(Error::other)?;
+    // download everything before touching the existing file, so a failed request keeps it intact
+    let mut contents = Vec::new();
+    copy(&mut response.into_reader(), &mut contents)?;
+    Ok(contents)
+}
+
+/// Where downloaded labels are written when the config doesn't name a labels file
+pub fn default_labels_path() -> Result<PathBuf, Error> {
+    Ok(current_exe()?.parent().unwrap().join(LABELS_FILE))
+}
+
+/// Writes downloaded labels to the path, then reloads the labels from the given paths the same
+/// way as on startup. Returns the hashes given more than one label
+pub fn install_labels(
+    sorted_labels: &Mutex<BTreeSet<String>>,
+    path: &Path,
+    paths: &[PathBuf],
+    contents: &[u8],
+) -> Result<Vec<Collision>, Error> {
+    copy(&mut &contents[..], &mut File::create(path)?)?;
+    load_labels(sorted_labels, paths).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            "the downloaded labels could not be loaded",
+        )
+    })
+}
@@ -0,0 +1,79 @@
+//! The prc file editor behind the `prickly` application, for embedding the editor in other
+//! tools or reusing its param logic without the terminal interface.
+//!
+//! - [`components`] has the editor's widgets: [`Root`](components::root::Root) is the whole
+//!   application, and [`Param`](components::param::Param) is the param tree on its own
+//! - [`format`](mod@format) reads and writes param files as prc, xml, or json
+//! - [`path`] finds params by paths like `fighter_param_table[3].walk_speed`,
+//!   and [`value`] parses and formats their values
+//! - [`diff`] lists the differences between two files
+//! - [`plugin`] adds commands and export formats to the editor
+//!
+//! Running the editor on a file:
+//!
+//! ```no_run
+//! use std::collections::BTreeSet;
+//! use std::sync::{Arc, Mutex};
+//!
+//! use prickly::components::root::Root;
+//! use prickly::theme::Theme;
+//!
+//! let labels = Arc::new(Mutex::new(BTreeSet::new()));
+//! prickly::labels::load_labels(&labels, &[]);
+//! let mut app = Root::new(labels, Theme::default(), Default::default(), Default::default());
+//! app.open("fighter_param.prc".into());
+//! prickly::terminal::run(&mut app, None)?;
+//! if let Err(message) = app.wait_for_save() {
+//!     eprintln!("Failed to save {}", message);
+//! }
+//! # Ok::<(), prickly::error::AppError>(())
+//! ```
+//!
+//! Comparing two files without the editor:
+//!
+//! ```no_run
+//! let a = prickly::format::open("a.prc")?;
+//! let b = prickly::format::open("b.prc")?;
+//! for difference in prickly::diff::diff(&a, &b) {
+//!     println!("{}", difference.path);
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+mod bookmarks;
+mod cracker;
+mod task;
+
+pub mod components;
+pub mod config;
+pub mod diff;
+pub mod enums;
+pub mod error;
+pub mod expr;
+pub mod format;
+pub mod history;
+pub mod keys;
+pub mod labels;
+pub mod path;
+pub mod plugin;
+pub mod replace;
+pub mod report;
+pub mod schema;
+pub mod script;
+pub mod session;
+pub mod settings;
+pub mod shared;
+pub mod sidecar;
+pub mod sort;
+pub mod stats;
+pub mod subtree;
+pub mod terminal;
+pub mod theme;
+pub mod transform;
+pub mod undo;
+pub mod utils;
+pub mod value;
+
+/// The versions of these the editor is built with, for embedding it
+pub use prc;
+pub use tui_components;
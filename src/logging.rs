@@ -0,0 +1,90 @@
+use std::env::current_exe;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+pub const LOG_FILE: &str = "prickly.log";
+
+/// Writes log records to a file, since stdout is taken by the editor.
+/// Each line is the time since startup, the level, where it came from, and the message
+struct FileLogger {
+    file: Mutex<File>,
+    start: Instant,
+    level: LevelFilter,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Ok(mut file) = self.file.lock() {
+            let elapsed = self.start.elapsed();
+            writeln!(
+                file,
+                "[{:>4}.{:03}] {:<5} {}: {}",
+                elapsed.as_secs(),
+                elapsed.subsec_millis(),
+                record.level(),
+                record.target(),
+                record.args()
+            )
+            .unwrap_or_default();
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            file.flush().unwrap_or_default();
+        }
+    }
+}
+
+/// The log file is kept in the application directory, next to the session
+fn log_path() -> Option<PathBuf> {
+    let exe = current_exe().ok()?;
+    Some(exe.parent()?.join(LOG_FILE))
+}
+
+/// Starts appending to the log file. Each `--verbose` flag logs more detail:
+/// once for opens, saves, and errors, twice for every edit, and three times for everything.
+/// Without the flag nothing is logged
+pub fn init(verbosity: u8) -> Result<(), String> {
+    let level = match verbosity {
+        0 => return Ok(()),
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    let path = log_path().ok_or("Failed to find the application directory for the log")?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|err| format!("Failed to open {}: {}", path.display(), err))?;
+    let logger = FileLogger {
+        file: Mutex::new(file),
+        start: Instant::now(),
+        level,
+    };
+    log::set_boxed_logger(Box::new(logger)).map_err(|err| err.to_string())?;
+    log::set_max_level(level);
+    let started = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    log::info!(
+        "prickly {} started at {} (unix time), logging {}",
+        env!("CARGO_PKG_VERSION"),
+        started,
+        level
+    );
+    Ok(())
+}
@@ -1,47 +1,142 @@
 use std::collections::BTreeSet;
-use std::env::current_exe;
+use std::io::{stdin, Error, ErrorKind, IsTerminal, Read};
+use std::path::PathBuf;
+use std::process::exit;
 use std::sync::{Arc, Mutex};
 
-use prc::hash40::label_map::LabelMap;
-use prc::hash40::Hash40;
-use prc::open;
+use prc::ParamStruct;
 use structopt::StructOpt;
 
-use components::root::Root;
+use prickly::components::root::Root;
+use prickly::format::{from_bytes, Format};
+use prickly::{config, enums, error, labels, plugin, schema, session, terminal, theme};
 
 mod args;
-mod error;
-
-pub mod components;
-pub mod utils;
+mod commands;
+mod logging;
 
 fn main() -> Result<(), error::AppError> {
     let args = args::Args::from_args();
+    let float_format = args.float_format();
+    let log_error = logging::init(args.verbose).err();
+    let (config, config_error) = match config::load_config(args.config.as_deref()) {
+        Ok(config) => (config, None),
+        Err(err) => (Default::default(), Some(err)),
+    };
+
+    let sorted_labels = Arc::new(Mutex::new(BTreeSet::new()));
+    // labels files given on the command line are read instead of the one from the config
+    let label_files = if args.labels.is_empty() {
+        config.labels.iter().cloned().collect()
+    } else {
+        args.labels.clone()
+    };
+    let collisions = labels::load_labels(&sorted_labels, &label_files);
+
+    if let Some(command) = args.command {
+        for err in log_error.iter().chain(&config_error) {
+            eprintln!("{}", err);
+        }
+        log::info!("running {:?}", command);
+        match commands::run(
+            command,
+            float_format,
+            config.export_format,
+            config.schema.as_deref(),
+        ) {
+            Ok(code) => exit(code),
+            Err(err) => {
+                log::error!("{}", err);
+                eprintln!("{}", err);
+                exit(2);
+            }
+        }
+    }
 
-    let param = args.file.as_ref().map(|path| open(path).unwrap().into());
-
-    let mut sorted_labels = BTreeSet::new();
-    let label_arc = Hash40::label_map();
-    let label_map = label_arc.lock().ok();
-    let labels = LabelMap::read_custom_labels("ParamLabels.csv")
-        .ok()
-        .or_else(|| {
-            current_exe().ok().and_then(|path| {
-                LabelMap::read_custom_labels(path.parent().unwrap().join("ParamLabels.csv")).ok()
-            })
-        });
-    if let Some((labels, mut label_map)) = labels.zip(label_map) {
-        sorted_labels = labels.iter().map(|(_, str)| str.to_owned()).collect();
-        label_map.strict = true;
-        label_map.add_custom_labels(labels.into_iter());
-    }
-
-    let mut app = Root::new(param, Arc::new(Mutex::new(sorted_labels)));
-
-    let title = match &args.file {
-        Some(path) => format!("prickly - {}", path),
-        None => "prickly - prc file editor".to_string(),
+    let (theme, theme_error) = match config.theme.map_or_else(theme::load_theme, Ok) {
+        Ok(theme) => (theme, None),
+        Err(err) => (Default::default(), Some(err)),
+    };
+    let (schema, schema_error) = match schema::load_schema(config.schema.as_deref()) {
+        Ok(schema) => (schema, None),
+        Err(err) => (None, Some(err)),
+    };
+    let enums_error = match enums::load_enums(config.enums.as_deref()) {
+        Ok(names) => {
+            if let Some(names) = names {
+                names.install();
+            }
+            None
+        }
+        Err(err) => Some(err),
     };
-    tui_components::run(&mut app, Some(title))?;
+    let mut app = Root::new(sorted_labels, theme, float_format, config);
+    app.set_label_files(label_files);
+    app.set_label_collisions(collisions.unwrap_or_default());
+    if let Some(folder) = plugin::load_script_folder() {
+        app.add_plugin(Arc::new(folder));
+    }
+    app.set_schema(schema);
+    if let Some(err) = config_error
+        .or(theme_error)
+        .or(schema_error)
+        .or(enums_error)
+        .or(log_error)
+    {
+        log::warn!("{}", err);
+        app.show_error(err);
+    }
+    // `-`, or no file while something is piped in, reads the file from stdin
+    let piped = match args.files.as_slice() {
+        [file] if file == "-" => Some(read_stdin().and_then(|prc| {
+            prc.ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "nothing was piped in"))
+        })),
+        [] if !stdin().is_terminal() => read_stdin().transpose(),
+        _ => None,
+    };
+    match (piped, args.files.is_empty()) {
+        (Some(Ok(prc)), _) => app.open_unsaved(prc, "stdin"),
+        (Some(Err(err)), _) => {
+            log::error!("failed to read stdin: {}", err);
+            app.show_error(format!("Failed to read stdin: {}", err));
+        }
+        (None, false) => {
+            let files = args.files.iter().map(PathBuf::from).collect();
+            app.open_all(files, args.at.clone());
+        }
+        (None, true) => {
+            if let Some(session) = session::load_session() {
+                app.offer_session(session);
+            }
+        }
+    }
+    if let Some(path) = &args.compare {
+        app.open_compare(path.into());
+    }
+
+    // the app keeps the title up to date with the open file
+    terminal::run(&mut app, None)?;
+    // the app may have exited before a save it started could fail, leaving nowhere to show it
+    let saved = app.wait_for_save();
+    if let Some(session) = app.session() {
+        if let Err(err) = session::save_session(&session) {
+            log::warn!("failed to save the session: {}", err);
+        }
+    }
+    log::info!("exiting");
+    if let Err(message) = saved {
+        eprintln!("Failed to save {}", message);
+        exit(1);
+    }
     Ok(())
 }
+
+/// Reads a whole file piped in, in whichever format it looks like, or nothing if stdin is empty
+fn read_stdin() -> Result<Option<ParamStruct>, Error> {
+    let mut bytes = vec![];
+    stdin().read_to_end(&mut bytes)?;
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    from_bytes(&bytes, Format::detect(&bytes)).map(Some)
+}
@@ -0,0 +1,292 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use prc::hash40::Hash40;
+use prc::{ParamKind, ParamStruct};
+use rayon::prelude::*;
+
+use crate::value::parse_hash;
+
+/// The location of a param within a file, written like `fighter_param_table[3].walk_speed`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ParamPath(pub Vec<PathSegment>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(Hash40),
+    Index(usize),
+}
+
+impl ParamPath {
+    /// The key of the param at this path, if its parent is a struct
+    pub fn key(&self) -> Option<Hash40> {
+        match self.0.last() {
+            Some(PathSegment::Key(hash)) => Some(*hash),
+            _ => None,
+        }
+    }
+
+    /// Finds the param at this path within the root struct
+    pub fn get<'a>(&self, root: &'a ParamStruct) -> Option<&'a ParamKind> {
+        let (first, rest) = self.0.split_first()?;
+        let mut param = match first {
+            PathSegment::Key(hash) => struct_child(root, *hash)?,
+            PathSegment::Index(_) => return None,
+        };
+        for segment in rest {
+            param = match (param, segment) {
+                (ParamKind::Struct(str), PathSegment::Key(hash)) => struct_child(str, *hash)?,
+                (ParamKind::List(list), PathSegment::Index(index)) => list.0.get(*index)?,
+                _ => return None,
+            };
+        }
+        Some(param)
+    }
+
+    /// Finds the param at this path within the root struct, for editing
+    pub fn get_mut<'a>(&self, root: &'a mut ParamStruct) -> Option<&'a mut ParamKind> {
+        let (first, rest) = self.0.split_first()?;
+        let mut param = match first {
+            PathSegment::Key(hash) => struct_child_mut(root, *hash)?,
+            PathSegment::Index(_) => return None,
+        };
+        for segment in rest {
+            param = match (param, segment) {
+                (ParamKind::Struct(str), PathSegment::Key(hash)) => struct_child_mut(str, *hash)?,
+                (ParamKind::List(list), PathSegment::Index(index)) => list.0.get_mut(*index)?,
+                _ => return None,
+            };
+        }
+        Some(param)
+    }
+}
+
+fn struct_child(param: &ParamStruct, hash: Hash40) -> Option<&ParamKind> {
+    param
+        .0
+        .iter()
+        .find(|(key, _)| *key == hash)
+        .map(|(_, child)| child)
+}
+
+fn struct_child_mut(param: &mut ParamStruct, hash: Hash40) -> Option<&mut ParamKind> {
+    param
+        .0
+        .iter_mut()
+        .find(|(key, _)| *key == hash)
+        .map(|(_, child)| child)
+}
+
+impl ParamPath {
+    /// Each segment as it's written in the path, like `fighter_param_table`, `[3]`, or `.walk_speed`
+    pub fn segment_strings(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| match segment {
+                PathSegment::Key(hash) if i == 0 => hash.to_string(),
+                PathSegment::Key(hash) => format!(".{}", hash),
+                PathSegment::Index(index) => format!("[{}]", index),
+            })
+            .collect()
+    }
+}
+
+impl Display for ParamPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.segment_strings().concat())
+    }
+}
+
+impl FromStr for ParamPath {
+    type Err = String;
+
+    /// Reads a path like `fighter_param_table[3].walk_speed`, where keys are labels or hex hashes
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(String::from("the path is empty"));
+        }
+        let mut segments = vec![];
+        for part in s.split('.') {
+            let (key, mut indices) = part.split_at(part.find('[').unwrap_or(part.len()));
+            if key.is_empty() {
+                return Err(format!("missing key in path: {}", s));
+            }
+            segments.push(PathSegment::Key(parse_hash(key)?));
+            while !indices.is_empty() {
+                let end = indices
+                    .find(']')
+                    .filter(|_| indices.starts_with('['))
+                    .ok_or_else(|| format!("unmatched brackets in path: {}", s))?;
+                let index = indices[1..end]
+                    .parse()
+                    .map_err(|_| format!("invalid index in path: {}", s))?;
+                segments.push(PathSegment::Index(index));
+                indices = &indices[end + 1..];
+            }
+        }
+        Ok(ParamPath(segments))
+    }
+}
+
+/// Visits every param below the root depth-first, along with its path
+pub fn visit<F: FnMut(&ParamPath, &ParamKind)>(root: &ParamStruct, mut f: F) {
+    let mut path = ParamPath::default();
+    visit_struct(root, &mut path, &mut f);
+}
+
+fn visit_struct<F: FnMut(&ParamPath, &ParamKind)>(
+    param: &ParamStruct,
+    path: &mut ParamPath,
+    f: &mut F,
+) {
+    for (hash, child) in param.0.iter() {
+        path.0.push(PathSegment::Key(*hash));
+        visit_param(child, path, f);
+        path.0.pop();
+    }
+}
+
+fn visit_param<F: FnMut(&ParamPath, &ParamKind)>(
+    param: &ParamKind,
+    path: &mut ParamPath,
+    f: &mut F,
+) {
+    f(path, param);
+    match param {
+        ParamKind::Struct(str) => visit_struct(str, path, f),
+        ParamKind::List(list) => {
+            for (index, child) in list.0.iter().enumerate() {
+                path.0.push(PathSegment::Index(index));
+                visit_param(child, path, f);
+                path.0.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Lists and structs with fewer children than this are visited, or filtered, on the thread which
+/// reached them
+pub const PARALLEL_MIN_CHILDREN: usize = 64;
+
+/// Visits every param below the root like `visit`, collecting what the function finds at each
+/// one in the same order. The children of large lists and structs are visited on several
+/// threads at once, so the function is called from any of them
+pub fn visit_par<T, F>(root: &ParamStruct, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&ParamPath, &ParamKind) -> Option<T> + Sync,
+{
+    let mut found = vec![];
+    let children = root
+        .0
+        .iter()
+        .map(|(hash, child)| (PathSegment::Key(*hash), child));
+    collect_children(children, &mut ParamPath::default(), &f, &mut found);
+    found
+}
+
+fn collect_param<T, F>(param: &ParamKind, path: &mut ParamPath, f: &F, found: &mut Vec<T>)
+where
+    T: Send,
+    F: Fn(&ParamPath, &ParamKind) -> Option<T> + Sync,
+{
+    found.extend(f(path, param));
+    match param {
+        ParamKind::Struct(str) => {
+            let children = str
+                .0
+                .iter()
+                .map(|(hash, child)| (PathSegment::Key(*hash), child));
+            collect_children(children, path, f, found);
+        }
+        ParamKind::List(list) => {
+            let children = (list.0.iter().enumerate())
+                .map(|(index, child)| (PathSegment::Index(index), child));
+            collect_children(children, path, f, found);
+        }
+        _ => {}
+    }
+}
+
+fn collect_children<'a, T, F, I>(children: I, path: &mut ParamPath, f: &F, found: &mut Vec<T>)
+where
+    T: Send,
+    F: Fn(&ParamPath, &ParamKind) -> Option<T> + Sync,
+    I: ExactSizeIterator<Item = (PathSegment, &'a ParamKind)>,
+{
+    if children.len() < PARALLEL_MIN_CHILDREN {
+        for (segment, child) in children {
+            path.0.push(segment);
+            collect_param(child, path, f, found);
+            path.0.pop();
+        }
+        return;
+    }
+    let parent = &*path;
+    let parts = children
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(segment, child)| {
+            let mut path = parent.clone();
+            path.0.push(segment);
+            let mut part = vec![];
+            collect_param(child, &mut path, f, &mut part);
+            part
+        })
+        .collect::<Vec<_>>();
+    found.extend(parts.into_iter().flatten());
+}
+
+#[cfg(test)]
+mod tests {
+    use prc::hash40::hash40;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let path = "fighter_param_table[3].walk_speed".parse::<ParamPath>();
+        assert_eq!(
+            path,
+            Ok(ParamPath(vec![
+                PathSegment::Key(hash40("fighter_param_table")),
+                PathSegment::Index(3),
+                PathSegment::Key(hash40("walk_speed")),
+            ]))
+        );
+        let path = "0x0a862e7d52[0][12]".parse::<ParamPath>();
+        assert_eq!(
+            path,
+            Ok(ParamPath(vec![
+                PathSegment::Key(Hash40(0x0a862e7d52)),
+                PathSegment::Index(0),
+                PathSegment::Index(12),
+            ]))
+        );
+    }
+
+    #[test]
+    fn display_reads_back() {
+        let path = ParamPath(vec![
+            PathSegment::Key(Hash40(0x0a862e7d52)),
+            PathSegment::Index(2),
+            PathSegment::Key(Hash40(0x1234567890)),
+        ]);
+        assert_eq!(path.to_string().parse(), Ok(path));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |s: &str| s.parse::<ParamPath>().unwrap_err();
+        assert_eq!(error(""), "the path is empty");
+        assert_eq!(error("a..b"), "missing key in path: a..b");
+        assert_eq!(error("[0]"), "missing key in path: [0]");
+        assert_eq!(error("a[0"), "unmatched brackets in path: a[0");
+        assert_eq!(error("a[0]x"), "unmatched brackets in path: a[0]x");
+        assert_eq!(error("a[x]"), "invalid index in path: a[x]");
+        assert_eq!(error("a[-1]"), "invalid index in path: a[-1]");
+        assert_eq!(error("0xzz"), "invalid hash: 0xzz");
+    }
+}
@@ -0,0 +1,208 @@
+use std::env::current_exe;
+use std::fmt::Debug;
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use prc::{ParamKind, ParamStruct};
+
+use crate::path::visit;
+use crate::replace::Replacement;
+use crate::script::{run_script, Statement};
+use crate::value::format_value;
+
+pub const PLUGINS_DIR: &str = "Plugins";
+
+/// Something which extends the editor: commands run from the command palette, and formats the
+/// open file can be exported to. Tools embedding the editor register their own with
+/// [`Root::add_plugin`](crate::components::root::Root::add_plugin)
+pub trait Plugin: Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    fn commands(&self) -> Vec<Arc<dyn PluginCommand>> {
+        vec![]
+    }
+
+    fn exporters(&self) -> Vec<Arc<dyn Exporter>> {
+        vec![]
+    }
+}
+
+/// A command in the palette which edits the open file
+pub trait PluginCommand: Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    /// What the command does, shown next to its name in the palette
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// The values the command changes in the file, made as one edit which can be undone
+    fn run(&self, root: &ParamStruct) -> Result<Vec<Replacement>, String>;
+}
+
+/// A format the open file can be exported to, besides the ones it can be saved as
+pub trait Exporter: Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    /// The extension given to exported files which are named without one
+    fn extension(&self) -> &str;
+
+    fn export(&self, root: &ParamStruct) -> Result<Vec<u8>, String>;
+}
+
+/// The plugins which come with the editor
+#[derive(Debug)]
+pub struct Builtin;
+
+impl Plugin for Builtin {
+    fn name(&self) -> &str {
+        "prickly"
+    }
+
+    fn exporters(&self) -> Vec<Arc<dyn Exporter>> {
+        vec![Arc::new(ValueList)]
+    }
+}
+
+/// Every value in the file on its own line, like `fighter_param_table[3].walk_speed = 1.2`,
+/// which can be run again as a script
+#[derive(Debug)]
+pub struct ValueList;
+
+impl Exporter for ValueList {
+    fn name(&self) -> &str {
+        "value list"
+    }
+
+    fn extension(&self) -> &str {
+        "txt"
+    }
+
+    fn export(&self, root: &ParamStruct) -> Result<Vec<u8>, String> {
+        let mut text = String::new();
+        visit(root, |path, param| {
+            if !matches!(param, ParamKind::List(_) | ParamKind::Struct(_)) {
+                text.push_str(&format!("{} = {}\n", path, format_value(param)));
+            }
+        });
+        Ok(text.into_bytes())
+    }
+}
+
+/// A script in the plugins folder, run as a command named after the file
+#[derive(Debug)]
+pub struct ScriptCommand {
+    name: String,
+    /// The script's first comment, if it starts with one
+    description: String,
+    /// The edits in the script, or why it couldn't be read
+    statements: Result<Vec<Statement>, String>,
+}
+
+impl ScriptCommand {
+    /// Reads a script written like the ones for `prickly apply`: an edit on each line, and
+    /// lines starting with `#` ignored
+    pub fn new<T: Into<String>>(name: T, script: &str) -> Self {
+        let description = script
+            .lines()
+            .next()
+            .and_then(|line| line.trim().strip_prefix('#'))
+            .unwrap_or_default()
+            .trim()
+            .to_owned();
+        let statements = script
+            .lines()
+            .enumerate()
+            .map(|(number, line)| (number, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(number, line)| {
+                line.parse()
+                    .map_err(|msg| format!("line {}: {}", number + 1, msg))
+            })
+            .collect();
+        Self {
+            name: name.into(),
+            description,
+            statements,
+        }
+    }
+}
+
+impl PluginCommand for ScriptCommand {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn run(&self, root: &ParamStruct) -> Result<Vec<Replacement>, String> {
+        run_script(root, self.statements.as_ref()?)
+    }
+}
+
+/// The scripts in the plugins folder, each a command
+#[derive(Debug)]
+pub struct ScriptFolder {
+    commands: Vec<Arc<dyn PluginCommand>>,
+}
+
+impl ScriptFolder {
+    /// Reads every script in the folder, in order of their names
+    pub fn load<P: AsRef<Path>>(dir: P) -> Self {
+        let mut paths = read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.is_file())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        paths.sort();
+        let commands = paths
+            .iter()
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_string_lossy().into_owned();
+                let command: Arc<dyn PluginCommand> = match read_to_string(path) {
+                    Ok(script) => Arc::new(ScriptCommand::new(name, &script)),
+                    Err(err) => {
+                        log::warn!("failed to read the plugin {}: {}", path.display(), err);
+                        return None;
+                    }
+                };
+                Some(command)
+            })
+            .collect();
+        Self { commands }
+    }
+}
+
+impl Plugin for ScriptFolder {
+    fn name(&self) -> &str {
+        PLUGINS_DIR
+    }
+
+    fn commands(&self) -> Vec<Arc<dyn PluginCommand>> {
+        self.commands.clone()
+    }
+}
+
+/// The plugins folder is kept in the application directory, like the labels
+pub fn plugins_dir() -> Option<PathBuf> {
+    let exe = current_exe().ok()?;
+    Some(exe.parent()?.join(PLUGINS_DIR))
+}
+
+/// The scripts in the plugins folder, if there is one
+pub fn load_script_folder() -> Option<ScriptFolder> {
+    let dir = plugins_dir().filter(|dir| dir.is_dir())?;
+    let folder = ScriptFolder::load(&dir);
+    log::info!(
+        "loaded {} plugin script(s) from {}",
+        folder.commands.len(),
+        dir.display()
+    );
+    Some(folder)
+}
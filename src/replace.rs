@@ -0,0 +1,113 @@
+use prc::{ParamKind, ParamStruct};
+use regex::Regex;
+
+use crate::path::{visit, visit_par, ParamPath};
+use crate::task::Progress;
+use crate::value::{format_value, parse_value};
+
+/// How the text to find is matched against each value, as it's written by `format_value`
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// The whole value has to be the text, and is replaced as a whole
+    Exact(String),
+    /// Every match within the value is replaced, and the replacement can use groups like `$1`
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn new(text: &str, regex: bool) -> Result<Self, String> {
+        if regex {
+            Regex::new(text)
+                .map(Pattern::Regex)
+                .map_err(|_| String::from("(invalid regex)"))
+        } else {
+            Ok(Pattern::Exact(text.to_owned()))
+        }
+    }
+
+    /// The value with the replacement applied, or None if the pattern doesn't match it
+    fn replace(&self, value: &str, with: &str) -> Option<String> {
+        match self {
+            Pattern::Exact(text) if text == value => Some(with.to_owned()),
+            Pattern::Exact(_) => None,
+            Pattern::Regex(regex) if regex.is_match(value) => {
+                Some(regex.replace_all(value, with).into_owned())
+            }
+            Pattern::Regex(_) => None,
+        }
+    }
+}
+
+/// A value the pattern matched, and what it becomes
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    pub path: ParamPath,
+    pub old: ParamKind,
+    /// The new value, or why the replaced text doesn't fit the param's type
+    pub new: Result<ParamKind, String>,
+}
+
+/// Finds the string, hash, and number values below the param at the base path which the pattern
+/// changes, in file order. An empty base path searches the whole file
+pub fn find_replacements(
+    root: &ParamStruct,
+    base: &ParamPath,
+    pattern: &Pattern,
+    with: &str,
+) -> Vec<Replacement> {
+    find_replacements_in(root, base, pattern, with, &Progress::default()).unwrap_or_default()
+}
+
+/// Finds the replacements like `find_replacements`, counting each param checked. Large lists
+/// and structs are searched on several threads. Returns None if the search was cancelled
+/// before it finished
+pub(crate) fn find_replacements_in(
+    root: &ParamStruct,
+    base: &ParamPath,
+    pattern: &Pattern,
+    with: &str,
+    progress: &Progress,
+) -> Option<Vec<Replacement>> {
+    let mut total = 0;
+    visit(root, |_, _| total += 1);
+    progress.set_total(total);
+
+    let replacements = visit_par(root, |path, param| {
+        if !progress.step() || !path.0.starts_with(&base.0) {
+            return None;
+        }
+        if matches!(
+            param,
+            ParamKind::Bool(_) | ParamKind::List(_) | ParamKind::Struct(_)
+        ) {
+            return None;
+        }
+        let text = pattern.replace(&format_value(param), with)?;
+        let new = parse_value(param, &text);
+        if new.as_ref() == Ok(param) {
+            return None;
+        }
+        Some(Replacement {
+            path: path.clone(),
+            old: param.clone(),
+            new,
+        })
+    });
+    if progress.is_cancelled() {
+        None
+    } else {
+        Some(replacements)
+    }
+}
+
+/// Sets each replacement which fits its param, returning how many were set
+pub fn apply_replacements(root: &mut ParamStruct, replacements: &[Replacement]) -> usize {
+    let mut applied = 0;
+    for replacement in replacements {
+        if let (Ok(new), Some(param)) = (&replacement.new, replacement.path.get_mut(root)) {
+            *param = new.clone();
+            applied += 1;
+        }
+    }
+    applied
+}
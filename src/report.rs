@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+
+use prc::hash40::Hash40;
+use prc::{ParamKind, ParamStruct};
+
+use crate::path::{visit, ParamPath};
+
+pub const UNKNOWN_HASHES_FILE: &str = "UnknownHashes.csv";
+
+/// A hash with no label, and everywhere it appears in the file
+#[derive(Debug, Clone)]
+pub struct UnknownHash {
+    pub hash: Hash40,
+    /// The paths of the params which use the hash, either as their key or their value
+    pub paths: Vec<ParamPath>,
+}
+
+/// Finds every hash in the file which has no label, most used first
+pub fn unknown_hashes(root: &ParamStruct) -> Vec<UnknownHash> {
+    let label_arc = Hash40::label_map();
+    let labels = match label_arc.lock() {
+        Ok(labels) => labels,
+        Err(err) => err.into_inner(),
+    };
+    let mut unknown = HashMap::<Hash40, Vec<ParamPath>>::new();
+    visit(root, |path, param| {
+        let value = match param {
+            ParamKind::Hash(hash) => Some(*hash),
+            _ => None,
+        };
+        for hash in path.key().into_iter().chain(value) {
+            if labels.label_of(hash).is_none() {
+                unknown.entry(hash).or_default().push(path.clone());
+            }
+        }
+    });
+    let mut unknown = unknown
+        .into_iter()
+        .map(|(hash, paths)| UnknownHash { hash, paths })
+        .collect::<Vec<_>>();
+    unknown.sort_by(|a, b| b.paths.len().cmp(&a.paths.len()).then(a.hash.cmp(&b.hash)));
+    unknown
+}
+
+/// Counts how many times each hash is used in the file, as a key or a value
+pub fn hash_usage(root: &ParamStruct) -> HashMap<Hash40, usize> {
+    fn count(param: &ParamKind, usage: &mut HashMap<Hash40, usize>) {
+        match param {
+            ParamKind::Hash(hash) => *usage.entry(*hash).or_default() += 1,
+            ParamKind::List(list) => list.0.iter().for_each(|child| count(child, usage)),
+            ParamKind::Struct(str) => count_struct(str, usage),
+            _ => {}
+        }
+    }
+
+    fn count_struct(str: &ParamStruct, usage: &mut HashMap<Hash40, usize>) {
+        for (key, child) in &str.0 {
+            *usage.entry(*key).or_default() += 1;
+            count(child, usage);
+        }
+    }
+
+    let mut usage = HashMap::new();
+    count_struct(root, &mut usage);
+    usage
+}
+
+/// Writes the hashes as CSV, with one line per hash: the hash, its count, and its paths
+pub fn write_unknown_hashes<P: AsRef<Path>>(path: P, unknown: &[UnknownHash]) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "hash,count,paths")?;
+    for entry in unknown {
+        let paths = entry
+            .paths
+            .iter()
+            .map(|path| path.to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+        writeln!(
+            writer,
+            "0x{:010x},{},{}",
+            entry.hash.0,
+            entry.paths.len(),
+            paths
+        )?;
+    }
+    writer.flush()
+}
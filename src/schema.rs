@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env::current_exe;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use prc::hash40::Hash40;
+use prc::{ParamKind, ParamStruct};
+use serde::Deserialize;
+
+use crate::path::{visit, ParamPath};
+use crate::script::PathPattern;
+use crate::value::param_type;
+use crate::value::parse_hash;
+
+pub const SCHEMA_FILE: &str = "Schema.toml";
+
+/// The type names a schema can expect, the same as the editor shows
+const TYPE_NAMES: &[&str] = &[
+    "bool", "i8", "u8", "i16", "u16", "i32", "u32", "f32", "hash", "string", "list", "struct",
+];
+
+/// The schema file as it's written
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SchemaFile {
+    /// The type of the params under each key, like `walk_speed = "f32"`
+    types: BTreeMap<String, String>,
+    /// The keys the structs at each path must have, like
+    /// `"fighter_param_table[*]" = ["fighter_kind", "walk_speed"]`
+    required: BTreeMap<String, Vec<String>>,
+}
+
+/// What the params of a file are expected to look like, to catch edits which would break the
+/// game before it reads them
+#[derive(Debug, Default, Clone)]
+pub struct Schema {
+    types: HashMap<Hash40, String>,
+    required: Vec<(PathPattern, Vec<Hash40>)>,
+}
+
+/// A param which doesn't match the schema
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub path: ParamPath,
+    pub message: String,
+}
+
+impl Schema {
+    /// Reads a schema written in TOML, with a `[types]` table and a `[required]` table
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: SchemaFile = toml::from_str(text).map_err(|err| err.to_string())?;
+        let types = file
+            .types
+            .into_iter()
+            .map(|(key, ty)| {
+                if !TYPE_NAMES.contains(&ty.as_str()) {
+                    return Err(format!("unknown type for {}: {}", key, ty));
+                }
+                Ok((parse_hash(&key)?, ty))
+            })
+            .collect::<Result<_, String>>()?;
+        let required = file
+            .required
+            .into_iter()
+            .map(|(path, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|key| parse_hash(key))
+                    .collect::<Result<_, _>>()?;
+                Ok((path.parse()?, keys))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { types, required })
+    }
+
+    /// Lists every param which doesn't match the schema, in file order for each rule
+    pub fn validate(&self, root: &ParamStruct) -> Vec<Violation> {
+        let mut violations = vec![];
+        visit(root, |path, param| {
+            let key = match path.key() {
+                Some(key) => key,
+                None => return,
+            };
+            let expected = self.types.get(&key);
+            if let Some(expected) = expected.filter(|ty| *ty != param_type(param)) {
+                violations.push(Violation {
+                    path: path.clone(),
+                    message: format!("should be {}, but is {}", expected, param_type(param)),
+                });
+            }
+        });
+        for (pattern, keys) in &self.required {
+            for path in pattern.expand(root) {
+                match path.get(root) {
+                    Some(ParamKind::Struct(str)) => {
+                        let missing = keys
+                            .iter()
+                            .filter(|key| !str.0.iter().any(|(hash, _)| hash == *key))
+                            .map(|key| key.to_string())
+                            .collect::<Vec<_>>();
+                        if !missing.is_empty() {
+                            violations.push(Violation {
+                                message: format!("is missing {}", missing.join(", ")),
+                                path,
+                            });
+                        }
+                    }
+                    Some(param) => violations.push(Violation {
+                        message: format!("should be struct, but is {}", param_type(param)),
+                        path,
+                    }),
+                    None => {}
+                }
+            }
+        }
+        violations
+    }
+}
+
+/// Finds the schema file, checking the current directory first, then the application directory
+fn schema_path() -> Option<PathBuf> {
+    let local = PathBuf::from(SCHEMA_FILE);
+    if local.is_file() {
+        return Some(local);
+    }
+    let exe = current_exe().ok()?;
+    Some(exe.parent()?.join(SCHEMA_FILE)).filter(|path| path.is_file())
+}
+
+/// Reads the schema file, or the one given in the config. Without one, files aren't validated
+pub fn load_schema(path: Option<&Path>) -> Result<Option<Schema>, String> {
+    let path = match path.map(Path::to_path_buf).or_else(schema_path) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    log::info!("reading the schema from {}", path.display());
+    read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| Schema::parse(&text))
+        .map(Some)
+        .map_err(|err| format!("Failed to load {}: {}", path.display(), err))
+}
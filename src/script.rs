@@ -0,0 +1,466 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use prc::hash40::Hash40;
+use prc::{ParamKind, ParamStruct};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FLOAT, INT};
+
+use crate::expr::evaluate;
+use crate::path::{ParamPath, PathSegment};
+use crate::replace::{apply_replacements, Replacement};
+use crate::transform::{transform_values, Operator, Transform};
+use crate::value::{number_value, parse_hash, parse_value};
+
+/// A path which can match many params, where `*` stands for any key and `[*]` for any index,
+/// like `fighter_param_table[*].walk_speed`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathPattern(pub Vec<PatternSegment>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PatternSegment {
+    Key(Hash40),
+    AnyKey,
+    Index(usize),
+    AnyIndex,
+}
+
+impl PathPattern {
+    /// The paths of every param the pattern matches, in file order
+    pub fn expand(&self, root: &ParamStruct) -> Vec<ParamPath> {
+        let mut paths = vec![];
+        expand_struct(root, &self.0, &mut ParamPath::default(), &mut paths);
+        paths
+    }
+}
+
+fn expand_struct(
+    param: &ParamStruct,
+    rest: &[PatternSegment],
+    path: &mut ParamPath,
+    paths: &mut Vec<ParamPath>,
+) {
+    let (segment, rest) = match rest.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    for (hash, child) in param.0.iter() {
+        if matches!(segment, PatternSegment::Key(key) if key == hash)
+            || *segment == PatternSegment::AnyKey
+        {
+            path.0.push(PathSegment::Key(*hash));
+            expand_param(child, rest, path, paths);
+            path.0.pop();
+        }
+    }
+}
+
+fn expand_param(
+    param: &ParamKind,
+    rest: &[PatternSegment],
+    path: &mut ParamPath,
+    paths: &mut Vec<ParamPath>,
+) {
+    if rest.is_empty() {
+        paths.push(path.clone());
+        return;
+    }
+    match (param, rest[0]) {
+        (ParamKind::Struct(str), _) => expand_struct(str, rest, path, paths),
+        (ParamKind::List(list), PatternSegment::Index(index)) => {
+            if let Some(child) = list.0.get(index) {
+                path.0.push(PathSegment::Index(index));
+                expand_param(child, &rest[1..], path, paths);
+                path.0.pop();
+            }
+        }
+        (ParamKind::List(list), PatternSegment::AnyIndex) => {
+            for (index, child) in list.0.iter().enumerate() {
+                path.0.push(PathSegment::Index(index));
+                expand_param(child, &rest[1..], path, paths);
+                path.0.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+impl FromStr for PathPattern {
+    type Err = String;
+
+    /// Reads a pattern written like a path, where `*` can be used as a key or an index
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(String::from("the path is empty"));
+        }
+        let mut segments = vec![];
+        for part in s.split('.') {
+            let (key, mut indices) = part.split_at(part.find('[').unwrap_or(part.len()));
+            segments.push(match key {
+                "" => return Err(format!("missing key in path: {}", s)),
+                "*" => PatternSegment::AnyKey,
+                key => PatternSegment::Key(parse_hash(key)?),
+            });
+            while !indices.is_empty() {
+                let end = indices
+                    .find(']')
+                    .filter(|_| indices.starts_with('['))
+                    .ok_or_else(|| format!("unmatched brackets in path: {}", s))?;
+                segments.push(match &indices[1..end] {
+                    "*" => PatternSegment::AnyIndex,
+                    index => PatternSegment::Index(
+                        index
+                            .parse()
+                            .map_err(|_| format!("invalid index in path: {}", s))?,
+                    ),
+                });
+                indices = &indices[end + 1..];
+            }
+        }
+        Ok(PathPattern(segments))
+    }
+}
+
+/// One edit in a script, made to every param its pattern matches
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub pattern: PathPattern,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Sets each param to the value, written the same way as when editing it
+    Set(String),
+    /// Changes each number, like `*= 1.05` multiplying it
+    Transform(Transform),
+}
+
+impl FromStr for Statement {
+    type Err = String;
+
+    /// Reads an edit written `path = value` or `path,value`, or `path += n` with any of
+    /// `+`, `-`, `*`, or `/` to change numbers instead of setting them
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (path, value) = s
+            .split_once(['=', ','])
+            .ok_or_else(|| String::from("expected `path = value`"))?;
+        let assigns = s[path.len()..].starts_with('=');
+        let operator = path
+            .chars()
+            .last()
+            .filter(|_| assigns)
+            .and_then(Operator::from_char);
+        let (path, action) = match operator {
+            Some(operator) => {
+                // the number is read on its own, then combined with each value
+                let number = evaluate(value.trim(), 0.0)?;
+                (
+                    &path[..path.len() - 1],
+                    Action::Transform(Transform::Relative(operator, number)),
+                )
+            }
+            None => (path, Action::Set(value.trim().to_owned())),
+        };
+        Ok(Statement {
+            pattern: path.trim().parse()?,
+            action,
+        })
+    }
+}
+
+/// Makes one edit to every param it matches, returning the values it changed.
+/// Fails without changing anything if the edit matches nothing or doesn't fit a param
+pub fn run_statement(
+    root: &mut ParamStruct,
+    statement: &Statement,
+) -> Result<Vec<Replacement>, String> {
+    let paths = statement.pattern.expand(root);
+    if paths.is_empty() {
+        return Err(String::from("no param matches the path"));
+    }
+    let replacements = match &statement.action {
+        Action::Set(value) => paths
+            .iter()
+            .filter_map(|path| {
+                let old = path.get(root)?;
+                Some(Replacement {
+                    path: path.clone(),
+                    old: old.clone(),
+                    new: parse_value(old, value),
+                })
+            })
+            .filter(|replacement| replacement.new.as_ref() != Ok(&replacement.old))
+            .collect::<Vec<_>>(),
+        Action::Transform(transform) => transform_values(root, &paths, transform),
+    };
+    if let Some(failed) = replacements.iter().find(|r| r.new.is_err()) {
+        let msg = failed.new.as_ref().err().cloned().unwrap_or_default();
+        return Err(format!("{}: {}", failed.path, msg));
+    }
+    apply_replacements(root, &replacements);
+    Ok(replacements)
+}
+
+/// Runs the edits in order, each seeing the ones before it, and returns every value they
+/// change from how it is in the root
+pub fn run_script(
+    root: &ParamStruct,
+    statements: &[Statement],
+) -> Result<Vec<Replacement>, String> {
+    let mut edited = root.clone();
+    // the paths in the order they were first edited, for listing the changes in that order
+    let mut touched = vec![];
+    let mut seen = HashSet::new();
+    for (number, statement) in statements.iter().enumerate() {
+        let replacements = run_statement(&mut edited, statement)
+            .map_err(|msg| format!("edit {}: {}", number + 1, msg))?;
+        for replacement in replacements {
+            if seen.insert(replacement.path.clone()) {
+                touched.push(replacement.path);
+            }
+        }
+    }
+    Ok(changes(root, &edited, touched))
+}
+
+/// How long a rhai script may run, so one that never ends can't hang the editor
+const SCRIPT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Runs a rhai script over a copy of the root, and returns every value it changes.
+/// Besides the language itself, the script has:
+/// - `paths(pattern)`: the path of every param a pattern matches, like `table[*].speed`
+/// - `get(path)`: a param's value, where hashes are their labels
+/// - `set(path, value)`: changes a param, from either a number or text written the same way as
+///   when editing it
+pub fn run_rhai(root: &ParamStruct, source: &str) -> Result<Vec<Replacement>, String> {
+    // the copy being edited, and the paths in the order they were set
+    let state = Rc::new(RefCell::new((root.clone(), vec![])));
+    let mut engine = Engine::new();
+    let start = Instant::now();
+    engine.on_progress(move |_| (start.elapsed() > SCRIPT_TIMEOUT).then_some(Dynamic::UNIT));
+    // printing would draw over the editor
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+
+    let edited = state.clone();
+    engine.register_fn(
+        "paths",
+        move |pattern: &str| -> Result<Array, Box<EvalAltResult>> {
+            let pattern = pattern.parse::<PathPattern>()?;
+            Ok(pattern
+                .expand(&edited.borrow().0)
+                .iter()
+                .map(|path| Dynamic::from(path.to_string()))
+                .collect())
+        },
+    );
+    let edited = state.clone();
+    engine.register_fn(
+        "get",
+        move |path: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+            let (root, _) = &*edited.borrow();
+            let param = find_param(root, path)?;
+            Ok(match param {
+                ParamKind::Bool(v) => Dynamic::from(*v),
+                ParamKind::Float(v) => Dynamic::from(*v as FLOAT),
+                ParamKind::Hash(v) => Dynamic::from(v.to_string()),
+                ParamKind::Str(v) => Dynamic::from(v.clone()),
+                ParamKind::List(_) | ParamKind::Struct(_) => {
+                    return Err(format!("{} is a list or struct, which has no value", path).into())
+                }
+                ParamKind::I8(v) => Dynamic::from(*v as INT),
+                ParamKind::U8(v) => Dynamic::from(*v as INT),
+                ParamKind::I16(v) => Dynamic::from(*v as INT),
+                ParamKind::U16(v) => Dynamic::from(*v as INT),
+                ParamKind::I32(v) => Dynamic::from(*v as INT),
+                ParamKind::U32(v) => Dynamic::from(*v as INT),
+            })
+        },
+    );
+    let edited = state.clone();
+    engine.register_fn(
+        "set",
+        move |path: &str, value: Dynamic| -> Result<(), Box<EvalAltResult>> {
+            let (root, touched) = &mut *edited.borrow_mut();
+            let old = find_param(root, path)?;
+            let new = if let Ok(number) = value.as_int() {
+                number_value(old, number as f64)
+            } else if let Ok(number) = value.as_float() {
+                number_value(old, number)
+            } else if let Ok(text) = value.clone().into_string() {
+                parse_value(old, &text)
+            } else {
+                parse_value(old, &value.to_string())
+            }
+            .map_err(|msg| format!("{}: {}", path, msg))?;
+            let path = path.parse::<ParamPath>()?;
+            if let Some(param) = path.get_mut(root) {
+                *param = new;
+            }
+            touched.push(path);
+            Ok(())
+        },
+    );
+
+    engine.run(source).map_err(|err| match *err {
+        EvalAltResult::ErrorTerminated(..) => String::from("the script took too long"),
+        err => err.to_string(),
+    })?;
+    let (edited, mut touched) = state.replace((ParamStruct(vec![]), vec![]));
+    // each change is listed where its param was first set
+    let mut seen = HashSet::new();
+    touched.retain(|path| seen.insert(path.clone()));
+    Ok(changes(root, &edited, touched))
+}
+
+fn find_param<'a>(root: &'a ParamStruct, path: &str) -> Result<&'a ParamKind, String> {
+    path.parse::<ParamPath>()?
+        .get(root)
+        .ok_or_else(|| format!("no param at {}", path))
+}
+
+/// The values at the touched paths which differ between the root and its edited copy
+fn changes(root: &ParamStruct, edited: &ParamStruct, touched: Vec<ParamPath>) -> Vec<Replacement> {
+    touched
+        .into_iter()
+        .filter_map(|path| {
+            let old = path.get(root)?.clone();
+            let new = path.get(edited)?.clone();
+            (old != new).then_some(Replacement {
+                path,
+                old,
+                new: Ok(new),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use prc::hash40::hash40;
+
+    use super::*;
+
+    fn root() -> ParamStruct {
+        ParamStruct(vec![
+            (hash40("jump"), ParamKind::I32(10)),
+            (hash40("walk_speed"), ParamKind::Float(1.5)),
+        ])
+    }
+
+    /// Runs one edit on a fresh root and returns the param it edits afterwards
+    fn run(edit: &str) -> ParamKind {
+        let mut root = root();
+        let statement = edit.parse::<Statement>().unwrap();
+        run_statement(&mut root, &statement).unwrap();
+        let path = edit.split(|c| "+-*/=,".contains(c)).next().unwrap();
+        path.trim()
+            .parse::<ParamPath>()
+            .unwrap()
+            .get(&root)
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn add_assign() {
+        assert_eq!(run("jump += 3"), ParamKind::I32(13));
+        assert_eq!(run("walk_speed += 0.5"), ParamKind::Float(2.0));
+    }
+
+    #[test]
+    fn subtract_assign() {
+        assert_eq!(run("jump -= 3"), ParamKind::I32(7));
+        assert_eq!(run("walk_speed -= 0.5"), ParamKind::Float(1.0));
+        assert_eq!(run("jump -= -3"), ParamKind::I32(13));
+    }
+
+    #[test]
+    fn multiply_assign() {
+        assert_eq!(run("jump *= 3"), ParamKind::I32(30));
+        assert_eq!(run("walk_speed *= 2"), ParamKind::Float(3.0));
+    }
+
+    #[test]
+    fn divide_assign() {
+        assert_eq!(run("jump /= 4"), ParamKind::I32(3));
+        assert_eq!(run("walk_speed /= 3"), ParamKind::Float(0.5));
+    }
+
+    #[test]
+    fn set() {
+        assert_eq!(run("jump = 3"), ParamKind::I32(3));
+        assert_eq!(run("walk_speed,-0.5"), ParamKind::Float(-0.5));
+    }
+
+    #[test]
+    fn parse_pattern() {
+        let pattern = "table[*].*[2]".parse::<PathPattern>();
+        assert_eq!(
+            pattern,
+            Ok(PathPattern(vec![
+                PatternSegment::Key(hash40("table")),
+                PatternSegment::AnyIndex,
+                PatternSegment::AnyKey,
+                PatternSegment::Index(2),
+            ]))
+        );
+        let error = |s: &str| s.parse::<PathPattern>().unwrap_err();
+        assert_eq!(error(""), "the path is empty");
+        assert_eq!(error("a.[*]"), "missing key in path: a.[*]");
+        assert_eq!(error("a[*"), "unmatched brackets in path: a[*");
+        assert_eq!(error("a[x]"), "invalid index in path: a[x]");
+    }
+
+    #[test]
+    fn expand_pattern() {
+        let list = ParamKind::List(prc::ParamList(vec![
+            ParamKind::Struct(root()),
+            ParamKind::Struct(root()),
+        ]));
+        let root = ParamStruct(vec![(hash40("table"), list)]);
+        let expand = |pattern: &str| pattern.parse::<PathPattern>().unwrap().expand(&root);
+        assert_eq!(
+            expand("table[*].jump"),
+            [
+                "table[0].jump".parse::<ParamPath>().unwrap(),
+                "table[1].jump".parse().unwrap(),
+            ]
+        );
+        assert_eq!(expand("table[1].*").len(), 2);
+        assert_eq!(expand("table[*]").len(), 2);
+        assert!(expand("table[2].*").is_empty());
+        assert!(expand("missing[*]").is_empty());
+    }
+
+    #[test]
+    fn parse_statement() {
+        let statement = "a[*].b *= 1.5".parse::<Statement>().unwrap();
+        assert_eq!(statement.pattern, "a[*].b".parse().unwrap());
+        assert!(matches!(
+            statement.action,
+            Action::Transform(Transform::Relative(Operator::Multiply, n)) if n == 1.5
+        ));
+        let statement = "a -= (1 + 2)".parse::<Statement>().unwrap();
+        assert!(matches!(
+            statement.action,
+            Action::Transform(Transform::Relative(Operator::Subtract, n)) if n == 3.0
+        ));
+        let statement = "a = -1".parse::<Statement>().unwrap();
+        assert!(matches!(statement.action, Action::Set(value) if value == "-1"));
+        let statement = "a.b,mario = luigi".parse::<Statement>().unwrap();
+        assert!(matches!(statement.action, Action::Set(value) if value == "mario = luigi"));
+        assert!("a".parse::<Statement>().is_err());
+        assert!("a += x".parse::<Statement>().is_err());
+        assert!("= 1".parse::<Statement>().is_err());
+    }
+
+    #[test]
+    fn dividing_by_zero_fails() {
+        let statement = "jump /= 0".parse::<Statement>().unwrap();
+        assert!(run_statement(&mut root(), &statement).is_err());
+    }
+}
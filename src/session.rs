@@ -0,0 +1,44 @@
+use std::env::current_exe;
+use std::fs::{read, write};
+use std::io::Error;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+pub const SESSION_FILE: &str = "Session.json";
+
+/// Where the user left off: the open file and how far into it they'd navigated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub file: PathBuf,
+    /// Each column from left to right. Every level but the last is open at its selected child
+    pub levels: Vec<LevelSession>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LevelSession {
+    /// The index of the selected child within its parent
+    pub selected: Option<usize>,
+    pub filter: Option<String>,
+}
+
+/// The session is kept in the application directory, so it's shared between working directories
+fn session_path() -> Result<PathBuf, Error> {
+    let exe = current_exe()?;
+    Ok(exe.parent().unwrap().join(SESSION_FILE))
+}
+
+/// Reads the last session, if there is one and its file still exists
+pub fn load_session() -> Option<Session> {
+    let bytes = read(session_path().ok()?).ok()?;
+    let session = serde_json::from_slice::<Session>(&bytes).ok()?;
+    if session.file.is_file() {
+        Some(session)
+    } else {
+        None
+    }
+}
+
+pub fn save_session(session: &Session) -> Result<(), Error> {
+    write(session_path()?, serde_json::to_vec_pretty(session)?)
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use prc::hash40::Hash40;
+use serde::{Deserialize, Serialize};
+
+use crate::labels::label_text;
+use crate::theme::Theme;
+
+/// Display options shared between every level of the param view
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Settings {
+    pub hash_display: HashDisplay,
+    pub float_format: FloatFormat,
+    pub theme: Theme,
+    /// Whether the last column has a column with the hex of each key and hash value
+    pub hex_column: bool,
+    /// Whether each row starts with its position in its list or struct
+    pub row_numbers: bool,
+    /// How much Left and Right change a float being edited, or ten times as much with Shift
+    pub float_step: f32,
+}
+
+pub const DEFAULT_FLOAT_STEP: f32 = 0.1;
+
+/// How hashes are shown in the param view
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashDisplay {
+    /// The label if one is known, otherwise the hex value
+    #[default]
+    Label,
+    /// Always the hex value
+    Hex,
+    /// The label followed by the hex value, if a label is known
+    Both,
+}
+
+impl HashDisplay {
+    pub fn next(self) -> Self {
+        match self {
+            HashDisplay::Label => HashDisplay::Hex,
+            HashDisplay::Hex => HashDisplay::Both,
+            HashDisplay::Both => HashDisplay::Label,
+        }
+    }
+
+    /// Like `format`, but shares the label rather than copying it when only labels are shown
+    pub fn text(self, hash: Hash40) -> Arc<str> {
+        match self {
+            HashDisplay::Label => label_text(hash),
+            _ => self.format(hash).into(),
+        }
+    }
+
+    pub fn format(self, hash: Hash40) -> String {
+        let hex = format!("0x{:010x}", hash.0);
+        match self {
+            HashDisplay::Label => label_text(hash).to_string(),
+            HashDisplay::Hex => hex,
+            HashDisplay::Both => {
+                let label = label_text(hash);
+                if *label == hex {
+                    hex
+                } else {
+                    format!("{} ({})", label, hex)
+                }
+            }
+        }
+    }
+}
+
+/// The hex of a param's struct key and hash value, like `0x0f2a6c9e31 = 0x1b4d20aa07`, for
+/// cross-referencing with other tools. Either side is left out if the param doesn't have it
+pub fn hex_pair(key: Option<Hash40>, value: Option<Hash40>) -> String {
+    let hex = |hash: Hash40| format!("0x{:010x}", hash.0);
+    match (key, value) {
+        (Some(key), Some(value)) => format!("{} = {}", hex(key), hex(value)),
+        (Some(key), None) => hex(key),
+        (None, Some(value)) => format!("= {}", hex(value)),
+        (None, None) => String::new(),
+    }
+}
+
+/// The fixed numbers of decimals which floats can be shown with, in the order they're cycled
+const DECIMALS: [usize; 4] = [2, 3, 4, 6];
+
+/// How floats are shown in the param view
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FloatFormat {
+    /// How many decimals to show, or None for the fewest which still read back as the same float
+    pub decimals: Option<usize>,
+    /// Whether floats are shown in scientific notation, like `2.5e-1`
+    pub scientific: bool,
+}
+
+impl FloatFormat {
+    /// Shows more decimals, going back to the fewest exact ones after the most
+    pub fn next_decimals(self) -> Self {
+        let decimals = match self.decimals {
+            None => Some(DECIMALS[0]),
+            Some(decimals) => DECIMALS.iter().copied().find(|next| *next > decimals),
+        };
+        Self { decimals, ..self }
+    }
+
+    pub fn format(self, float: f32) -> String {
+        match (self.decimals, self.scientific) {
+            (None, false) => float.to_string(),
+            (None, true) => format!("{:e}", float),
+            (Some(decimals), false) => format!("{:.*}", decimals, float),
+            (Some(decimals), true) => format!("{:.*e}", decimals, float),
+        }
+    }
+}
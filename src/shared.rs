@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use prc::ParamStruct;
+
+/// The file being edited, shared by every level of the editor.
+///
+/// Taking a snapshot (e.g. for saving) only clones a pointer. The data is copied
+/// once if it's edited while a snapshot is still in use, and never otherwise
+#[derive(Debug, Clone, Default)]
+pub struct SharedParam {
+    param: Arc<Mutex<Arc<ParamStruct>>>,
+    /// How many times the file has been edited, to tell whether anything built from it is
+    /// out of date
+    edits: Arc<AtomicUsize>,
+}
+
+impl SharedParam {
+    /// Shares the params, without copying them if they're already shared, like a snapshot
+    pub fn new<P: Into<Arc<ParamStruct>>>(param: P) -> Self {
+        Self {
+            param: Arc::new(Mutex::new(param.into())),
+            edits: Arc::default(),
+        }
+    }
+
+    /// The file as it is now, unaffected by later edits
+    pub fn snapshot(&self) -> Arc<ParamStruct> {
+        match self.param.lock() {
+            Ok(param) => param.clone(),
+            Err(err) => err.into_inner().clone(),
+        }
+    }
+
+    /// How many times the file has been edited since it was opened
+    pub fn edits(&self) -> usize {
+        self.edits.load(Ordering::Relaxed)
+    }
+
+    pub fn with<R, F: FnOnce(&ParamStruct) -> R>(&self, f: F) -> R {
+        let param = match self.param.lock() {
+            Ok(param) => param,
+            Err(err) => err.into_inner(),
+        };
+        f(&param)
+    }
+
+    /// Puts other params in place of the whole file. Unlike editing it through `with_mut`, the
+    /// params being replaced are never copied first
+    pub fn replace(&self, param: Arc<ParamStruct>) {
+        let mut current = match self.param.lock() {
+            Ok(current) => current,
+            Err(err) => err.into_inner(),
+        };
+        self.edits.fetch_add(1, Ordering::Relaxed);
+        *current = param;
+    }
+
+    pub fn with_mut<R, F: FnOnce(&mut ParamStruct) -> R>(&self, f: F) -> R {
+        let mut param = match self.param.lock() {
+            Ok(param) => param,
+            Err(err) => err.into_inner(),
+        };
+        self.edits.fetch_add(1, Ordering::Relaxed);
+        f(Arc::make_mut(&mut param))
+    }
+}
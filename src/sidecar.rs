@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{read, remove_file, write};
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::tree_view::ViewMode;
+use crate::path::ParamPath;
+use crate::settings::{FloatFormat, HashDisplay, Settings};
+
+/// Added to the name of a param file for the file kept next to it
+pub const SIDECAR_EXTENSION: &str = "prickly.json";
+
+/// What the editor keeps about a param file in a file next to it, so it travels with the file
+/// and is picked up again whenever the file is opened
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sidecar {
+    /// Params to come back to, by their paths
+    pub bookmarks: Vec<String>,
+    /// Notes about params, like why they were changed, by their paths
+    pub notes: BTreeMap<String, String>,
+    /// The filter of each list or struct which has one, by its path
+    pub filters: BTreeMap<String, String>,
+    /// How the file was shown when the sidecar was last written
+    pub view: Option<ViewSettings>,
+}
+
+/// The display options which are kept with a file
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewSettings {
+    pub layout: ViewMode,
+    /// Whether the details pane is shown
+    pub details: bool,
+    pub hash_display: HashDisplay,
+    pub float_format: FloatFormat,
+    pub hex_column: bool,
+    pub row_numbers: bool,
+}
+
+impl ViewSettings {
+    pub fn new(layout: ViewMode, details: bool, settings: &Settings) -> Self {
+        Self {
+            layout,
+            details,
+            hash_display: settings.hash_display,
+            float_format: settings.float_format,
+            hex_column: settings.hex_column,
+            row_numbers: settings.row_numbers,
+        }
+    }
+
+    /// Shows the file the way it was shown before
+    pub fn apply(self, settings: &mut Settings) {
+        settings.hash_display = self.hash_display;
+        settings.float_format = self.float_format;
+        settings.hex_column = self.hex_column;
+        settings.row_numbers = self.row_numbers;
+    }
+}
+
+impl Sidecar {
+    pub fn note(&self, path: &ParamPath) -> Option<&str> {
+        self.notes.get(&path.to_string()).map(String::as_str)
+    }
+
+    /// Attaches the note to the param, or removes its note if it's blank
+    pub fn set_note(&mut self, path: &ParamPath, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.notes.remove(&path.to_string());
+        } else {
+            self.notes.insert(path.to_string(), note.to_owned());
+        }
+    }
+
+    /// Bookmarks the param, or removes its bookmark. Returns whether it's bookmarked now
+    pub fn toggle_bookmark(&mut self, path: &ParamPath) -> bool {
+        let path = path.to_string();
+        match self.bookmarks.iter().position(|bookmark| *bookmark == path) {
+            Some(index) => {
+                self.bookmarks.remove(index);
+                false
+            }
+            None => {
+                self.bookmarks.push(path);
+                true
+            }
+        }
+    }
+
+    /// The bookmarked params, leaving out any whose paths can't be read
+    pub fn bookmarks(&self) -> HashSet<ParamPath> {
+        self.bookmarks
+            .iter()
+            .filter_map(|bookmark| parse_path(bookmark))
+            .collect()
+    }
+
+    /// The kept filters, leaving out any whose paths can't be read
+    pub fn filters(&self) -> HashMap<ParamPath, String> {
+        self.filters
+            .iter()
+            .filter_map(|(path, filter)| Some((parse_path(path)?, filter.clone())))
+            .collect()
+    }
+
+    /// Records how the file is shown now, to be written along with the notes and bookmarks
+    pub fn record(&mut self, view: ViewSettings, filters: &HashMap<ParamPath, String>) {
+        self.view = Some(view);
+        self.filters = filters
+            .iter()
+            .map(|(path, filter)| (path.to_string(), filter.clone()))
+            .collect();
+    }
+
+    /// Whether there's nothing the user asked to keep. The filters and view settings are only
+    /// kept along with notes or bookmarks, so not every file opened gets a sidecar
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty() && self.bookmarks.is_empty()
+    }
+}
+
+/// Reads a path kept in a sidecar, where an empty one is the top level
+fn parse_path(path: &str) -> Option<ParamPath> {
+    if path.is_empty() {
+        Some(ParamPath::default())
+    } else {
+        path.parse().ok()
+    }
+}
+
+/// The sidecar of a file is named after it, like `fighter_param.prc.prickly.json`
+pub fn sidecar_path(file: &Path) -> PathBuf {
+    let mut name = file.as_os_str().to_owned();
+    name.push(".");
+    name.push(SIDECAR_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Reads the sidecar of a file. Without one, nothing is kept about the file yet
+pub fn load_sidecar(file: &Path) -> Result<Sidecar, String> {
+    let path = sidecar_path(file);
+    log::info!("reading the sidecar {}", path.display());
+    match read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|err| err.to_string()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(Sidecar::default()),
+        Err(err) => Err(err.to_string()),
+    }
+    .map_err(|err| format!("Failed to load {}: {}", path.display(), err))
+}
+
+/// Writes the sidecar next to the file, or removes it once there's nothing left to keep
+pub fn save_sidecar(file: &Path, sidecar: &Sidecar) -> Result<(), Error> {
+    let path = sidecar_path(file);
+    if !sidecar.is_empty() {
+        return write(path, serde_json::to_vec_pretty(sidecar)?);
+    }
+    match remove_file(path) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,172 @@
+use std::cmp::Ordering;
+
+use prc::ParamKind;
+
+use crate::components::param::{ParamParent, ParentIndex};
+use crate::settings::HashDisplay;
+
+/// How the children of a param are ordered in the view. Sorting only changes the view,
+/// until the order is applied to the file
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    /// The order they're stored in the file
+    #[default]
+    File,
+    /// By the displayed key. List children keep their file order
+    Name,
+    /// By the key's hash value. List children keep their file order
+    Hash,
+    /// By type, in the order the types are numbered in the file format
+    Type,
+    /// Numbers by value, then everything else by its displayed text.
+    /// Lists and structs are sorted by their number of children
+    Value,
+}
+
+/// What a child is compared by. Numbers come before text
+#[derive(Debug, Clone)]
+enum SortKey {
+    Number(f64),
+    Text(String),
+}
+
+/// Numbers use a total order, so a NaN sorts to one end instead of comparing equal to
+/// everything and leaving the rest out of order
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+            (SortKey::Number(_), SortKey::Text(_)) => Ordering::Less,
+            (SortKey::Text(_), SortKey::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SortKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortKey {}
+
+impl SortOrder {
+    pub fn next(self) -> Self {
+        match self {
+            SortOrder::File => SortOrder::Name,
+            SortOrder::Name => SortOrder::Hash,
+            SortOrder::Hash => SortOrder::Type,
+            SortOrder::Type => SortOrder::Value,
+            SortOrder::Value => SortOrder::File,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortOrder::File => "file",
+            SortOrder::Name => "name",
+            SortOrder::Hash => "hash",
+            SortOrder::Type => "type",
+            SortOrder::Value => "value",
+        }
+    }
+
+    /// Sorts the positions of children within the level. Children which compare equal
+    /// keep their order from the file
+    pub fn sort(self, level: ParamParent, rows: &mut Vec<usize>, hashes: HashDisplay) {
+        if self == SortOrder::File {
+            rows.sort_unstable();
+            return;
+        }
+        let mut keyed = rows
+            .iter()
+            .map(|row| {
+                let (index, param) = level.child(*row);
+                (self.key(*row, index, param, hashes), *row)
+            })
+            .collect::<Vec<_>>();
+        keyed.sort_by(|(a, a_row), (b, b_row)| a.cmp(b).then(a_row.cmp(b_row)));
+        *rows = keyed.into_iter().map(|(_, row)| row).collect();
+    }
+
+    fn key(
+        self,
+        position: usize,
+        index: ParentIndex,
+        param: &ParamKind,
+        hashes: HashDisplay,
+    ) -> SortKey {
+        match (self, index) {
+            (SortOrder::Name, ParentIndex::Struct(hash)) => {
+                SortKey::Text(hashes.format(hash).to_lowercase())
+            }
+            (SortOrder::Hash, ParentIndex::Struct(hash)) => SortKey::Number(hash.0 as f64),
+            (SortOrder::Type, _) => SortKey::Number(type_rank(param) as f64),
+            (SortOrder::Value, _) => value_key(param, hashes),
+            _ => SortKey::Number(position as f64),
+        }
+    }
+}
+
+fn type_rank(param: &ParamKind) -> u8 {
+    match param {
+        ParamKind::Bool(_) => 1,
+        ParamKind::I8(_) => 2,
+        ParamKind::U8(_) => 3,
+        ParamKind::I16(_) => 4,
+        ParamKind::U16(_) => 5,
+        ParamKind::I32(_) => 6,
+        ParamKind::U32(_) => 7,
+        ParamKind::Float(_) => 8,
+        ParamKind::Hash(_) => 9,
+        ParamKind::Str(_) => 10,
+        ParamKind::List(_) => 11,
+        ParamKind::Struct(_) => 12,
+    }
+}
+
+fn value_key(param: &ParamKind, hashes: HashDisplay) -> SortKey {
+    match param {
+        ParamKind::Bool(v) => SortKey::Number(*v as u8 as f64),
+        ParamKind::I8(v) => SortKey::Number(*v as f64),
+        ParamKind::U8(v) => SortKey::Number(*v as f64),
+        ParamKind::I16(v) => SortKey::Number(*v as f64),
+        ParamKind::U16(v) => SortKey::Number(*v as f64),
+        ParamKind::I32(v) => SortKey::Number(*v as f64),
+        ParamKind::U32(v) => SortKey::Number(*v as f64),
+        ParamKind::Float(v) => SortKey::Number(*v as f64),
+        ParamKind::List(list) => SortKey::Number(list.0.len() as f64),
+        ParamKind::Struct(str) => SortKey::Number(str.0.len() as f64),
+        ParamKind::Hash(hash) => SortKey::Text(hashes.format(*hash).to_lowercase()),
+        ParamKind::Str(str) => SortKey::Text(str.to_lowercase()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nan_sorts_to_the_end() {
+        let mut keys = [
+            SortKey::Text("a".into()),
+            SortKey::Number(2.0),
+            SortKey::Number(f64::NAN),
+            SortKey::Number(-1.0),
+            SortKey::Number(f64::INFINITY),
+        ];
+        keys.sort();
+        assert!(matches!(keys[0], SortKey::Number(n) if n == -1.0));
+        assert!(matches!(keys[1], SortKey::Number(n) if n == 2.0));
+        assert!(matches!(keys[2], SortKey::Number(n) if n == f64::INFINITY));
+        assert!(matches!(keys[3], SortKey::Number(n) if n.is_nan()));
+        assert!(matches!(keys[4], SortKey::Text(_)));
+    }
+}
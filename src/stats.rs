@@ -0,0 +1,108 @@
+use std::collections::{BTreeMap, HashSet};
+
+use prc::hash40::Hash40;
+use prc::{ParamKind, ParamStruct};
+
+use crate::report::unknown_hashes;
+use crate::value::param_type;
+
+/// The size of a param file's header: its magic, and the sizes of its two tables
+const HEADER_SIZE: usize = 0x10;
+
+/// Figures describing a whole file
+#[derive(Debug, Clone, Default)]
+pub struct FileStats {
+    /// How many params there are of each type, by the name of the type
+    pub types: BTreeMap<&'static str, usize>,
+    /// How many params there are below the root
+    pub params: usize,
+    /// How deep the most nested param is, where the params in the root are 1 deep
+    pub max_depth: usize,
+    /// How many different hashes have no label, used as keys or values
+    pub unknown_hashes: usize,
+    /// How many different hashes the file's hash table holds
+    pub hashes: usize,
+    /// How many different strings there are, since each is only stored once
+    pub strings: usize,
+    /// The bytes the strings take up, with a terminator after each
+    pub string_table_size: usize,
+    /// How many bytes the file takes when it's saved as a param file
+    pub estimated_size: usize,
+}
+
+#[derive(Default)]
+struct Counter<'a> {
+    stats: FileStats,
+    hashes: HashSet<Hash40>,
+    strings: HashSet<&'a str>,
+    /// The key of each struct param and where it's written relative to the struct, sorted by key.
+    /// Identical tables are only written once
+    struct_tables: HashSet<Vec<(Hash40, usize)>>,
+}
+
+impl<'a> Counter<'a> {
+    /// Counts the struct's params, returning the bytes the struct takes in the params section
+    fn count_struct(&mut self, str: &'a ParamStruct, depth: usize) -> usize {
+        let mut children = str.0.iter().collect::<Vec<_>>();
+        children.sort_by_key(|(key, _)| *key);
+        let mut size = 9;
+        let mut table = Vec::with_capacity(children.len());
+        for (key, child) in children {
+            self.hashes.insert(*key);
+            table.push((*key, size));
+            size += self.count(child, depth);
+        }
+        self.struct_tables.insert(table);
+        size
+    }
+
+    /// Counts the param and everything in it, returning the bytes it takes in the params section
+    fn count(&mut self, param: &'a ParamKind, depth: usize) -> usize {
+        self.stats.params += 1;
+        self.stats.max_depth = self.stats.max_depth.max(depth);
+        *self.stats.types.entry(param_type(param)).or_default() += 1;
+        match param {
+            ParamKind::Bool(_) | ParamKind::I8(_) | ParamKind::U8(_) => 2,
+            ParamKind::I16(_) | ParamKind::U16(_) => 3,
+            ParamKind::I32(_) | ParamKind::U32(_) | ParamKind::Float(_) => 5,
+            ParamKind::Hash(hash) => {
+                self.hashes.insert(*hash);
+                5
+            }
+            ParamKind::Str(str) => {
+                self.strings.insert(str);
+                5
+            }
+            ParamKind::List(list) => {
+                5 + list
+                    .0
+                    .iter()
+                    .map(|child| 4 + self.count(child, depth + 1))
+                    .sum::<usize>()
+            }
+            ParamKind::Struct(str) => self.count_struct(str, depth + 1),
+        }
+    }
+}
+
+/// Counts up the params in the file, and works out the size it's saved with
+pub fn file_stats(root: &ParamStruct) -> FileStats {
+    let mut counter = Counter::default();
+    // the hash table always starts with an empty hash
+    counter.hashes.insert(Hash40(0));
+    let params_size = counter.count_struct(root, 1);
+    let struct_tables = counter
+        .struct_tables
+        .iter()
+        .map(|table| 8 * table.len())
+        .sum::<usize>();
+
+    let mut stats = counter.stats;
+    stats.unknown_hashes = unknown_hashes(root).len();
+    stats.hashes = counter.hashes.len();
+    stats.strings = counter.strings.len();
+    stats.string_table_size = counter.strings.iter().map(|str| str.len() + 1).sum();
+    stats.estimated_size =
+        HEADER_SIZE + 8 * stats.hashes + stats.string_table_size + struct_tables + params_size;
+    stats
+}
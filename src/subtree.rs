@@ -0,0 +1,18 @@
+use prc::{ParamKind, ParamStruct};
+
+use crate::path::{ParamPath, PathSegment};
+
+/// The param at the path as a file of its own. A struct becomes the root of the new file, and
+/// anything else is put in a root struct under its key, or the key of the list it's in
+pub fn extract(root: &ParamStruct, path: &ParamPath) -> Option<ParamStruct> {
+    match path.get(root)? {
+        ParamKind::Struct(str) => Some(str.clone()),
+        param => {
+            let key = path.0.iter().rev().find_map(|segment| match segment {
+                PathSegment::Key(hash) => Some(*hash),
+                PathSegment::Index(_) => None,
+            })?;
+            Some(ParamStruct(vec![(key, param.clone())]))
+        }
+    }
+}
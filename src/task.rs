@@ -0,0 +1,113 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+/// Work running on another thread, which the UI checks on instead of waiting for
+#[derive(Debug)]
+pub struct Task<T> {
+    handle: JoinHandle<T>,
+}
+
+impl<T: Send + 'static> Task<T> {
+    pub fn spawn<F: FnOnce() -> T + Send + 'static>(f: F) -> Self {
+        Self {
+            handle: thread::spawn(f),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Waits for the result. Returns None if the work panicked
+    pub fn join(self) -> Option<T> {
+        self.handle.join().ok()
+    }
+}
+
+/// How far a task has got through its work, shared with the thread doing it,
+/// which stops early once the task is cancelled
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    done: Arc<AtomicUsize>,
+    total: Arc<AtomicUsize>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Progress {
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Counts another part of the work as done. Returns false once the task is cancelled,
+    /// so the rest can be skipped
+    pub fn step(&self) -> bool {
+        self.done.fetch_add(1, Ordering::Relaxed);
+        !self.is_cancelled()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// The fraction of the work done, between 0 and 1, or None before the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total.load(Ordering::Relaxed) {
+            0 => None,
+            total => Some((self.done.load(Ordering::Relaxed) as f64 / total as f64).min(1.0)),
+        }
+    }
+}
+
+/// A task which reports its progress and can be stopped partway, like a search over a whole
+/// file. Dropping it stops the work, since nothing is left to take the result
+#[derive(Debug)]
+pub struct Cancellable<T> {
+    task: Option<Task<Option<T>>>,
+    progress: Progress,
+    started: Instant,
+}
+
+impl<T: Send + 'static> Cancellable<T> {
+    /// Runs the work, which gets the progress to report to and returns None if it was cancelled
+    pub fn spawn<F: FnOnce(&Progress) -> Option<T> + Send + 'static>(f: F) -> Self {
+        let progress = Progress::default();
+        let task = {
+            let progress = progress.clone();
+            Task::spawn(move || f(&progress))
+        };
+        Self {
+            task: Some(task),
+            progress,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.as_ref().is_none_or(Task::is_finished)
+    }
+
+    /// Waits for the result. Returns None if the work was cancelled or panicked
+    pub fn join(mut self) -> Option<T> {
+        self.task.take()?.join().flatten()
+    }
+}
+
+impl<T> Drop for Cancellable<T> {
+    fn drop(&mut self) {
+        self.progress.cancel();
+    }
+}
@@ -0,0 +1,144 @@
+use std::io::stdout;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tui_components::crossterm::cursor::MoveTo;
+use tui_components::crossterm::event::{
+    poll, read, DisableMouseCapture, EnableMouseCapture, Event as TermEvent, MouseEventKind,
+};
+use tui_components::crossterm::execute;
+use tui_components::crossterm::terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType};
+use tui_components::crossterm::ErrorKind;
+use tui_components::tui::backend::{Backend, CrosstermBackend};
+use tui_components::tui::Terminal;
+use tui_components::{App, AppResponse, Event, Wrapper};
+
+use crate::components::root::Root;
+
+/// Puts the terminal in raw mode with mouse capture, and back to how it was when dropped,
+/// whether the app exits, fails, or panics
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Result<Self, ErrorKind> {
+        enable_raw_mode()?;
+        // the guard exists from here on, so raw mode is undone even if capturing the mouse fails
+        let guard = RawMode;
+        execute!(stdout(), EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+fn restore_terminal() {
+    let _ = execute!(stdout(), DisableMouseCapture);
+    let _ = disable_raw_mode();
+}
+
+/// Restores the terminal before a panic's message is printed, so it's readable
+fn restore_on_panic() {
+    let print = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        print(info);
+    }));
+}
+
+/// Stops the editor the way Ctrl+Z does in a shell, leaving the terminal as it was before it
+/// started, and takes the terminal over again once the shell resumes it
+#[cfg(unix)]
+fn suspend<B: Backend>(t: &mut Terminal<B>) -> Result<(), ErrorKind> {
+    t.show_cursor()?;
+    execute!(stdout(), Clear(ClearType::All), MoveTo(0, 0))?;
+    restore_terminal();
+    // like a shell's job control, the whole process group is stopped, in case the editor was
+    // started by a script. It carries on from here once resumed
+    unsafe {
+        libc::kill(0, libc::SIGTSTP);
+    }
+    enable_raw_mode()?;
+    execute!(stdout(), EnableMouseCapture)?;
+    // whatever the shell printed meanwhile is drawn over
+    t.clear()
+}
+
+#[cfg(not(unix))]
+fn suspend<B: Backend>(_t: &mut Terminal<B>) -> Result<(), ErrorKind> {
+    Ok(())
+}
+
+/// Runs the app until it exits. Unlike `tui_components::run`, this also redraws
+/// while a background task is making progress, without waiting for input.
+/// Otherwise the app is only drawn again after an event, and sleeps until the next
+/// one or until something is due to change, like a notification going away.
+///
+/// An interrupt signal is handled like Ctrl+C, and the terminal is always restored,
+/// including while the editor is suspended.
+pub fn run(app: &mut Root, title: Option<String>) -> Result<(), ErrorKind> {
+    let mut should_refresh = true;
+
+    if let Some(title) = title {
+        tui_components::set_title(&title)?;
+    }
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = interrupted.clone();
+    if let Err(err) = ctrlc::set_handler(move || flag.store(true, Ordering::Relaxed)) {
+        log::warn!("failed to handle interrupt signals: {}", err);
+    }
+    restore_on_panic();
+    let _raw_mode = RawMode::enable()?;
+    let mut t = Terminal::new(CrosstermBackend::new(stdout()))?;
+    t.clear()?;
+
+    loop {
+        if app.update() {
+            should_refresh = true;
+        }
+
+        if should_refresh {
+            t.draw(|f| {
+                let size = f.size();
+                f.render_widget(Wrapper(app), size);
+            })?;
+            should_refresh = false;
+        }
+
+        let has_event = poll(app.next_update())?;
+        if interrupted.swap(false, Ordering::Relaxed) {
+            should_refresh = true;
+            if let AppResponse::Exit = app.interrupt() {
+                break;
+            }
+        }
+        if has_event {
+            let comp_event = match read()? {
+                TermEvent::Resize(..) => {
+                    should_refresh = true;
+                    continue;
+                }
+                // nothing follows the mouse without a button held
+                TermEvent::Mouse(m) if m.kind == MouseEventKind::Moved => continue,
+                TermEvent::Mouse(m) => Event::Mouse(m),
+                TermEvent::Key(k) if app.suspends(&k) => {
+                    suspend(&mut t)?;
+                    should_refresh = true;
+                    continue;
+                }
+                TermEvent::Key(k) => Event::Key(k),
+            };
+            should_refresh = true;
+            match app.handle_event(comp_event) {
+                AppResponse::Exit => break,
+                AppResponse::None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
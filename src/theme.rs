@@ -0,0 +1,159 @@
+use std::env::current_exe;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Deserializer};
+use tui_components::tui::buffer::Buffer;
+use tui_components::tui::layout::Rect;
+use tui_components::tui::style::Color;
+
+pub const THEME_FILE: &str = "Theme.toml";
+
+/// The colors used to draw the app. Any color missing from the theme file keeps its default.
+///
+/// Colors are written as names (`blue`, `dark_gray`, `light_red`, ...), as `#rrggbb`,
+/// or as a number from 0 to 255 for the terminal's indexed colors
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// The border and selected row of the focused table, and progress bars
+    #[serde(deserialize_with = "color")]
+    pub highlight: Color,
+    /// The text and borders of the parent columns
+    #[serde(deserialize_with = "color")]
+    pub inactive: Color,
+    /// The selected row's text in the parent columns
+    #[serde(deserialize_with = "color")]
+    pub inactive_highlight: Color,
+    /// Popup borders, rows which differ from the compared file, and text being edited
+    #[serde(deserialize_with = "color")]
+    pub accent: Color,
+    /// The file explorer's border and selected row
+    #[serde(deserialize_with = "color")]
+    pub explorer: Color,
+    /// Valid input and confirmed actions
+    #[serde(deserialize_with = "color")]
+    pub success: Color,
+    /// Input which is allowed, but might not be what was meant, like a new label
+    #[serde(deserialize_with = "color")]
+    pub warning: Color,
+    #[serde(deserialize_with = "color")]
+    pub error: Color,
+    /// Popup titles
+    #[serde(deserialize_with = "color")]
+    pub title: Color,
+    /// Hints and table headers
+    #[serde(deserialize_with = "color")]
+    pub hint: Color,
+    /// The background of the status bar
+    #[serde(deserialize_with = "color")]
+    pub status_bar: Color,
+    /// The type of lists and structs
+    #[serde(deserialize_with = "color")]
+    pub parent_type: Color,
+    /// The type of numbers and bools
+    #[serde(deserialize_with = "color")]
+    pub number_type: Color,
+    /// The type of hashes and strings
+    #[serde(deserialize_with = "color")]
+    pub text_type: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            highlight: Color::Blue,
+            inactive: Color::DarkGray,
+            inactive_highlight: Color::Gray,
+            accent: Color::Yellow,
+            explorer: Color::Green,
+            success: Color::Green,
+            warning: Color::LightYellow,
+            error: Color::Red,
+            title: Color::White,
+            hint: Color::Gray,
+            status_bar: Color::DarkGray,
+            parent_type: Color::Cyan,
+            number_type: Color::LightMagenta,
+            text_type: Color::LightGreen,
+        }
+    }
+}
+
+impl Theme {
+    /// Swaps the text colors a tui_components widget drew within the area for the theme's.
+    /// Each pair is the widget's color and the one to draw instead
+    pub fn recolor(&self, buffer: &mut Buffer, area: Rect, colors: &[(Color, Color)]) {
+        let area = area.intersection(buffer.area);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buffer.get_mut(x, y);
+                if let Some((_, color)) = colors.iter().find(|(from, _)| *from == cell.fg) {
+                    cell.fg = *color;
+                }
+            }
+        }
+    }
+}
+
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    parse_color(&text).ok_or_else(|| serde::de::Error::custom(format!("unknown color: {}", text)))
+}
+
+fn parse_color(text: &str) -> Option<Color> {
+    if let Some(hex) = text.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        return Some(Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8));
+    }
+    if let Ok(index) = text.parse::<u8>() {
+        return Some(Color::Indexed(index));
+    }
+    let name = text.to_lowercase().replace(['_', '-', ' '], "");
+    let color = match name.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// Finds the theme file, checking the current directory first, then the application directory
+fn theme_path() -> Option<PathBuf> {
+    let local = PathBuf::from(THEME_FILE);
+    if local.is_file() {
+        return Some(local);
+    }
+    let exe = current_exe().ok()?;
+    Some(exe.parent()?.join(THEME_FILE)).filter(|path| path.is_file())
+}
+
+/// Reads the theme file. Without one, the default theme is used
+pub fn load_theme() -> Result<Theme, String> {
+    let path = match theme_path() {
+        Some(path) => path,
+        None => return Ok(Theme::default()),
+    };
+    read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| toml::from_str(&text).map_err(|err| err.to_string()))
+        .map_err(|err| format!("Failed to load {}: {}", path.display(), err))
+}
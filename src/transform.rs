@@ -0,0 +1,106 @@
+use prc::ParamStruct;
+
+use crate::expr::evaluate;
+use crate::path::ParamPath;
+use crate::replace::Replacement;
+use crate::value::{number_value, param_number};
+
+/// An operation applied to many numbers at once
+#[derive(Debug, Clone)]
+pub enum Transform {
+    /// Arithmetic, where text starting with an operator applies to each value, like `*1.05`
+    Expr(String),
+    /// Keeps each value between a minimum and a maximum
+    Clamp(f64, f64),
+    /// Combines each value with a number, like `-= 3` subtracting 3 from it
+    Relative(Operator, f64),
+}
+
+/// How a relative transform combines each value with its number
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    /// The operator written as `+`, `-`, `*`, or `/`
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Operator::Add),
+            '-' => Some(Operator::Subtract),
+            '*' => Some(Operator::Multiply),
+            '/' => Some(Operator::Divide),
+            _ => None,
+        }
+    }
+
+    fn apply(self, lhs: f64, rhs: f64) -> f64 {
+        match self {
+            Operator::Add => lhs + rhs,
+            Operator::Subtract => lhs - rhs,
+            Operator::Multiply => lhs * rhs,
+            Operator::Divide => lhs / rhs,
+        }
+    }
+}
+
+impl Transform {
+    /// Reads an operation written as arithmetic, or as `clamp <min> <max>`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let args = match text.strip_prefix("clamp") {
+            Some(args) => args,
+            None if text.is_empty() => return Err(String::from("(incomplete)")),
+            None => return Ok(Transform::Expr(text.to_owned())),
+        };
+        let bounds = args
+            .split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| evaluate(arg, 0.0))
+            .collect::<Result<Vec<_>, _>>()?;
+        match bounds[..] {
+            [min, max] if min <= max => Ok(Transform::Clamp(min, max)),
+            [_, _] => Err(String::from("(the minimum is above the maximum)")),
+            _ => Err(String::from("(clamp needs a minimum and a maximum)")),
+        }
+    }
+
+    fn apply(&self, number: f64) -> Result<f64, String> {
+        match self {
+            Transform::Expr(text) => evaluate(text, number),
+            Transform::Clamp(min, max) => Ok(number.clamp(*min, *max)),
+            Transform::Relative(op, value) => match op.apply(number, *value) {
+                number if number.is_finite() => Ok(number),
+                _ => Err(String::from("(not a finite number)")),
+            },
+        }
+    }
+}
+
+/// Applies the operation to each number param at the paths, leaving out the ones it
+/// doesn't change. Params which aren't numbers are skipped
+pub fn transform_values(
+    root: &ParamStruct,
+    paths: &[ParamPath],
+    transform: &Transform,
+) -> Vec<Replacement> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let param = path.get(root)?;
+            let number = param_number(param)?;
+            let new = transform
+                .apply(number)
+                .and_then(|number| number_value(param, number));
+            Some(Replacement {
+                path: path.clone(),
+                old: param.clone(),
+                new,
+            })
+        })
+        .filter(|replacement| replacement.new.as_ref() != Ok(&replacement.old))
+        .collect()
+}
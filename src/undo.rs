@@ -0,0 +1,152 @@
+use prc::{ParamKind, ParamStruct};
+
+use crate::path::ParamPath;
+
+/// The most edits which can be undone
+const MAX_EDITS: usize = 100;
+
+/// A param as it was on one side of an edit. An empty path is the whole file
+#[derive(Debug, Clone)]
+pub struct Change {
+    path: ParamPath,
+    param: ParamKind,
+}
+
+impl Change {
+    /// Keeps a copy of the param at the path, before it's edited
+    pub fn of(root: &ParamStruct, path: ParamPath) -> Option<Self> {
+        let param = if path.0.is_empty() {
+            ParamKind::Struct(root.clone())
+        } else {
+            path.get(root)?.clone()
+        };
+        Some(Self { path, param })
+    }
+
+    /// Swaps the kept param with the one in the file, so the change can be reversed again
+    fn swap(&mut self, root: &mut ParamStruct) {
+        match (&mut self.param, self.path.0.is_empty()) {
+            (ParamKind::Struct(str), true) => std::mem::swap(root, str),
+            (param, false) => {
+                if let Some(current) = self.path.get_mut(root) {
+                    std::mem::swap(current, param);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Something done to the file which can be undone as a whole
+#[derive(Debug, Clone)]
+pub struct Edit {
+    pub description: String,
+    changes: Vec<Change>,
+}
+
+/// An edit as the history lists it, with the first param it changed
+#[derive(Debug, Clone)]
+pub struct EditEntry {
+    pub description: String,
+    pub path: ParamPath,
+    /// How many params the edit changed
+    pub count: usize,
+    /// The param before and after the edit, if it changed only one which isn't the whole file
+    pub values: Option<(ParamKind, ParamKind)>,
+}
+
+impl Edit {
+    fn swap(&mut self, root: &mut ParamStruct) {
+        // later changes may be inside earlier ones, so they're reversed first
+        for change in self.changes.iter_mut().rev() {
+            change.swap(root);
+        }
+        self.changes.reverse();
+    }
+
+    /// The param this edit kept for the path, if it changed exactly that one
+    fn kept(&self, path: &ParamPath) -> Option<ParamKind> {
+        self.changes
+            .iter()
+            .find(|change| change.path == *path)
+            .map(|change| change.param.clone())
+    }
+
+    fn entry(&self, change: &Change, values: Option<(ParamKind, ParamKind)>) -> EditEntry {
+        let single = self.changes.len() == 1 && !change.path.0.is_empty();
+        EditEntry {
+            description: self.description.clone(),
+            path: change.path.clone(),
+            count: self.changes.len(),
+            values: values.filter(|_| single),
+        }
+    }
+}
+
+/// The edits made to a file, keeping only the params each one changed
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl UndoHistory {
+    /// Remembers an edit about to be made. Anything undone before can no longer be redone
+    pub fn push(&mut self, description: String, changes: Vec<Change>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.undo.push(Edit {
+            description,
+            changes,
+        });
+        if self.undo.len() > MAX_EDITS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Lists every edit kept, oldest first, followed by the edits which can be redone in the
+    /// order they would be. Also returns how many of them are made
+    pub fn entries(&self, root: &ParamStruct) -> (Vec<EditEntry>, usize) {
+        // the made edits keep their params from before, and the undone ones from after, so the
+        // other side is what the next edit of the same param kept, or what's in the file now
+        let current = |path: &ParamPath| path.get(root).cloned();
+        let made = self.undo.iter().enumerate().map(|(index, edit)| {
+            let change = &edit.changes[0];
+            let after = self.undo[index + 1..]
+                .iter()
+                .find_map(|later| later.kept(&change.path))
+                .or_else(|| current(&change.path));
+            edit.entry(change, Some(change.param.clone()).zip(after))
+        });
+        let undone = self.redo.iter().enumerate().rev().map(|(index, edit)| {
+            // undoing reversed the order of the changes
+            let change = &edit.changes[edit.changes.len() - 1];
+            let before = self.redo[index + 1..]
+                .iter()
+                .find_map(|earlier| earlier.kept(&change.path))
+                .or_else(|| current(&change.path));
+            edit.entry(change, before.zip(Some(change.param.clone())))
+        });
+        (made.chain(undone).collect(), self.undo.len())
+    }
+
+    /// Reverses the latest edit, returning its description
+    pub fn undo(&mut self, root: &mut ParamStruct) -> Option<String> {
+        let mut edit = self.undo.pop()?;
+        edit.swap(root);
+        let description = edit.description.clone();
+        self.redo.push(edit);
+        Some(description)
+    }
+
+    /// Makes the latest undone edit again, returning its description
+    pub fn redo(&mut self, root: &mut ParamStruct) -> Option<String> {
+        let mut edit = self.redo.pop()?;
+        edit.swap(root);
+        let description = edit.description.clone();
+        self.undo.push(edit);
+        Some(description)
+    }
+}
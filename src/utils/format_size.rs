@@ -0,0 +1,15 @@
+/// The size in the largest unit which keeps it at least 1, like `12.3 KiB`, padded to line up
+/// in a column
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{:>6} B  ", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:>6.1} {}", size, UNITS[unit])
+}
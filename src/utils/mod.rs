@@ -1 +1,7 @@
+pub mod format_size;
 pub mod modulo;
+pub mod mouse;
+pub mod paste;
+pub mod shell;
+pub mod type_ahead;
+pub mod vim;
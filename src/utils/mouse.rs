@@ -0,0 +1,31 @@
+use std::time::{Duration, Instant};
+
+/// How soon a second click has to follow the first to count as a double click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// How many rows one step of the scroll wheel moves the selection
+pub const SCROLL_ROWS: usize = 3;
+
+/// Tells single clicks from double clicks, since the terminal only reports each press
+#[derive(Debug, Default, Clone)]
+pub struct ClickTracker {
+    last: Option<(Instant, u16, u16)>,
+}
+
+impl ClickTracker {
+    /// Records a click on the given cell. Returns whether it completes a double click
+    pub fn click(&mut self, column: u16, row: u16) -> bool {
+        let double = matches!(
+            self.last,
+            Some((time, last_column, last_row))
+                if last_column == column && last_row == row && time.elapsed() < DOUBLE_CLICK_TIME
+        );
+        // a third click starts over, instead of counting as another double click
+        self.last = if double {
+            None
+        } else {
+            Some((Instant::now(), column, row))
+        };
+        double
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt::Debug;
+
+use arboard::Clipboard;
+use tui_components::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tui_components::Event;
+
+/// Whether the key pastes from the clipboard: Ctrl+V, or Shift+Insert
+pub fn is_paste(key: &KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('v') => key.modifiers.contains(KeyModifiers::CONTROL),
+        KeyCode::Insert => key.modifiers.contains(KeyModifiers::SHIFT),
+        _ => false,
+    }
+}
+
+/// The first line of text in the clipboard, since every input holds a single line
+pub fn clipboard_text() -> Option<String> {
+    let text = Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|err| log::warn!("failed to read the clipboard: {}", err))
+        .ok()?;
+    text.lines().next().map(str::to_owned)
+}
+
+/// Puts text in the clipboard. On Linux the text is only available while the program which
+/// copied it holds on to the clipboard, so this is kept for as long as the app runs
+#[derive(Default)]
+pub struct ClipboardWriter(Option<Clipboard>);
+
+impl Debug for ClipboardWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ClipboardWriter").finish()
+    }
+}
+
+impl ClipboardWriter {
+    /// Replaces what's in the clipboard with the text
+    pub fn copy(&mut self, text: &str) -> Result<(), String> {
+        let clipboard = match &mut self.0 {
+            Some(clipboard) => clipboard,
+            None => self
+                .0
+                .insert(Clipboard::new().map_err(|err| err.to_string())?),
+        };
+        clipboard.set_text(text).map_err(|err| err.to_string())
+    }
+}
+
+/// The text as if each character had been typed, for inputs which only take one key at a time
+pub fn paste_events(text: &str) -> impl Iterator<Item = Event> + '_ {
+    text.chars()
+        .filter(|c| !c.is_control())
+        .map(|c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)))
+}
@@ -0,0 +1,40 @@
+use std::time::{Duration, Instant};
+
+/// How long after the last letter, or the key starting the prefix, letters stop adding to it
+const RESET_TIME: Duration = Duration::from_millis(1000);
+
+/// Collects letters typed in quick succession into a prefix to jump to, like file managers do
+#[derive(Debug, Default, Clone)]
+pub struct TypeAhead {
+    prefix: String,
+    last: Option<Instant>,
+}
+
+impl TypeAhead {
+    /// The key which starts typing a name. Typing straight away would leave names starting with
+    /// a letter bound to a command, like `j` or `s`, out of reach
+    pub const START: char = '\'';
+
+    /// Starts typing a new prefix, so the letters after it aren't taken as commands
+    pub fn start(&mut self) {
+        self.prefix.clear();
+        self.last = Some(Instant::now());
+    }
+
+    /// Whether a prefix is still being typed, so letters add to it instead of running commands
+    pub fn is_typing(&self) -> bool {
+        self.last.is_some_and(|last| last.elapsed() < RESET_TIME)
+    }
+
+    /// Adds the letter to the prefix. Returns the prefix in lowercase
+    pub fn push(&mut self, c: char) -> &str {
+        self.prefix.extend(c.to_lowercase());
+        self.last = Some(Instant::now());
+        &self.prefix
+    }
+
+    /// Whether the character can be part of a name, as opposed to a command like `/`
+    pub fn accepts(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+}
@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use tui_components::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::components::tree_view::ViewMode;
+use crate::settings::HashDisplay;
+
+/// Whether the key is pressed without Ctrl or Alt. Letters held with those are left for the commands
+pub fn is_plain(key: &KeyEvent) -> bool {
+    !key.modifiers
+        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
+
+/// The key pressed, where vim's h/j/k/l are read as the arrow keys they stand for
+pub fn vim_code(key: &KeyEvent) -> KeyCode {
+    match key.code {
+        KeyCode::Char('h') if is_plain(key) => KeyCode::Left,
+        KeyCode::Char('j') if is_plain(key) => KeyCode::Down,
+        KeyCode::Char('k') if is_plain(key) => KeyCode::Up,
+        KeyCode::Char('l') if is_plain(key) => KeyCode::Right,
+        code => code,
+    }
+}
+
+/// A command typed after `:`, like in vim
+#[derive(Debug, Clone, PartialEq)]
+pub enum VimCommand {
+    /// `:w` saves the file, or `:w <path>` saves it as another file
+    Write(Option<PathBuf>),
+    /// `:q` quits, and `:q!` quits without asking about unsaved changes
+    Quit {
+        force: bool,
+    },
+    /// `:wq` or `:x` saves the file and quits
+    WriteQuit,
+    /// `:e <path>` opens another file, and `:e` alone opens the file again as it was saved.
+    /// `:e!` doesn't ask about unsaved changes
+    Edit {
+        path: Option<PathBuf>,
+        force: bool,
+    },
+    Set(SetOption),
+    /// `:s/find/replace/` replaces values below the last column's params, and `:%s` in the whole
+    /// file. The text to find is a regex
+    Substitute {
+        find: String,
+        replace: String,
+        whole_file: bool,
+    },
+}
+
+/// A display option changed with `:set`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetOption {
+    /// `hex`, `nohex`, or `hex!`
+    HexColumn(Switch),
+    /// `number`, `nonumber`, or `number!`, or `nu` for short like in vim
+    RowNumbers(Switch),
+    /// `details`, `nodetails`, or `details!`
+    Details(Switch),
+    /// `scientific`, `noscientific`, or `scientific!`
+    Scientific(Switch),
+    /// `layout=tree` or `layout=columns`
+    Layout(ViewMode),
+    /// `hashes=label`, `hashes=hex`, or `hashes=both`
+    Hashes(HashDisplay),
+    /// `decimals=<n>`, or `decimals=exact` for the fewest which read back as the same float
+    Decimals(Option<usize>),
+}
+
+/// How `:set` changes an option which is on or off
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Switch {
+    On,
+    Off,
+    Toggle,
+}
+
+impl Switch {
+    pub fn apply(self, value: bool) -> bool {
+        match self {
+            Switch::On => true,
+            Switch::Off => false,
+            Switch::Toggle => !value,
+        }
+    }
+}
+
+/// The options `:set` knows, for the error when it's given another
+const SET_OPTIONS: &str =
+    "hex, number, details, scientific, layout=tree|columns, hashes=label|hex|both, decimals=<n>|exact";
+
+impl FromStr for VimCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        let text = text.strip_prefix(':').unwrap_or(text).trim_start();
+        if let Some(rest) = text.strip_prefix("%s") {
+            return parse_substitute(rest, true);
+        }
+        // `:s/a/b/`, but not `:set`
+        if let Some(rest) = text
+            .strip_prefix('s')
+            .filter(|rest| rest.starts_with(|c: char| !c.is_alphanumeric() && !c.is_whitespace()))
+        {
+            return parse_substitute(rest, false);
+        }
+        let (name, argument) = match text.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, Some(argument.trim()).filter(|arg| !arg.is_empty())),
+            None => (text, None),
+        };
+        let path = argument.map(PathBuf::from);
+        match (name, argument) {
+            ("w" | "write", _) => Ok(VimCommand::Write(path)),
+            ("q" | "quit", None) => Ok(VimCommand::Quit { force: false }),
+            ("q!" | "quit!", None) => Ok(VimCommand::Quit { force: true }),
+            ("wq" | "x", None) => Ok(VimCommand::WriteQuit),
+            ("e" | "edit", _) => Ok(VimCommand::Edit { path, force: false }),
+            ("e!" | "edit!", _) => Ok(VimCommand::Edit { path, force: true }),
+            ("set" | "se", Some(option)) => parse_option(option).map(VimCommand::Set),
+            ("set" | "se", None) => Err(format!("Set one of: {}", SET_OPTIONS)),
+            ("", None) => Err("Type a command, like :w or :q".into()),
+            (_, Some(_)) if ["q", "q!", "wq", "x"].contains(&name) => {
+                Err(format!(":{} doesn't take a file", name))
+            }
+            _ => Err(format!("Not a command: {}", name)),
+        }
+    }
+}
+
+fn parse_option(option: &str) -> Result<SetOption, String> {
+    let unknown = || format!("Unknown option: {}. Set one of: {}", option, SET_OPTIONS);
+    match option.split_once('=') {
+        Some(("layout", "tree")) => Ok(SetOption::Layout(ViewMode::Tree)),
+        Some(("layout", "columns")) => Ok(SetOption::Layout(ViewMode::Columns)),
+        Some(("hashes", "label")) => Ok(SetOption::Hashes(HashDisplay::Label)),
+        Some(("hashes", "hex")) => Ok(SetOption::Hashes(HashDisplay::Hex)),
+        Some(("hashes", "both")) => Ok(SetOption::Hashes(HashDisplay::Both)),
+        Some(("decimals", "exact")) => Ok(SetOption::Decimals(None)),
+        Some(("decimals", count)) => count
+            .parse()
+            .map(|count| SetOption::Decimals(Some(count)))
+            .map_err(|_| format!("Not a number of decimals: {}", count)),
+        Some(_) => Err(unknown()),
+        None => match switch(option) {
+            ("hex", switch) => Ok(SetOption::HexColumn(switch)),
+            ("number" | "nu", switch) => Ok(SetOption::RowNumbers(switch)),
+            ("details", switch) => Ok(SetOption::Details(switch)),
+            ("scientific", switch) => Ok(SetOption::Scientific(switch)),
+            _ => Err(unknown()),
+        },
+    }
+}
+
+/// Reads an option which is on or off, like `hex`, `nohex`, `invhex`, or `hex!`
+fn switch(option: &str) -> (&str, Switch) {
+    if let Some(name) = option.strip_suffix('!') {
+        (name, Switch::Toggle)
+    } else if let Some(name) = option.strip_prefix("inv") {
+        (name, Switch::Toggle)
+    } else if let Some(name) = option.strip_prefix("no") {
+        (name, Switch::Off)
+    } else {
+        (option, Switch::On)
+    }
+}
+
+/// Reads the `/find/replace/` after `:s`, where any punctuation can stand in for the slashes,
+/// and one is written in the text with a backslash, like `\/`
+fn parse_substitute(text: &str, whole_file: bool) -> Result<VimCommand, String> {
+    let usage = || String::from("Write it like :%s/find/replace/");
+    let mut chars = text.chars();
+    let separator = chars.next().ok_or_else(usage)?;
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+    for c in chars {
+        let part = parts.last_mut().unwrap();
+        match c {
+            _ if escaped => {
+                if c != separator {
+                    part.push('\\');
+                }
+                part.push(c);
+                escaped = false;
+            }
+            '\\' => escaped = true,
+            _ if c == separator => parts.push(String::new()),
+            _ => part.push(c),
+        }
+    }
+    if escaped {
+        parts.last_mut().unwrap().push('\\');
+    }
+    // the last separator can be left out, and flags like `g` after it change nothing,
+    // since every match in a value is replaced
+    let (find, replace) = match parts.as_slice() {
+        [find, replace] | [find, replace, _] => (find, replace),
+        _ => return Err(usage()),
+    };
+    if find.is_empty() {
+        return Err(usage());
+    }
+    Ok(VimCommand::Substitute {
+        find: find.clone(),
+        replace: vim_groups(replace),
+        whole_file,
+    })
+}
+
+/// Writes vim's groups in a replacement, like `\1`, the way the regex crate reads them
+fn vim_groups(replace: &str) -> String {
+    let mut text = String::new();
+    let mut chars = replace.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(digit)) if digit.is_ascii_digit() => {
+                text.push_str(&format!("${{{}}}", digit));
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                text.push('\\');
+                chars.next();
+            }
+            _ => text.push(c),
+        }
+    }
+    text
+}
@@ -0,0 +1,226 @@
+use std::convert::TryFrom;
+
+use prc::hash40::{hash40, Hash40};
+use prc::{ParamKind, ParamList, ParamStruct};
+
+/// Reads a hash written as either hex (`0x...`) or a label.
+/// Labels are looked up in the label map first, in case the label is a custom one
+pub fn parse_hash(text: &str) -> Result<Hash40, String> {
+    if text.starts_with("0x") {
+        Hash40::from_hex_str(text).map_err(|_| format!("invalid hash: {}", text))
+    } else {
+        let label_arc = Hash40::label_map();
+        let labels = match label_arc.lock() {
+            Ok(labels) => labels,
+            Err(err) => err.into_inner(),
+        };
+        Ok(labels.hash_of(text).unwrap_or_else(|| hash40(text)))
+    }
+}
+
+/// Writes a param's value as text which `parse_value` can read back.
+/// Lists and structs are written as their number of children
+pub fn format_value(param: &ParamKind) -> String {
+    match param {
+        ParamKind::Bool(v) => v.to_string(),
+        ParamKind::I8(v) => v.to_string(),
+        ParamKind::U8(v) => v.to_string(),
+        ParamKind::I16(v) => v.to_string(),
+        ParamKind::U16(v) => v.to_string(),
+        ParamKind::I32(v) => v.to_string(),
+        ParamKind::U32(v) => v.to_string(),
+        ParamKind::Float(v) => v.to_string(),
+        ParamKind::Hash(v) => v.to_string(),
+        ParamKind::Str(v) => v.to_owned(),
+        ParamKind::List(v) => format!("({} children)", v.0.len()),
+        ParamKind::Struct(v) => format!("({} children)", v.0.len()),
+    }
+}
+
+/// The name of a param's type, as it's shown next to the value
+pub fn param_type(param: &ParamKind) -> &'static str {
+    match param {
+        ParamKind::Bool(_) => "bool",
+        ParamKind::I8(_) => "i8",
+        ParamKind::U8(_) => "u8",
+        ParamKind::I16(_) => "i16",
+        ParamKind::U16(_) => "u16",
+        ParamKind::I32(_) => "i32",
+        ParamKind::U32(_) => "u32",
+        ParamKind::Float(_) => "f32",
+        ParamKind::Hash(_) => "hash",
+        ParamKind::Str(_) => "string",
+        ParamKind::List(_) => "list",
+        ParamKind::Struct(_) => "struct",
+    }
+}
+
+/// Reads a new value for a param, keeping the param's type.
+/// Integers can also be written in hex (`0x...`), optionally negated
+pub fn parse_value(param: &ParamKind, text: &str) -> Result<ParamKind, String> {
+    fn parse<T: std::str::FromStr>(text: &str, ty: &str) -> Result<T, String> {
+        text.parse()
+            .map_err(|_| format!("invalid value for {}: {}", ty, text))
+    }
+
+    fn int<T: std::str::FromStr + TryFrom<i64>>(text: &str, ty: &str) -> Result<T, String> {
+        match parse_hex(text) {
+            Some(number) => number
+                .and_then(|number| T::try_from(number).ok())
+                .ok_or_else(|| format!("invalid value for {}: {}", ty, text)),
+            None => parse(text, ty),
+        }
+    }
+
+    match param {
+        ParamKind::Bool(_) => parse::<bool>(text, "bool").map(ParamKind::from),
+        ParamKind::I8(_) => int::<i8>(text, "i8").map(ParamKind::from),
+        ParamKind::U8(_) => int::<u8>(text, "u8").map(ParamKind::from),
+        ParamKind::I16(_) => int::<i16>(text, "i16").map(ParamKind::from),
+        ParamKind::U16(_) => int::<u16>(text, "u16").map(ParamKind::from),
+        ParamKind::I32(_) => int::<i32>(text, "i32").map(ParamKind::from),
+        ParamKind::U32(_) => int::<u32>(text, "u32").map(ParamKind::from),
+        ParamKind::Float(_) => parse::<f32>(text, "f32").map(ParamKind::from),
+        ParamKind::Hash(_) => parse_hash(text).map(ParamKind::from),
+        ParamKind::Str(_) => Ok(ParamKind::from(text.to_owned())),
+        ParamKind::List(_) | ParamKind::Struct(_) => {
+            Err(String::from("lists and structs can't be set to a value"))
+        }
+    }
+}
+
+/// Reads an integer written in hex, like `0x1f` or `-0x1f`. Returns None if the text isn't hex,
+/// or Some(None) if it is but the digits are invalid
+pub fn parse_hex(text: &str) -> Option<Option<i64>> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let hex = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))?;
+    // from_str_radix would also take a sign after the prefix
+    let number = Some(hex)
+        .filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .and_then(|hex| i64::from_str_radix(hex, 16).ok());
+    Some(number.map(|number| if negative { -number } else { number }))
+}
+
+/// A number param's value, for doing arithmetic with. Other params have none
+pub fn param_number(param: &ParamKind) -> Option<f64> {
+    match param {
+        ParamKind::I8(v) => Some(*v as f64),
+        ParamKind::U8(v) => Some(*v as f64),
+        ParamKind::I16(v) => Some(*v as f64),
+        ParamKind::U16(v) => Some(*v as f64),
+        ParamKind::I32(v) => Some(*v as f64),
+        ParamKind::U32(v) => Some(*v as f64),
+        ParamKind::Float(v) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+/// Makes a new value for a number param from the result of some arithmetic, keeping the param's
+/// type. Integers are rounded to the nearest, and fail if that doesn't fit the type
+pub fn number_value(param: &ParamKind, number: f64) -> Result<ParamKind, String> {
+    fn int<T: TryFrom<i64>>(number: f64, ty: &str) -> Result<T, String> {
+        T::try_from(number.round() as i64).map_err(|_| format!("(out of range for {})", ty))
+    }
+
+    match param {
+        ParamKind::I8(_) => int::<i8>(number, "i8").map(ParamKind::from),
+        ParamKind::U8(_) => int::<u8>(number, "u8").map(ParamKind::from),
+        ParamKind::I16(_) => int::<i16>(number, "i16").map(ParamKind::from),
+        ParamKind::U16(_) => int::<u16>(number, "u16").map(ParamKind::from),
+        ParamKind::I32(_) => int::<i32>(number, "i32").map(ParamKind::from),
+        ParamKind::U32(_) => int::<u32>(number, "u32").map(ParamKind::from),
+        ParamKind::Float(_) if (number as f32).is_finite() => Ok(ParamKind::from(number as f32)),
+        ParamKind::Float(_) => Err(String::from("(out of range for f32)")),
+        _ => Err(String::from("(not a number)")),
+    }
+}
+
+/// Adds a number of steps to a float, rounded to the decimals of the float or the step,
+/// whichever has more, so stepping back and forth doesn't pick up rounding errors
+pub fn step_float(value: f32, step: f32, steps: f32) -> f32 {
+    if !value.is_finite() {
+        return value;
+    }
+    let decimals = |float: f32| {
+        float
+            .to_string()
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len())
+    };
+    let scale = 10f64.powi(decimals(value).max(decimals(step)) as i32);
+    let sum = value as f64 + step as f64 * steps as f64;
+    ((sum * scale).round() / scale) as f32
+}
+
+/// A new param shaped like the template, with every value zeroed: numbers are 0, bools false,
+/// and strings and hashes empty. Struct keys are kept, while lists are left without children
+pub fn blank_value(template: &ParamKind) -> ParamKind {
+    match template {
+        ParamKind::Bool(_) => ParamKind::Bool(false),
+        ParamKind::I8(_) => ParamKind::I8(0),
+        ParamKind::U8(_) => ParamKind::U8(0),
+        ParamKind::I16(_) => ParamKind::I16(0),
+        ParamKind::U16(_) => ParamKind::U16(0),
+        ParamKind::I32(_) => ParamKind::I32(0),
+        ParamKind::U32(_) => ParamKind::U32(0),
+        ParamKind::Float(_) => ParamKind::Float(0.0),
+        ParamKind::Hash(_) => ParamKind::Hash(hash40("")),
+        ParamKind::Str(_) => ParamKind::Str(String::new()),
+        ParamKind::List(_) => ParamKind::List(ParamList(vec![])),
+        ParamKind::Struct(str) => ParamKind::Struct(ParamStruct(
+            str.0
+                .iter()
+                .map(|(key, child)| (*key, blank_value(child)))
+                .collect(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(parse_hex("0x1f"), Some(Some(31)));
+        assert_eq!(parse_hex("0X1F"), Some(Some(31)));
+        assert_eq!(parse_hex("-0x10"), Some(Some(-16)));
+        assert_eq!(parse_hex("0x0"), Some(Some(0)));
+    }
+
+    #[test]
+    fn hex_with_invalid_digits() {
+        assert_eq!(parse_hex("0x"), Some(None));
+        assert_eq!(parse_hex("0xg"), Some(None));
+        // from_str_radix alone would take the sign
+        assert_eq!(parse_hex("0x-1"), Some(None));
+        assert_eq!(parse_hex("0x+1"), Some(None));
+        assert_eq!(parse_hex("0x10000000000000000"), Some(None));
+    }
+
+    #[test]
+    fn not_hex() {
+        assert_eq!(parse_hex("31"), None);
+        assert_eq!(parse_hex("-31"), None);
+        assert_eq!(parse_hex("x1f"), None);
+        assert_eq!(parse_hex(""), None);
+    }
+
+    #[test]
+    fn ints_read_hex() {
+        assert_eq!(
+            parse_value(&ParamKind::U8(0), "0xff"),
+            Ok(ParamKind::U8(255))
+        );
+        assert_eq!(
+            parse_value(&ParamKind::I8(0), "-0x80"),
+            Ok(ParamKind::I8(-128))
+        );
+        assert!(parse_value(&ParamKind::U8(0), "0x100").is_err());
+    }
+}